@@ -1,25 +1,144 @@
 //! Provides functionality for analyzing CHD (Compressed Hunks of Data) files.
 //!
 //! This module focuses on decompressing and extracting relevant header data from CHD files.
-//! It exposes a function to decompress a portion of a CHD file for header analysis.
+//! It exposes a function to decompress a portion of a CHD file for header analysis. For CD images
+//! the track metadata is used to start decompressing at the first data track, rather than at an
+//! audio track that may come before it.
 
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
 
 use chd::Chd;
-use log::debug;
+use chd::metadata::{KnownMetadata, MetadataTag};
+use log::{debug, trace};
 
 use crate::error::RomAnalyzerError;
 
 // We only need the first few KB for header analysis for PSX and SegaCD.
 const MAX_HEADER_SIZE: usize = 0x20000; // 128KB
 
+// A hunk is decompressed into a buffer of its full size, so larger hunks are rejected. chdman
+// creates hunks of at most 1MB.
+const MAX_HUNK_SIZE: u32 = 0x100_0000; // 16MB
+
+// The chd crate reads the largest (version 5) header size up front, so shorter files can't be
+// opened.
+const MIN_CHD_SIZE: u64 = 124;
+
+// CD frames are stored as 2352 bytes of sector data followed by 96 bytes of subcode data, and
+// each track is padded to a multiple of 4 frames.
+const CD_FRAME_SIZE: u64 = 2352 + 96;
+const CD_TRACK_PADDING: u64 = 4;
+
+/// A single track of a CD image, as described by a CHD `CHTR` or `CHT2` metadata entry.
+#[derive(Debug, PartialEq, Clone)]
+struct CdTrack {
+    /// The track number, starting at 1.
+    number: u32,
+    /// The track type (e.g. "MODE1_RAW", "MODE2_RAW" or "AUDIO").
+    track_type: String,
+    /// The number of frames in the track, including any stored pregap.
+    frames: u64,
+    /// The number of pregap frames stored in the track data before the track itself.
+    stored_pregap: u64,
+}
+
+impl CdTrack {
+    fn is_audio(&self) -> bool {
+        self.track_type == "AUDIO"
+    }
+}
+
+/// Parses the text of a CHD `CHTR` or `CHT2` track metadata entry.
+///
+/// The entry is a list of space separated `KEY:VALUE` pairs, such as
+/// `TRACK:1 TYPE:MODE2_RAW SUBTYPE:NONE FRAMES:1000 PREGAP:150 PGTYPE:VMODE2_RAW ...`. A pregap
+/// is only stored in the track data when its `PGTYPE` starts with `V`.
+///
+/// # Returns
+///
+/// The parsed [`CdTrack`], or `None` if the entry is missing the track number, type or frames.
+fn parse_track_metadata(value: &[u8]) -> Option<CdTrack> {
+    let text = String::from_utf8_lossy(value);
+    let field = |key: &str| {
+        text.trim_end_matches('\0')
+            .split_whitespace()
+            .find_map(|pair| pair.strip_prefix(key)?.strip_prefix(':'))
+    };
+
+    let pregap = field("PREGAP").and_then(|v| v.parse().ok()).unwrap_or(0);
+    let pregap_stored = field("PGTYPE").is_some_and(|v| v.starts_with('V'));
+    Some(CdTrack {
+        number: field("TRACK")?.parse().ok()?,
+        track_type: field("TYPE")?.to_string(),
+        frames: field("FRAMES")?.parse().ok()?,
+        stored_pregap: if pregap_stored { pregap } else { 0 },
+    })
+}
+
+/// Finds the byte offset of the first data track within a CD image's hunk data.
+///
+/// Tracks are laid out in order, each padded to a multiple of [`CD_TRACK_PADDING`] frames. Any
+/// stored pregap of the data track is skipped.
+///
+/// # Returns
+///
+/// The offset of the first data track, or `None` if every track is an audio track or the track
+/// sizes overflow.
+fn first_data_track_offset(tracks: &[CdTrack]) -> Option<u64> {
+    let mut frame_offset: u64 = 0;
+    for track in tracks {
+        if !track.is_audio() {
+            return frame_offset
+                .checked_add(track.stored_pregap)?
+                .checked_mul(CD_FRAME_SIZE);
+        }
+        let padded_frames = track
+            .frames
+            .div_ceil(CD_TRACK_PADDING)
+            .checked_mul(CD_TRACK_PADDING)?;
+        frame_offset = frame_offset.checked_add(padded_frames)?;
+    }
+    None
+}
+
+/// Checks that the hunk geometry of a CHD header is plausible before any hunk is read.
+///
+/// The hunk size has to be non-zero and at most [`MAX_HUNK_SIZE`], and the hunks have to cover the
+/// logical size of the CHD with less than one hunk to spare. This rejects corrupted headers
+/// declaring a huge hunk count, as well as version 5 headers whose hunk count was truncated.
+///
+/// # Returns
+///
+/// `Ok(())` if the geometry is plausible, or a [`RomAnalyzerError::ArchiveError`] describing it.
+fn validate_hunk_geometry(
+    hunk_count: u32,
+    hunk_size: u32,
+    logical_bytes: u64,
+) -> Result<(), RomAnalyzerError> {
+    if hunk_size == 0 || hunk_size > MAX_HUNK_SIZE {
+        return Err(RomAnalyzerError::ArchiveError(format!(
+            "Implausible CHD hunk size: {} bytes (expected 1 to {}).",
+            hunk_size, MAX_HUNK_SIZE
+        )));
+    }
+    let expected_count = logical_bytes.div_ceil(hunk_size as u64);
+    if hunk_count as u64 != expected_count {
+        return Err(RomAnalyzerError::ArchiveError(format!(
+            "Implausible CHD hunk count: {} hunks of {} bytes for a logical size of {} bytes.",
+            hunk_count, hunk_size, logical_bytes
+        )));
+    }
+    Ok(())
+}
+
 /// Analyzes a CHD (Compressed Hunks of Data) file, decompressing a portion of it.
 ///
 /// This function opens a CHD file, reads its header to determine hunk size and count,
 /// and then decompresses a maximum of `MAX_HEADER_SIZE` bytes from the beginning
-/// of the CHD data. This decompressed data is typically sufficient for extracting
+/// of the CHD data. For CD images with track metadata, decompression instead starts at the
+/// first data track. This decompressed data is typically sufficient for extracting
 /// console-specific headers without decompressing the entire (potentially very large)
 /// CHD file.
 ///
@@ -38,14 +157,26 @@ const MAX_HEADER_SIZE: usize = 0x20000; // 128KB
 /// This function can return an error if:
 /// - The file cannot be opened.
 /// - The CHD format is invalid or corrupted.
+/// - The file is empty or truncated, or the CHD has no hunks.
+/// - The header declares an implausible hunk size or count.
 /// - There are issues during hunk decompression.
 pub fn analyze_chd_file(filepath: &Path) -> Result<Vec<u8>, RomAnalyzerError> {
     let file = File::open(filepath)?;
+    if file.metadata()?.len() < MIN_CHD_SIZE {
+        return Err(empty_or_truncated_error(
+            filepath,
+            "it is too small to hold a CHD header",
+        ));
+    }
     let mut reader = BufReader::new(file);
     let mut chd = Chd::open(&mut reader, None).map_err(RomAnalyzerError::ChdError)?;
 
     let hunk_count = chd.header().hunk_count();
     let hunk_size = chd.header().hunk_size();
+    if hunk_count == 0 {
+        return Err(empty_or_truncated_error(filepath, "it has no hunks"));
+    }
+    validate_hunk_geometry(hunk_count, hunk_size, chd.header().logical_bytes())?;
 
     debug!(
         "[+] Analyzing CHD file: {}",
@@ -55,27 +186,44 @@ pub fn analyze_chd_file(filepath: &Path) -> Result<Vec<u8>, RomAnalyzerError> {
             .to_string_lossy()
     );
 
-    let mut decompressed_data = Vec::new();
-    decompressed_data.reserve_exact(
-        ((hunk_count as u64) * (hunk_size as u64)).min(MAX_HEADER_SIZE as u64) as usize,
-    );
-
-    let mut out_buf = chd.get_hunksized_buffer();
-    let mut temp_buf = Vec::new();
-
-    for hunk_num in 0..hunk_count {
-        if decompressed_data.len() >= MAX_HEADER_SIZE {
-            break;
-        }
-
-        let mut hunk = chd.hunk(hunk_num).map_err(RomAnalyzerError::ChdError)?;
-        hunk.read_hunk_in(&mut temp_buf, &mut out_buf)
+    let track_refs: Vec<_> = chd
+        .metadata_refs()
+        .filter(|meta| KnownMetadata::is_cdrom(meta.metatag()))
+        .collect();
+    let mut tracks = Vec::with_capacity(track_refs.len());
+    for track_ref in &track_refs {
+        let metadata = track_ref
+            .read(chd.inner())
             .map_err(RomAnalyzerError::ChdError)?;
-
-        let remaining_capacity = MAX_HEADER_SIZE - decompressed_data.len();
-        let data_to_add = out_buf.len().min(remaining_capacity);
-        decompressed_data.extend_from_slice(&out_buf[..data_to_add]);
+        tracks.extend(parse_track_metadata(&metadata.value));
     }
+    for track in &tracks {
+        trace!(
+            "[+] CHD track {}: type {}, {} frames, {} stored pregap frames",
+            track.number, track.track_type, track.frames, track.stored_pregap
+        );
+    }
+    let start_offset = first_data_track_offset(&tracks).unwrap_or(0);
+    if start_offset > 0 {
+        debug!(
+            "[+] Reading CHD from the first data track at 0x{:x}.",
+            start_offset
+        );
+    }
+
+    let mut temp_buf = Vec::new();
+    let decompressed_data = read_hunk_prefix(
+        hunk_count,
+        hunk_size,
+        start_offset,
+        MAX_HEADER_SIZE,
+        |hunk_num, out_buf| {
+            let mut hunk = chd.hunk(hunk_num).map_err(RomAnalyzerError::ChdError)?;
+            hunk.read_hunk_in(&mut temp_buf, out_buf)
+                .map_err(RomAnalyzerError::ChdError)?;
+            Ok(())
+        },
+    )?;
 
     debug!(
         "[+] Decompressed first {} bytes for header analysis.",
@@ -85,6 +233,72 @@ pub fn analyze_chd_file(filepath: &Path) -> Result<Vec<u8>, RomAnalyzerError> {
     Ok(decompressed_data)
 }
 
+/// Builds the error returned for a CHD file that holds no data to analyze.
+fn empty_or_truncated_error(filepath: &Path, reason: &str) -> RomAnalyzerError {
+    RomAnalyzerError::ArchiveError(format!(
+        "CHD archive is empty or truncated, {}: {}",
+        reason,
+        filepath.display()
+    ))
+}
+
+/// Reads hunks through `read_hunk` from `start_offset` until `max_bytes` of data have been collected.
+///
+/// Only the hunks that overlap the `max_bytes` from `start_offset` are read, so a CHD whose first
+/// hunk already covers the cap is decompressed exactly once. A single hunk-sized buffer is reused for every
+/// read and the output is allocated at its final size up front.
+///
+/// # Arguments
+///
+/// * `hunk_count` - The number of hunks in the CHD.
+/// * `hunk_size` - The decompressed size of a single hunk in bytes.
+/// * `start_offset` - The byte offset within the decompressed data to start reading from.
+/// * `max_bytes` - The maximum number of decompressed bytes to return.
+/// * `read_hunk` - Decompresses the given hunk number into the provided hunk-sized buffer.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok(Vec<u8>)` containing up to `max_bytes` of decompressed data.
+/// - `Err`([`RomAnalyzerError`]) if any call to `read_hunk` fails.
+fn read_hunk_prefix<F>(
+    hunk_count: u32,
+    hunk_size: u32,
+    start_offset: u64,
+    max_bytes: usize,
+    mut read_hunk: F,
+) -> Result<Vec<u8>, RomAnalyzerError>
+where
+    F: FnMut(u32, &mut [u8]) -> Result<(), RomAnalyzerError>,
+{
+    let hunk_size = hunk_size as usize;
+    if hunk_size == 0 {
+        return Ok(Vec::new());
+    }
+    let first_hunk = start_offset / hunk_size as u64;
+    if first_hunk >= hunk_count as u64 {
+        return Ok(Vec::new());
+    }
+    // The first hunk may start before `start_offset`, so its leading bytes are skipped.
+    let mut skip = (start_offset % hunk_size as u64) as usize;
+    let last_hunk =
+        (first_hunk + (skip + max_bytes).div_ceil(hunk_size) as u64).min(hunk_count as u64);
+    let output_size = ((last_hunk - first_hunk) as usize * hunk_size - skip).min(max_bytes);
+
+    let mut decompressed_data = Vec::with_capacity(output_size);
+    let mut out_buf = vec![0; hunk_size];
+    for hunk_num in first_hunk as u32..last_hunk as u32 {
+        read_hunk(hunk_num, &mut out_buf)?;
+
+        let remaining_capacity = output_size - decompressed_data.len();
+        let hunk_data = &out_buf[skip..];
+        decompressed_data.extend_from_slice(&hunk_data[..hunk_data.len().min(remaining_capacity)]);
+        skip = 0;
+    }
+
+    Ok(decompressed_data)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +317,209 @@ mod tests {
             _ => panic!("Expected IoError variant"),
         }
     }
+
+    /// Test helper to build an uncompressed version 5 CHD header with no metadata.
+    fn generate_v5_header(logical_bytes: u64, hunk_bytes: u32) -> Vec<u8> {
+        let mut header = b"MComprHD".to_vec();
+        header.extend(124u32.to_be_bytes()); // Header length
+        header.extend(5u32.to_be_bytes()); // Version
+        header.extend([0; 16]); // No compressors
+        header.extend(logical_bytes.to_be_bytes());
+        header.extend(124u64.to_be_bytes()); // Map offset
+        header.extend(0u64.to_be_bytes()); // Metadata offset
+        header.extend(hunk_bytes.to_be_bytes());
+        header.extend(0x200u32.to_be_bytes()); // Unit bytes
+        header.resize(124, 0); // SHA1 digests
+        header
+    }
+
+    #[test]
+    fn test_analyze_chd_file_empty() -> Result<(), RomAnalyzerError> {
+        let dir = tempfile::tempdir()?;
+        let chd_path = dir.path().join("empty.chd");
+        std::fs::write(&chd_path, [])?;
+
+        match analyze_chd_file(&chd_path) {
+            Err(RomAnalyzerError::ArchiveError(message)) => {
+                assert!(message.starts_with("CHD archive is empty or truncated"));
+                assert!(message.ends_with("empty.chd"));
+            }
+            other => panic!("Expected ArchiveError, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_chd_file_zero_hunks() -> Result<(), RomAnalyzerError> {
+        let dir = tempfile::tempdir()?;
+        let chd_path = dir.path().join("zero_hunks.chd");
+        std::fs::write(&chd_path, generate_v5_header(0, 0x1000))?;
+
+        match analyze_chd_file(&chd_path) {
+            Err(RomAnalyzerError::ArchiveError(message)) => assert!(
+                message.starts_with("CHD archive is empty or truncated, it has no hunks"),
+                "{}",
+                message
+            ),
+            other => panic!("Expected ArchiveError, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_hunk_prefix_stops_at_cap() -> Result<(), RomAnalyzerError> {
+        // With 32KB hunks only the first four hunks are needed to fill 128KB.
+        let mut hunks_read = Vec::new();
+        let data = read_hunk_prefix(16, 0x8000, 0, MAX_HEADER_SIZE, |hunk_num, out_buf| {
+            hunks_read.push(hunk_num);
+            out_buf.fill(hunk_num as u8);
+            Ok(())
+        })?;
+
+        assert_eq!(hunks_read, vec![0, 1, 2, 3]);
+        assert_eq!(data.len(), MAX_HEADER_SIZE);
+        assert_eq!(data[0x8000 * 3], 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_hunk_prefix_single_large_hunk() -> Result<(), RomAnalyzerError> {
+        // A hunk larger than the cap is read once and truncated.
+        let mut read_count = 0;
+        let data = read_hunk_prefix(4, 0x40000, 0, MAX_HEADER_SIZE, |_, _| {
+            read_count += 1;
+            Ok(())
+        })?;
+
+        assert_eq!(read_count, 1);
+        assert_eq!(data.len(), MAX_HEADER_SIZE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_hunk_prefix_small_chd() -> Result<(), RomAnalyzerError> {
+        // A CHD smaller than the cap is read in full.
+        let mut read_count = 0;
+        let data = read_hunk_prefix(2, 0x1000, 0, MAX_HEADER_SIZE, |_, _| {
+            read_count += 1;
+            Ok(())
+        })?;
+
+        assert_eq!(read_count, 2);
+        assert_eq!(data.len(), 0x2000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_hunk_prefix_from_offset() -> Result<(), RomAnalyzerError> {
+        // Starting partway into hunk 2 reads from there, and one hunk further to fill the cap.
+        let mut hunks_read = Vec::new();
+        let data = read_hunk_prefix(16, 0x8000, 0x10100, MAX_HEADER_SIZE, |hunk_num, out_buf| {
+            hunks_read.push(hunk_num);
+            out_buf.fill(hunk_num as u8);
+            Ok(())
+        })?;
+
+        assert_eq!(hunks_read, vec![2, 3, 4, 5, 6]);
+        assert_eq!(data.len(), MAX_HEADER_SIZE);
+        assert_eq!(data[0], 2);
+        assert_eq!(data[0x8000 - 0x100], 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_hunk_prefix_offset_past_end() -> Result<(), RomAnalyzerError> {
+        let data = read_hunk_prefix(2, 0x1000, 0x2000, MAX_HEADER_SIZE, |_, _| {
+            panic!("No hunk should be read");
+        })?;
+
+        assert!(data.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_hunk_geometry() {
+        // A 700MB CD image with the default 19584 byte hunks.
+        assert!(validate_hunk_geometry(37479, 19584, 733_988_736).is_ok());
+        assert!(validate_hunk_geometry(37478, 19584, 733_988_736).is_err());
+        assert!(validate_hunk_geometry(1, 0, 0).is_err());
+        assert!(validate_hunk_geometry(1, MAX_HUNK_SIZE + 1, 0x100_0001).is_err());
+        assert!(validate_hunk_geometry(0, 0x1000, 0).is_ok());
+    }
+
+    #[test]
+    fn test_validate_hunk_geometry_absurd_hunk_count() {
+        let result = validate_hunk_geometry(u32::MAX, 0x1000, 0x20000);
+        match result {
+            Err(RomAnalyzerError::ArchiveError(message)) => {
+                assert!(message.contains("4294967295 hunks"), "{}", message)
+            }
+            other => panic!("Expected ArchiveError, got {:?}", other),
+        }
+        // The largest hunk geometry can't overflow the size arithmetic.
+        assert!(validate_hunk_geometry(u32::MAX, MAX_HUNK_SIZE, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_first_data_track_offset_overflow() {
+        let tracks = [
+            CdTrack {
+                number: 1,
+                track_type: "AUDIO".to_string(),
+                frames: u64::MAX,
+                stored_pregap: 0,
+            },
+            CdTrack {
+                number: 2,
+                track_type: "MODE1_RAW".to_string(),
+                frames: 1000,
+                stored_pregap: 0,
+            },
+        ];
+        assert_eq!(first_data_track_offset(&tracks), None);
+    }
+
+    #[test]
+    fn test_parse_track_metadata() {
+        let track = parse_track_metadata(
+            b"TRACK:2 TYPE:MODE2_RAW SUBTYPE:NONE FRAMES:5000 PREGAP:150 PGTYPE:VMODE2_RAW PGSUB:RW POSTGAP:0\0",
+        );
+        assert_eq!(
+            track,
+            Some(CdTrack {
+                number: 2,
+                track_type: "MODE2_RAW".to_string(),
+                frames: 5000,
+                stored_pregap: 150,
+            })
+        );
+
+        // CHTR entries have no pregap information.
+        let track = parse_track_metadata(b"TRACK:1 TYPE:AUDIO SUBTYPE:NONE FRAMES:1000");
+        assert_eq!(track.map(|t| t.stored_pregap), Some(0));
+
+        assert_eq!(parse_track_metadata(b"TRACK:1 TYPE:AUDIO"), None);
+    }
+
+    #[test]
+    fn test_first_data_track_offset_skips_audio() {
+        let tracks: Vec<_> = [
+            &b"TRACK:1 TYPE:AUDIO SUBTYPE:NONE FRAMES:1001 PREGAP:0 PGTYPE:MODE1 PGSUB:NONE POSTGAP:0"[..],
+            &b"TRACK:2 TYPE:MODE2_RAW SUBTYPE:NONE FRAMES:5150 PREGAP:150 PGTYPE:VMODE2_RAW PGSUB:NONE POSTGAP:0"[..],
+        ]
+        .iter()
+        .filter_map(|value| parse_track_metadata(value))
+        .collect();
+
+        // The audio track is padded from 1001 to 1004 frames, then the stored pregap is skipped.
+        assert_eq!(
+            first_data_track_offset(&tracks),
+            Some((1004 + 150) * CD_FRAME_SIZE)
+        );
+        assert_eq!(
+            first_data_track_offset(&tracks[1..]),
+            Some(150 * CD_FRAME_SIZE)
+        );
+        assert_eq!(first_data_track_offset(&tracks[..1]), None);
+    }
 }
@@ -0,0 +1,279 @@
+//! Provides functionality for reading CSO (compressed ISO) images.
+//!
+//! A CSO file splits an ISO image into fixed-size blocks which are stored either raw or
+//! deflate-compressed, with an index table pointing at each block. This module inflates only
+//! the leading blocks needed for header analysis, so large PSP/PSX images are never fully
+//! decompressed.
+//!
+//! CSO format documentation referenced here:
+//! <https://github.com/unknownbrackets/maxcso/blob/master/README_CSO.md>
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use flate2::read::DeflateDecoder;
+use log::debug;
+
+use crate::error::RomAnalyzerError;
+
+// We only need the first few KB for header analysis for PSX and SegaCD.
+const MAX_HEADER_SIZE: usize = 0x20000; // 128KB
+
+const CSO_MAGIC: &[u8] = b"CISO";
+const CSO_HEADER_SIZE: usize = 0x18;
+const CSO_INDEX_ENTRY_SIZE: usize = 4;
+// The high bit of an index entry marks a block stored without compression.
+const CSO_PLAIN_BLOCK_FLAG: u32 = 0x8000_0000;
+// Real images use 2KB-16KB blocks, anything past this is treated as a corrupt header.
+const CSO_MAX_BLOCK_SIZE: u32 = 0x100000; // 1MB
+
+/// The fields of the fixed-size CSO header that are needed to locate blocks.
+struct CsoHeader {
+    total_bytes: u64,
+    block_size: usize,
+    align: u8,
+}
+
+impl CsoHeader {
+    /// Parses the 24-byte CSO header at the start of `data`.
+    fn parse(data: &[u8]) -> Result<CsoHeader, RomAnalyzerError> {
+        if data.len() < CSO_HEADER_SIZE {
+            return Err(RomAnalyzerError::DataTooSmall {
+                file_size: data.len(),
+                required_size: CSO_HEADER_SIZE,
+                details: "CSO header".to_string(),
+            });
+        }
+        if &data[0..4] != CSO_MAGIC {
+            return Err(RomAnalyzerError::ArchiveError(
+                "Invalid CSO magic. Expected 'CISO'.".to_string(),
+            ));
+        }
+
+        let total_bytes = u64::from_le_bytes(data[0x08..0x10].try_into().unwrap_or_default());
+        let block_size = u32::from_le_bytes(data[0x10..0x14].try_into().unwrap_or_default());
+        let align = data[0x15];
+        if block_size == 0 || block_size > CSO_MAX_BLOCK_SIZE || align >= 32 {
+            return Err(RomAnalyzerError::ArchiveError(format!(
+                "Invalid CSO header (block size {}, index alignment {}).",
+                block_size, align
+            )));
+        }
+
+        Ok(CsoHeader {
+            total_bytes,
+            block_size: block_size as usize,
+            align,
+        })
+    }
+
+    /// Returns how many leading blocks must be inflated to produce `max_bytes` of output.
+    fn blocks_needed(&self, max_bytes: usize) -> usize {
+        let total_blocks = self.total_bytes.div_ceil(self.block_size as u64);
+        (max_bytes.div_ceil(self.block_size) as u64).min(total_blocks) as usize
+    }
+}
+
+/// Decompresses the leading blocks of an in-memory CSO image.
+///
+/// This function reads the CSO header and index table, then inflates blocks in order until
+/// `max_bytes` of the original ISO have been recovered (or the image ends). Blocks flagged as
+/// plain in the index are copied as-is, all others are raw deflate streams. `data` only needs
+/// to contain the header, index and compressed data up to the last block required.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the CSO file, or a prefix of it.
+/// * `max_bytes` - The maximum number of decompressed bytes to return.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok(Vec<u8>)` containing up to `max_bytes` of the decompressed ISO image.
+/// - `Err`([`RomAnalyzerError`]) if the header is invalid, the index points outside of `data`
+///   or a block fails to inflate.
+pub fn decompress_cso_header(data: &[u8], max_bytes: usize) -> Result<Vec<u8>, RomAnalyzerError> {
+    let header = CsoHeader::parse(data)?;
+    let blocks_needed = header.blocks_needed(max_bytes);
+    let output_size = (header.total_bytes.min(max_bytes as u64)) as usize;
+
+    // Each block needs its own index entry plus the following one to know where it ends.
+    let index_end = CSO_HEADER_SIZE + (blocks_needed + 1) * CSO_INDEX_ENTRY_SIZE;
+    if data.len() < index_end {
+        return Err(RomAnalyzerError::DataTooSmall {
+            file_size: data.len(),
+            required_size: index_end,
+            details: "CSO index table".to_string(),
+        });
+    }
+    let index_entry = |block: usize| {
+        let start = CSO_HEADER_SIZE + block * CSO_INDEX_ENTRY_SIZE;
+        u32::from_le_bytes(
+            data[start..start + CSO_INDEX_ENTRY_SIZE]
+                .try_into()
+                .unwrap_or_default(),
+        )
+    };
+
+    let mut decompressed_data = Vec::with_capacity(output_size);
+    for block in 0..blocks_needed {
+        let entry = index_entry(block);
+        let is_plain = entry & CSO_PLAIN_BLOCK_FLAG != 0;
+        let start = ((entry & !CSO_PLAIN_BLOCK_FLAG) as usize) << header.align;
+        let end = ((index_entry(block + 1) & !CSO_PLAIN_BLOCK_FLAG) as usize) << header.align;
+
+        let block_data = data.get(start..end).ok_or_else(|| {
+            RomAnalyzerError::ArchiveError(format!(
+                "CSO block {} (0x{:X}..0x{:X}) is outside of the file.",
+                block, start, end
+            ))
+        })?;
+
+        let mut block_buf = Vec::with_capacity(header.block_size);
+        if is_plain {
+            block_buf.extend_from_slice(&block_data[..block_data.len().min(header.block_size)]);
+        } else {
+            DeflateDecoder::new(block_data)
+                .take(header.block_size as u64)
+                .read_to_end(&mut block_buf)?;
+        }
+
+        let remaining_capacity = output_size - decompressed_data.len();
+        decompressed_data.extend_from_slice(&block_buf[..block_buf.len().min(remaining_capacity)]);
+    }
+
+    Ok(decompressed_data)
+}
+
+/// Analyzes a CSO file, decompressing only the blocks needed for header analysis.
+///
+/// This function reads the CSO header to work out how much of the file holds the first
+/// `MAX_HEADER_SIZE` bytes of the ISO image, reads just that prefix from disk and passes it to
+/// [`decompress_cso_header`].
+///
+/// # Arguments
+///
+/// * `filepath` - The path to the CSO file.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok(Vec<u8>)` containing the decompressed initial bytes of the ISO image.
+/// - `Err`([`RomAnalyzerError`]) if the file cannot be read or is not a valid CSO image.
+pub fn analyze_cso_file(filepath: &Path) -> Result<Vec<u8>, RomAnalyzerError> {
+    let mut file = File::open(filepath)?;
+
+    debug!(
+        "[+] Analyzing CSO file: {}",
+        filepath
+            .file_name()
+            .unwrap_or_else(|| filepath.as_ref())
+            .to_string_lossy()
+    );
+
+    let mut header_bytes = Vec::with_capacity(CSO_HEADER_SIZE);
+    (&mut file)
+        .take(CSO_HEADER_SIZE as u64)
+        .read_to_end(&mut header_bytes)?;
+    let header = CsoHeader::parse(&header_bytes)?;
+
+    // Compressed blocks are never stored larger than their plain size, but each one may be
+    // padded up to the index alignment.
+    let blocks_needed = header.blocks_needed(MAX_HEADER_SIZE);
+    let index_end = CSO_HEADER_SIZE + (blocks_needed + 1) * CSO_INDEX_ENTRY_SIZE;
+    let read_limit = index_end + blocks_needed * (header.block_size + (1 << header.align));
+
+    let mut data = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.take(read_limit as u64).read_to_end(&mut data)?;
+
+    let decompressed_data = decompress_cso_header(&data, MAX_HEADER_SIZE)?;
+
+    debug!(
+        "[+] Decompressed first {} bytes for header analysis.",
+        decompressed_data.len()
+    );
+
+    Ok(decompressed_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::DeflateEncoder;
+    use std::io::Write;
+
+    const TEST_BLOCK_SIZE: usize = 0x800;
+
+    /// Test helper to wrap `iso_data` in a CSO image, compressing every other block.
+    fn build_cso(iso_data: &[u8]) -> Vec<u8> {
+        let blocks: Vec<&[u8]> = iso_data.chunks(TEST_BLOCK_SIZE).collect();
+        let index_len = (blocks.len() + 1) * CSO_INDEX_ENTRY_SIZE;
+
+        let mut header = Vec::new();
+        header.extend_from_slice(CSO_MAGIC);
+        header.extend_from_slice(&(CSO_HEADER_SIZE as u32).to_le_bytes());
+        header.extend_from_slice(&(iso_data.len() as u64).to_le_bytes());
+        header.extend_from_slice(&(TEST_BLOCK_SIZE as u32).to_le_bytes());
+        header.extend_from_slice(&[1, 0, 0, 0]); // Version 1, no index alignment.
+
+        let mut index = Vec::new();
+        let mut block_data = Vec::new();
+        for (i, block) in blocks.iter().enumerate() {
+            let offset = (CSO_HEADER_SIZE + index_len + block_data.len()) as u32;
+            if i % 2 == 0 {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(block).unwrap();
+                block_data.extend_from_slice(&encoder.finish().unwrap());
+                index.extend_from_slice(&offset.to_le_bytes());
+            } else {
+                block_data.extend_from_slice(block);
+                index.extend_from_slice(&(offset | CSO_PLAIN_BLOCK_FLAG).to_le_bytes());
+            }
+        }
+        let end_offset = (CSO_HEADER_SIZE + index_len + block_data.len()) as u32;
+        index.extend_from_slice(&end_offset.to_le_bytes());
+
+        [header, index, block_data].concat()
+    }
+
+    #[test]
+    fn test_decompress_cso_header_round_trip() -> Result<(), RomAnalyzerError> {
+        let iso_data: Vec<u8> = (0..0x2100).map(|i| (i % 251) as u8).collect();
+        let cso = build_cso(&iso_data);
+
+        assert_eq!(decompress_cso_header(&cso, 0x10000)?, iso_data);
+        assert_eq!(decompress_cso_header(&cso, 0x900)?, iso_data[..0x900]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompress_cso_header_invalid_magic() {
+        let mut cso = build_cso(&[0u8; 0x800]);
+        cso[0..4].copy_from_slice(b"ZISO");
+        let result = decompress_cso_header(&cso, 0x800);
+        assert!(matches!(result, Err(RomAnalyzerError::ArchiveError(_))));
+    }
+
+    #[test]
+    fn test_decompress_cso_header_truncated_block() {
+        let cso = build_cso(&[0u8; 0x1000]);
+        let result = decompress_cso_header(&cso[..cso.len() - 1], 0x1000);
+        assert!(matches!(result, Err(RomAnalyzerError::ArchiveError(_))));
+    }
+
+    #[test]
+    fn test_analyze_rom_data_cso_sles() -> Result<(), RomAnalyzerError> {
+        let mut iso_data = vec![0u8; 0x2000];
+        iso_data[0x1100..0x1104].copy_from_slice(b"SLES");
+        let dir = tempfile::tempdir()?;
+        let cso_path = dir.path().join("game.cso");
+        std::fs::write(&cso_path, build_cso(&iso_data))?;
+
+        let analysis = crate::analyze_rom_data(cso_path.to_str().unwrap())?;
+        assert_eq!(analysis.region(), "Europe (PAL)");
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! Provides functionality for reading CUE sheets of multi-file (bin/cue) CD images.
+//!
+//! A CUE sheet lists the track files of a disc image with `FILE` commands, e.g.
+//! `FILE "Game (Track 1).bin" BINARY`. Header analysis only needs the first file, which holds
+//! the data track, while directory walks use the full list to skip tracks that are already
+//! covered by their sheet.
+//!
+//! CUE sheet documentation referenced here:
+//! <https://www.gnu.org/software/ccd2cue/manual/html_node/CUE-sheet-format.html>
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+use crate::error::RomAnalyzerError;
+
+/// Extracts the file names referenced by the `FILE` commands of a CUE sheet, in order.
+///
+/// Both quoted and unquoted file names are supported. Lines holding other commands are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::archive::cue::parse_cue_files;
+///
+/// let sheet = "FILE \"Game (Track 1).bin\" BINARY\n  TRACK 01 MODE2/2352\n    INDEX 01 00:00:00\n";
+/// assert_eq!(parse_cue_files(sheet), vec!["Game (Track 1).bin"]);
+/// ```
+pub fn parse_cue_files(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (command, rest) = line.split_once(char::is_whitespace)?;
+            if !command.eq_ignore_ascii_case("FILE") {
+                return None;
+            }
+            let rest = rest.trim_start();
+            let name = match rest.strip_prefix('"') {
+                Some(quoted) => quoted.split('"').next()?,
+                // An unquoted name runs up to the file type.
+                None => rest
+                    .rsplit_once(char::is_whitespace)
+                    .map_or(rest, |(name, _)| name),
+            };
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Reads a CUE sheet and resolves the track files it references.
+///
+/// File names are relative to the directory holding the sheet. The files are not required to
+/// exist.
+///
+/// # Arguments
+///
+/// * `cue_path` - The path to the `.cue` file.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok(Vec<PathBuf>)` with the path of every referenced file, in sheet order.
+/// - `Err`([`RomAnalyzerError`]) if the sheet cannot be read.
+pub fn cue_track_paths(cue_path: &Path) -> Result<Vec<PathBuf>, RomAnalyzerError> {
+    let contents = fs::read(cue_path)?;
+    let cue_dir = cue_path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(parse_cue_files(&String::from_utf8_lossy(&contents))
+        .into_iter()
+        .map(|name| cue_dir.join(name))
+        .collect())
+}
+
+/// Finds the data track of a bin/cue image, the first file its CUE sheet references.
+///
+/// # Arguments
+///
+/// * `cue_path` - The path to the `.cue` file.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok(PathBuf)` with the path of the first track file.
+/// - `Err`([`RomAnalyzerError::ArchiveError`]) if the sheet references no files, or the first
+///   one doesn't exist, or another [`RomAnalyzerError`] if the sheet cannot be read.
+pub fn cue_data_track(cue_path: &Path) -> Result<PathBuf, RomAnalyzerError> {
+    let track = cue_track_paths(cue_path)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| RomAnalyzerError::ArchiveError("CUE sheet lists no files.".to_string()))?;
+    if !track.is_file() {
+        return Err(RomAnalyzerError::ArchiveError(format!(
+            "Track file {} referenced by the CUE sheet not found.",
+            track.display()
+        )));
+    }
+
+    debug!("[+] Analyzing data track {} of CUE sheet.", track.display());
+    Ok(track)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cue_files() {
+        let sheet = "REM GENRE Action\r\n\
+                     FILE \"Game (Track 1).bin\" BINARY\r\n\
+                     \x20 TRACK 01 MODE2/2352\r\n\
+                     \x20   INDEX 01 00:00:00\r\n\
+                     file track2.bin binary\r\n\
+                     \x20 TRACK 02 AUDIO\r\n\
+                     FILE \"\" BINARY\r\n";
+        assert_eq!(
+            parse_cue_files(sheet),
+            vec!["Game (Track 1).bin", "track2.bin"]
+        );
+        assert!(parse_cue_files("").is_empty());
+    }
+
+    #[test]
+    fn test_cue_data_track() -> Result<(), RomAnalyzerError> {
+        let dir = tempfile::tempdir()?;
+        let cue_path = dir.path().join("game.cue");
+        fs::write(
+            &cue_path,
+            "FILE \"game (Track 1).bin\" BINARY\nFILE \"game (Track 2).bin\" BINARY\n",
+        )?;
+
+        assert_eq!(
+            cue_track_paths(&cue_path)?,
+            vec![
+                dir.path().join("game (Track 1).bin"),
+                dir.path().join("game (Track 2).bin")
+            ]
+        );
+        assert!(matches!(
+            cue_data_track(&cue_path),
+            Err(RomAnalyzerError::ArchiveError(_))
+        ));
+
+        fs::write(dir.path().join("game (Track 1).bin"), [0; 16])?;
+        assert_eq!(
+            cue_data_track(&cue_path)?,
+            dir.path().join("game (Track 1).bin")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cue_data_track_empty_sheet() -> Result<(), RomAnalyzerError> {
+        let dir = tempfile::tempdir()?;
+        let cue_path = dir.path().join("game.cue");
+        fs::write(&cue_path, "REM nothing here\n")?;
+        assert!(matches!(
+            cue_data_track(&cue_path),
+            Err(RomAnalyzerError::ArchiveError(_))
+        ));
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
 //! This module handles the processing and extraction of ROM data from various archive formats.
 
 pub mod chd;
+pub mod cso;
+pub mod cue;
+pub mod rvz;
 pub mod zip;
@@ -0,0 +1,118 @@
+//! Provides functionality for reading WIA and RVZ (compressed GameCube/Wii) images.
+//!
+//! Both formats store an uncompressed copy of the first 0x80 bytes of the disc in their second
+//! header, which is all GameCube and Wii header analysis needs. This module reads just that
+//! copy, so the compressed disc data itself is never touched.
+//!
+//! WIA/RVZ format documentation referenced here:
+//! <https://github.com/dolphin-emu/dolphin/blob/master/docs/WiaAndRvz.md>
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use log::debug;
+
+use crate::error::RomAnalyzerError;
+
+const WIA_MAGIC: &[u8] = b"WIA\x01";
+const RVZ_MAGIC: &[u8] = b"RVZ\x01";
+
+// The first header is 0x48 bytes. The second header starts with the disc type, compression
+// type, compression level and chunk size, followed by the copy of the disc header.
+const DISC_HEADER_OFFSET: usize = 0x58;
+const DISC_HEADER_SIZE: usize = 0x80;
+
+/// Returns the copy of the disc header stored in a WIA or RVZ image.
+///
+/// # Arguments
+///
+/// * `data` - The start of the WIA or RVZ file.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok(&[u8])` containing the first 0x80 bytes of the disc.
+/// - `Err`([`RomAnalyzerError`]) if the data is too short or has neither magic.
+pub fn rvz_disc_header(data: &[u8]) -> Result<&[u8], RomAnalyzerError> {
+    if !data.starts_with(WIA_MAGIC) && !data.starts_with(RVZ_MAGIC) {
+        return Err(RomAnalyzerError::ArchiveError(
+            "Invalid WIA/RVZ magic. Expected 'WIA\\x01' or 'RVZ\\x01'.".to_string(),
+        ));
+    }
+    data.get(DISC_HEADER_OFFSET..DISC_HEADER_OFFSET + DISC_HEADER_SIZE)
+        .ok_or_else(|| RomAnalyzerError::DataTooSmall {
+            file_size: data.len(),
+            required_size: DISC_HEADER_OFFSET + DISC_HEADER_SIZE,
+            details: "WIA/RVZ header".to_string(),
+        })
+}
+
+/// Reads the disc header from a WIA or RVZ file on disk.
+///
+/// Only the file headers are read, see [`rvz_disc_header`].
+///
+/// # Arguments
+///
+/// * `filepath` - The path to the WIA or RVZ file.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok(Vec<u8>)` containing the first 0x80 bytes of the disc.
+/// - `Err`([`RomAnalyzerError`]) if the file cannot be read or is not a valid WIA/RVZ image.
+pub fn analyze_rvz_file(filepath: &Path) -> Result<Vec<u8>, RomAnalyzerError> {
+    let file = File::open(filepath)?;
+
+    debug!(
+        "[+] Analyzing WIA/RVZ file: {}",
+        filepath
+            .file_name()
+            .unwrap_or_else(|| filepath.as_ref())
+            .to_string_lossy()
+    );
+
+    let mut data = Vec::with_capacity(DISC_HEADER_OFFSET + DISC_HEADER_SIZE);
+    file.take((DISC_HEADER_OFFSET + DISC_HEADER_SIZE) as u64)
+        .read_to_end(&mut data)?;
+
+    Ok(rvz_disc_header(&data)?.to_vec())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Test helper to wrap a disc header in the headers of an RVZ image.
+    pub(crate) fn build_rvz(disc_header: &[u8]) -> Vec<u8> {
+        let mut data = vec![0; DISC_HEADER_OFFSET + DISC_HEADER_SIZE];
+        data[..4].copy_from_slice(RVZ_MAGIC);
+        data[DISC_HEADER_OFFSET..DISC_HEADER_OFFSET + disc_header.len()]
+            .copy_from_slice(disc_header);
+        data
+    }
+
+    #[test]
+    fn test_rvz_disc_header() -> Result<(), RomAnalyzerError> {
+        let data = build_rvz(b"GALE01");
+        assert!(rvz_disc_header(&data)?.starts_with(b"GALE01"));
+
+        let mut wia = data.clone();
+        wia[..4].copy_from_slice(WIA_MAGIC);
+        assert!(rvz_disc_header(&wia)?.starts_with(b"GALE01"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rvz_disc_header_invalid() {
+        let data = build_rvz(b"GALE01");
+        assert!(matches!(
+            rvz_disc_header(&data[..0x60]),
+            Err(RomAnalyzerError::DataTooSmall { .. })
+        ));
+        assert!(matches!(
+            rvz_disc_header(b"CISO\0\0\0\0"),
+            Err(RomAnalyzerError::ArchiveError(_))
+        ));
+    }
+}
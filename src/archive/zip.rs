@@ -1,29 +1,104 @@
 //! Provides functionality for processing ZIP archives to extract ROM files.
 //!
 //! This module can open a ZIP file, iterate through its contents, and identify
-//! supported ROM files based on their file extensions. It then extracts the
-//! raw byte data of the first supported ROM found within the archive.
+//! supported ROM files based on their file extensions. When an archive holds more
+//! than one supported ROM, a [`ZipSelectionStrategy`] decides which one is extracted, or
+//! [`process_zip_file_all`] can extract all of them.
+//!
+//! ROMs are occasionally double-archived, so a ZIP entry that is itself a ZIP archive is
+//! extracted to memory and searched as well, up to [`MAX_ARCHIVE_DEPTH`] levels deep.
 
 use std::fs::File;
-use std::io::Read;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
 
 use log::debug;
 use zip::ZipArchive;
+use zip::result::ZipError;
 
-use crate::SUPPORTED_ROM_EXTENSIONS;
 use crate::error::RomAnalyzerError;
+use crate::{
+    ROM_DETECTION_PREFIX_SIZE, RomFileType, SUPPORTED_ROM_EXTENSIONS, detect_rom_file_type,
+};
 
 /// Max ROM size to extract from the zip (128kb).
 /// This avoids us  extracting larger files to memory which is a concern for memory constrained
 /// systems that may be utilizing this functionality.
 const MAX_ROM_SIZE: u64 = 128 * 1024;
 
-/// Processes a ZIP archive to find and extract the first supported ROM file.
+/// Entries whose declared uncompressed size exceeds their compressed size by more than this
+/// factor are rejected as likely zip bombs. Real ROMs, even heavily padded ones, stay far below it.
+const MAX_COMPRESSION_RATIO: u64 = 1000;
+
+/// The maximum number of nested archive levels that are opened, counting the outermost one.
+/// This prevents crafted archives from nesting endlessly.
+pub const MAX_ARCHIVE_DEPTH: usize = 3;
+
+/// Archive types that are searched recursively when found inside a ZIP archive.
+const NESTED_ARCHIVE_EXTENSIONS: &[&str] = &[".zip"];
+
+/// Entries smaller than this can't hold any supported ROM header (the iNES header is the
+/// smallest at 16 bytes), so they are skipped as decoys or placeholders.
+const MIN_ROM_SIZE: u64 = 16;
+
+/// Determines which supported ROM is extracted when a ZIP archive contains several.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZipSelectionStrategy {
+    /// Pick the first supported ROM in archive order.
+    First,
+    /// Pick the supported ROM with the largest uncompressed size.
+    #[default]
+    Largest,
+    /// Pick the ROM whose extension appears earliest in the given list (e.g. `&[".bin", ".iso"]`).
+    /// Ties, and entries matching none of the extensions, fall back to the largest size.
+    PreferExtensions(&'static [&'static str]),
+}
+
+/// A ROM extracted from a ZIP archive.
+pub(crate) struct ZipRom {
+    /// The leading bytes of the ROM, up to `MAX_ROM_SIZE`.
+    pub(crate) data: Vec<u8>,
+    /// The filename of the ROM within the archive, prefixed with any nested archive names.
+    pub(crate) name: String,
+    /// The full uncompressed size of the ROM, which `data` may be shorter than.
+    pub(crate) size: u64,
+}
+
+/// A supported ROM or nested archive entry found while scanning a ZIP archive.
+struct ZipCandidate {
+    index: usize,
+    name: String,
+    size: u64,
+    compressed_size: u64,
+    is_archive: bool,
+}
+
+impl ZipSelectionStrategy {
+    /// Picks the best candidate according to this strategy.
+    fn select(self, candidates: &[ZipCandidate]) -> Option<&ZipCandidate> {
+        match self {
+            ZipSelectionStrategy::First => candidates.first(),
+            // `max_by_key` returns the last maximum, so reverse to keep archive order on ties.
+            ZipSelectionStrategy::Largest => candidates.iter().rev().max_by_key(|c| c.size),
+            // `min_by_key` already returns the first minimum, keeping archive order on ties.
+            ZipSelectionStrategy::PreferExtensions(preferred) => {
+                candidates.iter().min_by_key(|c| {
+                    let lower_name = c.name.to_lowercase();
+                    let rank = preferred
+                        .iter()
+                        .position(|ext| lower_name.ends_with(&ext.to_lowercase()))
+                        .unwrap_or(preferred.len());
+                    (rank, std::cmp::Reverse(c.size))
+                })
+            }
+        }
+    }
+}
+
+/// Processes a ZIP archive to find and extract the largest supported ROM file.
 ///
-/// This function opens the provided ZIP file, iterates through its entries,
-/// and checks if any entry has a file extension listed in [`SUPPORTED_ROM_EXTENSIONS`].
-/// If a supported ROM is found, its decompressed data and filename are returned.
-/// Only the first supported ROM encountered is extracted.
+/// This is a convenience wrapper around [`process_zip_file_with_options`] using the default
+/// [`ZipSelectionStrategy::Largest`] strategy.
 ///
 /// # Arguments
 ///
@@ -43,12 +118,188 @@ pub fn process_zip_file(
     file: File,
     original_filename: &str,
 ) -> Result<(Vec<u8>, String), RomAnalyzerError> {
-    let mut archive = ZipArchive::new(file)?;
+    process_zip_file_with_options(file, original_filename, ZipSelectionStrategy::default())
+}
+
+/// Processes a ZIP archive to find and extract a supported ROM file using the given strategy.
+///
+/// This function opens the provided ZIP file and collects every entry that has a file extension
+/// listed in [`SUPPORTED_ROM_EXTENSIONS`], skipping directories and entries too small to hold a
+/// ROM header. The `strategy` then picks a single entry, whose decompressed data and filename
+/// are returned. If the picked entry is itself a ZIP archive, it is searched the same way and
+/// the returned filename is prefixed with the nested archive's name (e.g. `inner.zip/game.nes`).
+///
+/// # Arguments
+///
+/// * `file` - A `File` object representing the opened ZIP archive.
+/// * `original_filename` - The name of the ZIP file, used for error reporting.
+/// * `strategy` - The [`ZipSelectionStrategy`] used to pick between multiple supported ROMs.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok((Vec<u8>, String))` containing the raw byte data of the extracted ROM
+///   and its original filename within the archive.
+/// - `Err`([`RomAnalyzerError`]) if:
+///   - The ZIP archive is invalid or corrupted.
+///   - An I/O error occurs during reading.
+///   - No supported ROM files are found within the archive.
+///   - Archives are nested more than [`MAX_ARCHIVE_DEPTH`] levels deep.
+pub fn process_zip_file_with_options(
+    file: File,
+    original_filename: &str,
+    strategy: ZipSelectionStrategy,
+) -> Result<(Vec<u8>, String), RomAnalyzerError> {
+    extract_selected(file, original_filename, strategy, 1).map(|rom| (rom.data, rom.name))
+}
+
+/// Extracts the ROM [`process_zip_file`] would, along with its full uncompressed size.
+pub(crate) fn extract_zip_rom(
+    file: File,
+    original_filename: &str,
+) -> Result<ZipRom, RomAnalyzerError> {
+    extract_selected(file, original_filename, ZipSelectionStrategy::default(), 1)
+}
+
+/// Opens the archive at nesting level `depth` and extracts the entry picked by `strategy`,
+/// recursing into it if it is a nested archive.
+fn extract_selected<R: Read + Seek>(
+    reader: R,
+    original_filename: &str,
+    strategy: ZipSelectionStrategy,
+    depth: usize,
+) -> Result<ZipRom, RomAnalyzerError> {
+    let mut archive = open_archive(reader, original_filename, depth)?;
+
+    let candidates = collect_candidates(&mut archive)?;
+    let Some(selected) = strategy.select(&candidates) else {
+        return Err(no_supported_roms_error(original_filename));
+    };
+
+    debug!("[+] Found supported ROM in zip: {}", selected.name);
+    let data = read_candidate(&mut archive, selected)?;
+
+    if selected.is_archive {
+        let rom = extract_selected(Cursor::new(data), &selected.name, strategy, depth + 1)?;
+        return Ok(ZipRom {
+            name: format!("{}/{}", selected.name, rom.name),
+            ..rom
+        });
+    }
+    Ok(ZipRom {
+        data,
+        name: selected.name.clone(),
+        size: selected.size,
+    })
+}
+
+/// Processes a ZIP archive and extracts every supported ROM file it contains.
+///
+/// This function applies the same filtering as [`process_zip_file_with_options`], but instead of
+/// picking a single entry it returns all of them, in archive order. This suits ROM-set archives
+/// that bundle several games together. Nested ZIP archives are expanded in place.
+///
+/// # Arguments
+///
+/// * `file` - A `File` object representing the opened ZIP archive.
+/// * `original_filename` - The name of the ZIP file, used for error reporting.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok(Vec<(Vec<u8>, String)>)` containing the raw byte data and filename of each ROM.
+/// - `Err`([`RomAnalyzerError`]) if:
+///   - The ZIP archive is invalid or corrupted.
+///   - An I/O error occurs during reading.
+///   - No supported ROM files are found within the archive.
+///   - Archives are nested more than [`MAX_ARCHIVE_DEPTH`] levels deep.
+pub fn process_zip_file_all(
+    file: File,
+    original_filename: &str,
+) -> Result<Vec<(Vec<u8>, String)>, RomAnalyzerError> {
+    let roms = extract_all(file, original_filename, 1)?;
+    Ok(roms.into_iter().map(|rom| (rom.data, rom.name)).collect())
+}
+
+/// Extracts the ROMs [`process_zip_file_all`] would, along with their full uncompressed sizes.
+pub(crate) fn extract_zip_roms_all(
+    file: File,
+    original_filename: &str,
+) -> Result<Vec<ZipRom>, RomAnalyzerError> {
+    extract_all(file, original_filename, 1)
+}
+
+/// Opens the archive at nesting level `depth` and extracts every supported entry, expanding
+/// nested archives in place.
+fn extract_all<R: Read + Seek>(
+    reader: R,
+    original_filename: &str,
+    depth: usize,
+) -> Result<Vec<ZipRom>, RomAnalyzerError> {
+    let mut archive = open_archive(reader, original_filename, depth)?;
+
+    let candidates = collect_candidates(&mut archive)?;
+    if candidates.is_empty() {
+        return Err(no_supported_roms_error(original_filename));
+    }
+
+    let mut roms = Vec::new();
+    for candidate in candidates {
+        debug!("[+] Found supported ROM in zip: {}", candidate.name);
+        let data = read_candidate(&mut archive, &candidate)?;
+        if candidate.is_archive {
+            for rom in extract_all(Cursor::new(data), &candidate.name, depth + 1)? {
+                roms.push(ZipRom {
+                    name: format!("{}/{}", candidate.name, rom.name),
+                    ..rom
+                });
+            }
+        } else {
+            roms.push(ZipRom {
+                data,
+                name: candidate.name,
+                size: candidate.size,
+            });
+        }
+    }
+    Ok(roms)
+}
+
+/// Opens a ZIP archive at nesting level `depth`, refusing to go past [`MAX_ARCHIVE_DEPTH`].
+fn open_archive<R: Read + Seek>(
+    reader: R,
+    original_filename: &str,
+    depth: usize,
+) -> Result<ZipArchive<R>, RomAnalyzerError> {
+    if depth > MAX_ARCHIVE_DEPTH {
+        return Err(RomAnalyzerError::ArchiveError(format!(
+            "Archives nested more than {} levels deep: {}",
+            MAX_ARCHIVE_DEPTH, original_filename
+        )));
+    }
 
     debug!("[+] Analyzing ZIP archive: {}", original_filename);
+    ZipArchive::new(reader).map_err(|e| match e {
+        // Empty and truncated files lack the end of central directory record, or end before it.
+        ZipError::InvalidArchive(_) | ZipError::Io(_) => RomAnalyzerError::ArchiveError(format!(
+            "Zip archive is empty or truncated ({}): {}",
+            e, original_filename
+        )),
+        e => e.into(),
+    })
+}
 
+/// Collects every entry in the archive that looks like a supported ROM or a nested archive.
+///
+/// Entries without any extension are peeked at and kept only if [`detect_rom_file_type`]
+/// recognizes their content. Directories, entries with an unsupported extension and entries
+/// too small to hold a ROM header are skipped.
+fn collect_candidates<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<Vec<ZipCandidate>, RomAnalyzerError> {
+    let mut candidates = Vec::new();
     for i in 0..archive.len() {
-        let file_in_zip = archive.by_index(i)?;
+        let mut file_in_zip = archive.by_index(i)?;
         let entry_name = file_in_zip.name().to_string();
         let lower_entry_name = entry_name.to_lowercase();
 
@@ -59,22 +310,84 @@ pub fn process_zip_file(
         let is_supported_rom = SUPPORTED_ROM_EXTENSIONS
             .iter()
             .any(|ext| lower_entry_name.ends_with(ext));
+        let is_archive = NESTED_ARCHIVE_EXTENSIONS
+            .iter()
+            .any(|ext| lower_entry_name.ends_with(ext));
+
+        let is_extensionless = Path::new(&entry_name).extension().is_none();
+
+        if !is_supported_rom && !is_archive && !is_extensionless {
+            continue;
+        }
 
-        if is_supported_rom {
-            debug!("[+] Found supported ROM in zip: {}", entry_name);
-            // Read the file up to MAX_ROM_SIZE.
-            let mut limited_reader = file_in_zip.take(MAX_ROM_SIZE);
-            let mut data = Vec::new();
-            limited_reader.read_to_end(&mut data)?;
+        if file_in_zip.size() < MIN_ROM_SIZE {
+            debug!(
+                "[-] Skipping {} in zip, too small to be a ROM ({} bytes)",
+                entry_name,
+                file_in_zip.size()
+            );
+            continue;
+        }
 
-            return Ok((data, entry_name));
+        if is_extensionless {
+            let mut prefix = Vec::new();
+            (&mut file_in_zip)
+                .take(ROM_DETECTION_PREFIX_SIZE as u64)
+                .read_to_end(&mut prefix)?;
+            if detect_rom_file_type(&prefix) == RomFileType::Unknown {
+                continue;
+            }
+            debug!("[+] Identified {} in zip by its content", entry_name);
         }
+
+        candidates.push(ZipCandidate {
+            index: i,
+            name: entry_name,
+            size: file_in_zip.size(),
+            compressed_size: file_in_zip.compressed_size(),
+            is_archive,
+        });
     }
+    Ok(candidates)
+}
 
-    Err(RomAnalyzerError::ArchiveError(format!(
+/// Reads a candidate entry from the archive, up to `MAX_ROM_SIZE` bytes.
+///
+/// Entries exceeding [`MAX_COMPRESSION_RATIO`] are rejected before any decompression happens.
+/// Nested archives can't be opened from a truncated prefix, so they must fit within the cap.
+fn read_candidate<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    candidate: &ZipCandidate,
+) -> Result<Vec<u8>, RomAnalyzerError> {
+    if candidate.size
+        > candidate
+            .compressed_size
+            .saturating_mul(MAX_COMPRESSION_RATIO)
+    {
+        return Err(RomAnalyzerError::ArchiveError(format!(
+            "Entry {} exceeds the {}:1 compression ratio limit ({} bytes compressed to {})",
+            candidate.name, MAX_COMPRESSION_RATIO, candidate.size, candidate.compressed_size
+        )));
+    }
+    if candidate.is_archive && candidate.size > MAX_ROM_SIZE {
+        return Err(RomAnalyzerError::ArchiveError(format!(
+            "Nested archive {} is larger than the {} byte limit",
+            candidate.name, MAX_ROM_SIZE
+        )));
+    }
+
+    let mut limited_reader = archive.by_index(candidate.index)?.take(MAX_ROM_SIZE);
+    let mut data = Vec::new();
+    limited_reader.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Builds the error returned when an archive holds no supported ROM files.
+fn no_supported_roms_error(original_filename: &str) -> RomAnalyzerError {
+    RomAnalyzerError::ArchiveError(format!(
         "No supported ROM files found within the zip archive: {}",
         original_filename
-    )))
+    ))
 }
 
 #[cfg(test)]
@@ -94,13 +407,20 @@ mod tests {
 
     /// Test helper function to create a temporary Zip file for testing.
     fn create_zip_file(filename: &str, file_contents: &[u8]) -> Result<TestZip, RomAnalyzerError> {
+        create_multi_zip_file(&[(filename, file_contents)])
+    }
+
+    /// Test helper function to create a temporary Zip file holding several entries.
+    fn create_multi_zip_file(entries: &[(&str, &[u8])]) -> Result<TestZip, RomAnalyzerError> {
         let dir = tempdir()?;
         let zip_path = dir.path().join("test.zip");
         let zip_file = File::create(&zip_path)?;
 
         let mut zip = ZipWriter::new(zip_file);
-        zip.start_file(filename, FileOptions::default())?;
-        zip.write_all(file_contents)?;
+        for (filename, file_contents) in entries {
+            zip.start_file(*filename, FileOptions::default())?;
+            zip.write_all(file_contents)?;
+        }
         zip.finish()?;
 
         let zip_path_string: String = zip_path
@@ -114,6 +434,28 @@ mod tests {
         })
     }
 
+    /// Test helper function to build a Zip archive in memory, for nesting inside other archives.
+    fn build_zip_bytes(entries: &[(&str, &[u8])]) -> Result<Vec<u8>, RomAnalyzerError> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (filename, file_contents) in entries {
+            zip.start_file(*filename, FileOptions::default())?;
+            zip.write_all(file_contents)?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+
+    /// Test helper function to wrap `entries` in `levels` nested archives named `level<N>.zip`.
+    fn build_nested_zip_bytes(
+        entries: &[(&str, &[u8])],
+        levels: usize,
+    ) -> Result<Vec<u8>, RomAnalyzerError> {
+        let mut data = build_zip_bytes(entries)?;
+        for level in 1..levels {
+            data = build_zip_bytes(&[(&format!("level{}.zip", level), &data)])?;
+        }
+        Ok(data)
+    }
+
     #[test]
     fn test_process_zip_file_no_supported_roms() {
         let expected_filename = "unsupported.txt";
@@ -155,4 +497,298 @@ mod tests {
         assert_eq!(extracted_data, expected_data);
         assert_eq!(extracted_filename, expected_filename);
     }
+
+    #[test]
+    fn test_process_zip_file_multiple_roms_picks_largest() {
+        let small_rom = vec![1u8; 100];
+        let large_rom = vec![2u8; 4000];
+        let medium_rom = vec![3u8; 1000];
+
+        let zip_path = create_multi_zip_file(&[
+            ("small.nes", &small_rom),
+            ("large.bin", &large_rom),
+            ("medium.sfc", &medium_rom),
+        ])
+        .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let (extracted_data, extracted_filename) =
+            process_zip_file(zip_file, &zip_path.path).expect("Expected a ROM to be extracted");
+        assert_eq!(extracted_filename, "large.bin");
+        assert_eq!(extracted_data, large_rom);
+    }
+
+    #[test]
+    fn test_process_zip_file_with_options_first() {
+        let small_rom = vec![1u8; 100];
+        let large_rom = vec![2u8; 4000];
+
+        let zip_path =
+            create_multi_zip_file(&[("small.nes", &small_rom), ("large.bin", &large_rom)])
+                .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let (_, extracted_filename) =
+            process_zip_file_with_options(zip_file, &zip_path.path, ZipSelectionStrategy::First)
+                .expect("Expected a ROM to be extracted");
+        assert_eq!(extracted_filename, "small.nes");
+    }
+
+    #[test]
+    fn test_process_zip_file_with_options_prefer_extensions() {
+        let iso_data = vec![1u8; 4000];
+        let small_bin = vec![2u8; 100];
+        let large_bin = vec![3u8; 1000];
+
+        let zip_path = create_multi_zip_file(&[
+            ("track.iso", &iso_data),
+            ("track01.bin", &small_bin),
+            ("track02.BIN", &large_bin),
+        ])
+        .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let (_, extracted_filename) = process_zip_file_with_options(
+            zip_file,
+            &zip_path.path,
+            ZipSelectionStrategy::PreferExtensions(&[".bin", ".iso"]),
+        )
+        .expect("Expected a ROM to be extracted");
+        // Both .bin files outrank the larger .iso, and the larger .bin wins the tie.
+        assert_eq!(extracted_filename, "track02.BIN");
+    }
+
+    #[test]
+    fn test_process_zip_file_with_options_prefer_extensions_keeps_archive_order() {
+        let track = vec![1u8; 1000];
+
+        let zip_path = create_multi_zip_file(&[
+            ("track.iso", &track),
+            ("track01.bin", &track),
+            ("track02.bin", &track),
+        ])
+        .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let (_, extracted_filename) = process_zip_file_with_options(
+            zip_file,
+            &zip_path.path,
+            ZipSelectionStrategy::PreferExtensions(&[".bin", ".iso"]),
+        )
+        .expect("Expected a ROM to be extracted");
+        // On a full tie of rank and size, the first entry in archive order wins.
+        assert_eq!(extracted_filename, "track01.bin");
+    }
+
+    #[test]
+    fn test_process_zip_file_skips_tiny_entries() {
+        let zip_path = create_multi_zip_file(&[("decoy.nes", b"tiny"), ("readme.txt", b"hi")])
+            .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let result = process_zip_file(zip_file, &zip_path.path);
+        assert!(matches!(result, Err(RomAnalyzerError::ArchiveError(_))));
+    }
+
+    #[test]
+    fn test_process_zip_file_all_returns_every_rom() {
+        let nes_rom = vec![1u8; 100];
+        let gb_rom = vec![2u8; 4000];
+
+        let zip_path = create_multi_zip_file(&[
+            ("game.nes", &nes_rom),
+            ("readme.txt", b"Not a ROM at all."),
+            ("game.gb", &gb_rom),
+        ])
+        .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let roms = process_zip_file_all(zip_file, &zip_path.path).expect("Expected ROMs");
+        assert_eq!(
+            roms,
+            vec![
+                (nes_rom, "game.nes".to_string()),
+                (gb_rom, "game.gb".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_zip_file_zip_in_zip() {
+        let nes_rom = vec![1u8; 100];
+        let inner_zip = build_zip_bytes(&[("game.nes", &nes_rom)]).expect("Failed to build zip");
+
+        let zip_path = create_zip_file("inner.zip", &inner_zip).expect("Failed to create zip");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let (extracted_data, extracted_filename) =
+            process_zip_file(zip_file, &zip_path.path).expect("Expected a ROM to be extracted");
+        assert_eq!(extracted_filename, "inner.zip/game.nes");
+        assert_eq!(extracted_data, nes_rom);
+    }
+
+    #[test]
+    fn test_process_zip_file_nesting_at_depth_limit() {
+        let nes_rom = vec![1u8; 100];
+        let nested = build_nested_zip_bytes(&[("game.nes", &nes_rom)], MAX_ARCHIVE_DEPTH - 1)
+            .expect("Failed to build zip");
+
+        let zip_path = create_zip_file("level2.zip", &nested).expect("Failed to create zip");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let (extracted_data, extracted_filename) =
+            process_zip_file(zip_file, &zip_path.path).expect("Expected a ROM to be extracted");
+        assert_eq!(extracted_filename, "level2.zip/level1.zip/game.nes");
+        assert_eq!(extracted_data, nes_rom);
+    }
+
+    #[test]
+    fn test_process_zip_file_nesting_too_deep() {
+        let nested = build_nested_zip_bytes(&[("game.nes", &[1u8; 100])], MAX_ARCHIVE_DEPTH)
+            .expect("Failed to build zip");
+
+        let zip_path = create_zip_file("level3.zip", &nested).expect("Failed to create zip");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        match process_zip_file(zip_file, &zip_path.path) {
+            Err(RomAnalyzerError::ArchiveError(message)) => {
+                assert!(message.starts_with("Archives nested more than 3 levels deep"))
+            }
+            other => panic!("Expected ArchiveError variant, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_process_zip_file_nested_archive_over_size_cap() {
+        // Incompressible data keeps the nested archive above the size cap.
+        let mut state = 0x2545F491u32;
+        let noise: Vec<u8> = (0..MAX_ROM_SIZE + 1)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        let inner_zip = build_zip_bytes(&[("game.bin", &noise)]).expect("Failed to build zip");
+
+        let zip_path = create_zip_file("inner.zip", &inner_zip).expect("Failed to create zip");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        match process_zip_file(zip_file, &zip_path.path) {
+            Err(RomAnalyzerError::ArchiveError(message)) => {
+                assert!(message.contains("larger than the 131072 byte limit"))
+            }
+            other => panic!("Expected ArchiveError variant, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_process_zip_file_all_expands_nested_archives() {
+        let nes_rom = vec![1u8; 100];
+        let gb_rom = vec![2u8; 400];
+        let inner_zip = build_zip_bytes(&[("game.gb", &gb_rom)]).expect("Failed to build zip");
+
+        let zip_path = create_multi_zip_file(&[("game.nes", &nes_rom), ("inner.zip", &inner_zip)])
+            .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let roms = process_zip_file_all(zip_file, &zip_path.path).expect("Expected ROMs");
+        assert_eq!(
+            roms,
+            vec![
+                (nes_rom, "game.nes".to_string()),
+                (gb_rom, "inner.zip/game.gb".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_zip_file_rejects_compression_ratio_bomb() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        let zip_path = dir.path().join("bomb.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path).expect("Failed to create zip"));
+        zip.start_file(
+            "bomb.bin",
+            FileOptions::default().compression_method(zip::CompressionMethod::Bzip2),
+        )
+        .expect("Failed to start zip entry");
+        zip.write_all(&vec![0u8; 1024 * 1024])
+            .expect("Failed to write zip entry");
+        zip.finish().expect("Failed to finish zip");
+
+        let zip_file = File::open(&zip_path).expect("Failed to open zip for reading");
+        match process_zip_file(zip_file, "bomb.zip") {
+            Err(RomAnalyzerError::ArchiveError(message)) => {
+                assert!(message.starts_with("Entry bomb.bin exceeds the 1000:1 compression ratio"))
+            }
+            other => panic!("Expected ArchiveError variant, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_process_zip_file_extensionless_rom_detected_by_content() {
+        let mut genesis_rom = vec![0u8; 0x400];
+        genesis_rom[0x100..0x10C].copy_from_slice(b"SEGA GENESIS");
+
+        let zip_path = create_multi_zip_file(&[
+            ("README", b"This is a plain text file, not a ROM at all."),
+            ("GAME", &genesis_rom),
+        ])
+        .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let (extracted_data, extracted_filename) =
+            process_zip_file(zip_file, &zip_path.path).expect("Expected a ROM to be extracted");
+        assert_eq!(extracted_filename, "GAME");
+        assert_eq!(extracted_data, genesis_rom);
+    }
+
+    #[test]
+    fn test_process_zip_file_extensionless_unknown_content_skipped() {
+        let zip_path = create_zip_file("README", b"This is a plain text file, not a ROM at all.")
+            .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let result = process_zip_file(zip_file, &zip_path.path);
+        assert!(matches!(result, Err(RomAnalyzerError::ArchiveError(_))));
+    }
+
+    #[test]
+    fn test_process_zip_file_empty_archive() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let zip_path = dir.path().join("empty.zip");
+        File::create(&zip_path)?;
+
+        match process_zip_file(File::open(&zip_path)?, "empty.zip") {
+            Err(RomAnalyzerError::ArchiveError(message)) => {
+                assert!(message.starts_with("Zip archive is empty or truncated"));
+                assert!(message.ends_with(": empty.zip"));
+            }
+            other => panic!("Expected ArchiveError, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_zip_file_truncated_archive() -> Result<(), RomAnalyzerError> {
+        let zip_bytes = build_zip_bytes(&[("game.nes", &[0x4E; 64])])?;
+        let dir = tempdir()?;
+        let zip_path = dir.path().join("truncated.zip");
+        std::fs::write(&zip_path, &zip_bytes[..zip_bytes.len() / 2])?;
+
+        let result = process_zip_file(File::open(&zip_path)?, "truncated.zip");
+        assert!(matches!(result, Err(RomAnalyzerError::ArchiveError(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_zip_file_all_no_supported_roms() {
+        let zip_path = create_zip_file("readme.txt", b"This is not a ROM.")
+            .expect("Failed to create test zip file");
+        let zip_file = File::open(&zip_path.path).expect("Failed to open zip for reading");
+
+        let result = process_zip_file_all(zip_file, &zip_path.path);
+        assert!(matches!(result, Err(RomAnalyzerError::ArchiveError(_))));
+    }
 }
@@ -0,0 +1,372 @@
+//! Provides header analysis functionality for Atari 7800 ROMs.
+//!
+//! Atari 7800 `.a78` ROMs start with a 128-byte header added by the dumping tools, which holds
+//! the cartridge title, its size, the bank-switching and sound hardware it uses, and whether it
+//! was made for NTSC or PAL consoles. The header has no region code, so the region is inferred
+//! from the filename, falling back to the TV type.
+//!
+//! A78 header documentation referenced here:
+//! <https://7800.8bitdev.org/index.php/A78_Header_Specification>
+
+use serde::Serialize;
+
+use crate::console::{RomMetadata, archive_source_field, field};
+use crate::error::RomAnalyzerError;
+use crate::region::{
+    Confidence, Region, VideoSystem, check_region_mismatch, infer_region_from_filename,
+};
+use crate::util::sanitize_title;
+
+pub(crate) const A78_HEADER_SIZE: usize = 0x80;
+pub(crate) const A78_SIGNATURE: &[u8] = b"ATARI7800";
+const VERSION_OFFSET: usize = 0x00;
+const SIGNATURE_OFFSET: usize = 0x01;
+const TITLE_START: usize = 0x11;
+const TITLE_END: usize = 0x31;
+// The size of the ROM data following the header, big-endian.
+const ROM_SIZE_START: usize = 0x31;
+const CART_TYPE_START: usize = 0x35;
+const TV_TYPE_OFFSET: usize = 0x39;
+const TV_TYPE_PAL_MASK: u8 = 0x01;
+const SAVE_DEVICE_OFFSET: usize = 0x3A;
+
+// Names of the cartridge type flags, from the lowest bit up.
+const CART_TYPE_FLAGS: &[&str] = &[
+    "POKEY at $4000",
+    "SuperGame bank switching",
+    "SuperGame RAM at $4000",
+    "ROM at $4000",
+    "Bank 6 at $4000",
+    "Banked RAM",
+    "POKEY at $0450",
+    "Mirror RAM at $4000",
+    "Activision bank switching",
+    "Absolute bank switching",
+    "POKEY at $0440",
+    "YM2151 at $0460",
+];
+
+/// Struct to hold the analysis results for an Atari 7800 ROM.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Atari7800Analysis {
+    /// The name of the source file.
+    pub source_name: String,
+    /// The identified region(s) as a region::Region bitmask.
+    pub region: Region,
+    /// The identified region name (e.g., "Europe").
+    pub region_string: String,
+    /// If the region implied by the TV type doesn't match the region in the filename.
+    pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The A78 header version.
+    pub header_version: u8,
+    /// The cartridge title from the header.
+    pub game_title: String,
+    /// The size of the ROM data following the header, as declared in the header.
+    pub rom_size: u32,
+    /// The cartridge type flags, see [`cart_type_features`].
+    pub cart_type: u16,
+    /// The TV type byte from the header.
+    pub tv_type: u8,
+    /// The video timing given by the TV type.
+    pub video_system: VideoSystem,
+    /// The save device byte from the header, see [`save_device_name`].
+    pub save_device: u8,
+}
+
+impl RomMetadata for Atari7800Analysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        let features = cart_type_features(self.cart_type);
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Atari 7800")),
+            Some(field("Game Title", &self.game_title)),
+            Some(field("ROM Size", format!("{} bytes", self.rom_size))),
+            (!features.is_empty()).then(|| field("Cart Type", features.join(", "))),
+            Some(field("Video", self.video_system)),
+            (self.save_device != 0)
+                .then(|| field("Save Device", save_device_name(self.save_device))),
+            Some(field("Region", self.region)),
+            (self.region_confidence == Confidence::FromHeader)
+                .then(|| field("Note", "Region inferred from the TV type.")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Determines the video system from the A78 TV type byte.
+///
+/// Bit 0 of the TV type selects PAL timing, the other bits describe the video output and are
+/// ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::atari7800::map_video_system;
+/// use rom_analyzer::region::VideoSystem;
+///
+/// assert_eq!(map_video_system(0x00), VideoSystem::Ntsc);
+/// assert_eq!(map_video_system(0x01), VideoSystem::Pal);
+/// assert_eq!(map_video_system(0x03), VideoSystem::Pal);
+/// ```
+pub fn map_video_system(tv_type: u8) -> VideoSystem {
+    if tv_type & TV_TYPE_PAL_MASK != 0 {
+        VideoSystem::Pal
+    } else {
+        VideoSystem::Ntsc
+    }
+}
+
+/// Returns the region a cartridge of the given video system was made for.
+///
+/// The 7800 was only released in North America (NTSC) and Europe (PAL).
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::atari7800::tv_region;
+/// use rom_analyzer::region::{Region, VideoSystem};
+///
+/// assert_eq!(tv_region(VideoSystem::Ntsc), Region::USA);
+/// assert_eq!(tv_region(VideoSystem::Pal), Region::EUROPE);
+/// ```
+pub fn tv_region(video_system: VideoSystem) -> Region {
+    match video_system {
+        VideoSystem::Pal => Region::EUROPE,
+        _ => Region::USA,
+    }
+}
+
+/// Lists the hardware named by the set bits of the A78 cartridge type.
+///
+/// Unnamed bits are ignored, so a plain cartridge without bank switching returns an empty `Vec`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::atari7800::cart_type_features;
+///
+/// assert_eq!(
+///     cart_type_features(0x0003),
+///     vec!["POKEY at $4000", "SuperGame bank switching"]
+/// );
+/// assert!(cart_type_features(0x0000).is_empty());
+/// ```
+pub fn cart_type_features(cart_type: u16) -> Vec<&'static str> {
+    CART_TYPE_FLAGS
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| cart_type & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Returns the name of the save device given by the A78 save device byte.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::atari7800::save_device_name;
+///
+/// assert_eq!(save_device_name(0x00), "None");
+/// assert_eq!(save_device_name(0x01), "High Score Cartridge");
+/// assert_eq!(save_device_name(0x02), "SaveKey / AtariVox");
+/// assert_eq!(save_device_name(0x7F), "Unknown");
+/// ```
+pub fn save_device_name(save_device: u8) -> &'static str {
+    match save_device {
+        0x00 => "None",
+        0x01 => "High Score Cartridge",
+        0x02 => "SaveKey / AtariVox",
+        _ => "Unknown",
+    }
+}
+
+/// Analyzes Atari 7800 ROM data.
+///
+/// This function reads the 128-byte A78 header to extract the cartridge title, declared ROM
+/// size, cartridge type flags, TV type and save device. The region is inferred from the
+/// `source_name`, and taken from the TV type (North America for NTSC, Europe for PAL) when the
+/// filename has none. A filename region that doesn't match the TV type is a region mismatch.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `source_name` - The name of the ROM file, used for region inference and mismatch checks.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`Atari7800Analysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to contain an A78 header, or
+///   [`RomAnalyzerError::InvalidSignature`] if the "ATARI7800" signature is missing.
+pub fn analyze_atari7800_data(
+    data: &[u8],
+    source_name: &str,
+) -> Result<Atari7800Analysis, RomAnalyzerError> {
+    if data.len() < A78_HEADER_SIZE {
+        return Err(RomAnalyzerError::DataTooSmall {
+            file_size: data.len(),
+            required_size: A78_HEADER_SIZE,
+            details: "A78 header".to_string(),
+        });
+    }
+
+    let signature = &data[SIGNATURE_OFFSET..SIGNATURE_OFFSET + A78_SIGNATURE.len()];
+    if signature != A78_SIGNATURE {
+        return Err(RomAnalyzerError::InvalidSignature {
+            expected: String::from_utf8_lossy(A78_SIGNATURE).to_string(),
+            found: signature.escape_ascii().to_string(),
+            offset: SIGNATURE_OFFSET,
+        });
+    }
+
+    let rom_size = u32::from_be_bytes([
+        data[ROM_SIZE_START],
+        data[ROM_SIZE_START + 1],
+        data[ROM_SIZE_START + 2],
+        data[ROM_SIZE_START + 3],
+    ]);
+    let cart_type = u16::from_be_bytes([data[CART_TYPE_START], data[CART_TYPE_START + 1]]);
+    let tv_type = data[TV_TYPE_OFFSET];
+    let video_system = map_video_system(tv_type);
+
+    let tv_region = tv_region(video_system);
+    let filename_region = infer_region_from_filename(source_name);
+    let (region, region_confidence) = if filename_region == Region::UNKNOWN {
+        (tv_region, Confidence::FromHeader)
+    } else {
+        (filename_region, Confidence::FromFilename)
+    };
+    let region_mismatch = check_region_mismatch(source_name, tv_region);
+
+    Ok(Atari7800Analysis {
+        source_name: source_name.to_string(),
+        region,
+        region_string: region.to_string(),
+        region_mismatch,
+        region_confidence,
+        archive_source: None,
+        header_version: data[VERSION_OFFSET],
+        game_title: sanitize_title(&data[TITLE_START..TITLE_END]),
+        rom_size,
+        cart_type,
+        tv_type,
+        video_system,
+        save_device: data[SAVE_DEVICE_OFFSET],
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Builds an A78 header followed by `rom_size` bytes of ROM data.
+    pub(crate) fn generate_a78_rom(title: &str, tv_type: u8, rom_size: u32) -> Vec<u8> {
+        let mut data = vec![0; A78_HEADER_SIZE + rom_size as usize];
+        data[VERSION_OFFSET] = 0x03;
+        data[SIGNATURE_OFFSET..SIGNATURE_OFFSET + A78_SIGNATURE.len()]
+            .copy_from_slice(A78_SIGNATURE);
+        data[TITLE_START..TITLE_START + title.len()].copy_from_slice(title.as_bytes());
+        data[ROM_SIZE_START..ROM_SIZE_START + 4].copy_from_slice(&rom_size.to_be_bytes());
+        data[TV_TYPE_OFFSET] = tv_type;
+        data
+    }
+
+    #[test]
+    fn test_analyze_atari7800_data_ntsc() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_a78_rom("Food Fight", 0x00, 0x8000);
+        data[CART_TYPE_START + 1] = 0x01;
+        data[SAVE_DEVICE_OFFSET] = 0x01;
+        let analysis = analyze_atari7800_data(&data, "Food Fight.a78")?;
+
+        assert_eq!(analysis.header_version, 0x03);
+        assert_eq!(analysis.game_title, "Food Fight");
+        assert_eq!(analysis.rom_size, 0x8000);
+        assert_eq!(analysis.cart_type, 0x0001);
+        assert_eq!(analysis.video_system, VideoSystem::Ntsc);
+        assert_eq!(analysis.region, Region::USA);
+        assert_eq!(analysis.region_confidence, Confidence::FromHeader);
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "Food Fight.a78\n\
+             System:       Atari 7800\n\
+             Game Title:   Food Fight\n\
+             ROM Size:     32768 bytes\n\
+             Cart Type:    POKEY at $4000\n\
+             Video:        NTSC\n\
+             Save Device:  High Score Cartridge\n\
+             Region:       USA\n\
+             Note:         Region inferred from the TV type."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_atari7800_data_pal() -> Result<(), RomAnalyzerError> {
+        let data = generate_a78_rom("Ballblazer", 0x01, 0x8000);
+        let analysis = analyze_atari7800_data(&data, "Ballblazer (Europe).a78")?;
+        assert_eq!(analysis.video_system, VideoSystem::Pal);
+        assert_eq!(analysis.region, Region::EUROPE);
+        assert_eq!(analysis.region_confidence, Confidence::FromFilename);
+        assert!(!analysis.region_mismatch);
+
+        // A PAL cartridge named for the USA is a mismatch.
+        let analysis = analyze_atari7800_data(&data, "Ballblazer (USA).a78")?;
+        assert_eq!(analysis.region, Region::USA);
+        assert!(analysis.region_mismatch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_atari7800_data_too_small() {
+        let data = generate_a78_rom("Game", 0x00, 0);
+        let result = analyze_atari7800_data(&data[..A78_HEADER_SIZE - 1], "game.a78");
+        assert!(matches!(
+            result,
+            Err(RomAnalyzerError::DataTooSmall {
+                required_size: A78_HEADER_SIZE,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_analyze_atari7800_data_bad_signature() {
+        let result = analyze_atari7800_data(&[0; A78_HEADER_SIZE], "game.a78");
+        assert!(matches!(
+            result,
+            Err(RomAnalyzerError::InvalidSignature { offset: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,277 @@
+//! Provides header analysis functionality for generic ISO9660 CD images.
+//!
+//! Discs that no console analyzer recognizes may still carry an ISO9660 filesystem. Its
+//! Primary Volume Descriptor (PVD) at sector 16 records the volume label and publisher, which
+//! identify the disc even when its system is unknown. The PVD has no region information, so the
+//! region is inferred from the filename.
+//!
+//! ISO9660 documentation referenced here:
+//! <https://wiki.osdev.org/ISO_9660#The_Primary_Volume_Descriptor>
+
+use serde::Serialize;
+
+use crate::console::{RomMetadata, archive_source_field, field};
+use crate::error::RomAnalyzerError;
+use crate::region::{Confidence, Region, check_region_mismatch, infer_region_from_filename};
+use crate::util::sanitize_title;
+
+// The PVD is the first volume descriptor, in sector 16.
+const PVD_SECTOR: usize = 16;
+const PVD_SIGNATURE: &[u8] = b"\x01CD001";
+// ISO images hold 2048-byte sectors. Raw images hold 2352-byte sectors, whose data follows a
+// 16-byte header in Mode 1 and a 24-byte header (with subheader) in Mode 2.
+const ISO_SECTOR_SIZE: usize = 0x800;
+const RAW_SECTOR_SIZE: usize = 0x930;
+const RAW_MODE1_DATA_OFFSET: usize = 0x10;
+const RAW_MODE2_DATA_OFFSET: usize = 0x18;
+// Field ranges relative to the start of the PVD.
+const SYSTEM_ID: (usize, usize) = (0x08, 0x28);
+const VOLUME_ID: (usize, usize) = (0x28, 0x48);
+const PUBLISHER_ID: (usize, usize) = (0x13E, 0x1BE);
+const PVD_SIZE: usize = PUBLISHER_ID.1;
+// The system identifier of PlayStation discs, which are left to the PSX analyzer.
+const PSX_SYSTEM_ID: &str = "PLAYSTATION";
+
+/// Struct to hold the analysis results for a generic ISO9660 CD image.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct CdImageAnalysis {
+    /// The name of the source file.
+    pub source_name: String,
+    /// The region(s) inferred from the filename as a region::Region bitmask.
+    pub region: Region,
+    /// The inferred region name (e.g., "Europe").
+    pub region_string: String,
+    /// If the region in the ROM header doesn't match the region in the filename.
+    pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The system identifier from the PVD, if set.
+    pub system_id: Option<String>,
+    /// The volume identifier from the PVD, if set.
+    pub volume_label: Option<String>,
+    /// The publisher identifier from the PVD, if set.
+    pub publisher: Option<String>,
+}
+
+impl RomMetadata for CdImageAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "ISO9660 CD image")),
+            self.system_id.as_ref().map(|id| field("System ID", id)),
+            self.volume_label
+                .as_ref()
+                .map(|label| field("Volume Label", label)),
+            self.publisher
+                .as_ref()
+                .map(|publisher| field("Publisher", publisher)),
+            Some(field("Region", self.region)),
+            Some(field(
+                "Note",
+                "Region information not in disc header, inferred from filename.",
+            )),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Helper function to locate the Primary Volume Descriptor of an ISO or raw CD image.
+///
+/// # Returns
+///
+/// The PVD bytes, or `None` if no PVD signature is found at sector 16.
+fn find_primary_volume_descriptor(data: &[u8]) -> Option<&[u8]> {
+    [
+        PVD_SECTOR * ISO_SECTOR_SIZE,
+        PVD_SECTOR * RAW_SECTOR_SIZE + RAW_MODE1_DATA_OFFSET,
+        PVD_SECTOR * RAW_SECTOR_SIZE + RAW_MODE2_DATA_OFFSET,
+    ]
+    .into_iter()
+    .filter_map(|offset| data.get(offset..offset + PVD_SIZE))
+    .find(|pvd| pvd.starts_with(PVD_SIGNATURE))
+}
+
+/// Reads a space-padded PVD text field, returning `None` if it is blank.
+fn pvd_field(pvd: &[u8], (start, end): (usize, usize)) -> Option<String> {
+    Some(sanitize_title(&pvd[start..end])).filter(|field| !field.is_empty())
+}
+
+/// Helper function to check whether a CD image should be analyzed as a generic ISO9660 image.
+///
+/// This is the case for images with a PVD that aren't PlayStation discs. Those are recognized
+/// by their system identifier, or by `has_psx_executable` when the caller found an executable
+/// prefix (see [`crate::console::psx`]).
+pub(crate) fn is_generic_cd_image(data: &[u8], has_psx_executable: bool) -> bool {
+    !has_psx_executable
+        && find_primary_volume_descriptor(data).is_some_and(|pvd| {
+            pvd_field(pvd, SYSTEM_ID).is_none_or(|system_id| system_id != PSX_SYSTEM_ID)
+        })
+}
+
+/// Analyzes a generic ISO9660 CD image.
+///
+/// This function reads the system identifier, volume label and publisher from the Primary
+/// Volume Descriptor at sector 16 of an ISO (2048-byte sector) or raw (2352-byte sector)
+/// image. As the PVD has no region information, the region is inferred from the `source_name`.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw disc image data.
+/// * `source_name` - The name of the image file, used for region inference.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`CdImageAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError::InvalidHeader`]) if the image has no Primary Volume Descriptor.
+pub fn analyze_cd_image_data(
+    data: &[u8],
+    source_name: &str,
+) -> Result<CdImageAnalysis, RomAnalyzerError> {
+    let pvd = find_primary_volume_descriptor(data).ok_or_else(|| {
+        RomAnalyzerError::InvalidHeader("No ISO9660 Primary Volume Descriptor found.".to_string())
+    })?;
+
+    let region = infer_region_from_filename(source_name);
+    let region_mismatch = check_region_mismatch(source_name, region);
+
+    Ok(CdImageAnalysis {
+        source_name: source_name.to_string(),
+        region,
+        region_string: region.to_string(),
+        region_mismatch,
+        region_confidence: Confidence::from_filename(region),
+        archive_source: None,
+        system_id: pvd_field(pvd, SYSTEM_ID),
+        volume_label: pvd_field(pvd, VOLUME_ID),
+        publisher: pvd_field(pvd, PUBLISHER_ID),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Builds an image with a PVD holding the given identifiers at `pvd_offset`.
+    pub(crate) fn generate_cd_image(
+        pvd_offset: usize,
+        system_id: &str,
+        volume_label: &str,
+        publisher: &str,
+    ) -> Vec<u8> {
+        let mut data = vec![0; pvd_offset + ISO_SECTOR_SIZE];
+        let pvd = &mut data[pvd_offset..];
+        pvd[..PVD_SIGNATURE.len()].copy_from_slice(PVD_SIGNATURE);
+        for ((start, end), value) in [
+            (SYSTEM_ID, system_id),
+            (VOLUME_ID, volume_label),
+            (PUBLISHER_ID, publisher),
+        ] {
+            let mut field = value.as_bytes().to_vec();
+            field.resize(end - start, b' ');
+            pvd[start..end].copy_from_slice(&field);
+        }
+        data
+    }
+
+    #[test]
+    fn test_analyze_cd_image_data() -> Result<(), RomAnalyzerError> {
+        let data = generate_cd_image(
+            PVD_SECTOR * ISO_SECTOR_SIZE,
+            "WIN32",
+            "MY_DISC",
+            "SOME PUBLISHER",
+        );
+        let analysis = analyze_cd_image_data(&data, "My Disc (Europe).iso")?;
+
+        assert_eq!(analysis.system_id.as_deref(), Some("WIN32"));
+        assert_eq!(analysis.volume_label.as_deref(), Some("MY_DISC"));
+        assert_eq!(analysis.publisher.as_deref(), Some("SOME PUBLISHER"));
+        assert_eq!(analysis.region, Region::EUROPE);
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "My Disc (Europe).iso\n\
+             System:       ISO9660 CD image\n\
+             System ID:    WIN32\n\
+             Volume Label: MY_DISC\n\
+             Publisher:    SOME PUBLISHER\n\
+             Region:       Europe\n\
+             Note:         Region information not in disc header, inferred from filename."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_cd_image_data_raw_sectors() -> Result<(), RomAnalyzerError> {
+        for offset in [RAW_MODE1_DATA_OFFSET, RAW_MODE2_DATA_OFFSET] {
+            let data = generate_cd_image(PVD_SECTOR * RAW_SECTOR_SIZE + offset, "", "RAW", "");
+            let analysis = analyze_cd_image_data(&data, "disc.bin")?;
+            assert_eq!(analysis.system_id, None);
+            assert_eq!(analysis.volume_label.as_deref(), Some("RAW"));
+            assert_eq!(analysis.publisher, None);
+            assert_eq!(
+                analysis.print(),
+                "disc.bin\n\
+                 System:       ISO9660 CD image\n\
+                 Volume Label: RAW\n\
+                 Region:       Unknown\n\
+                 Note:         Region information not in disc header, inferred from filename."
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_cd_image_data_no_pvd() {
+        let result = analyze_cd_image_data(&[0; 0x10000], "disc.iso");
+        assert!(matches!(result, Err(RomAnalyzerError::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn test_is_generic_cd_image() {
+        let offset = PVD_SECTOR * ISO_SECTOR_SIZE;
+        assert!(is_generic_cd_image(
+            &generate_cd_image(offset, "", "DISC", ""),
+            false
+        ));
+        // PlayStation discs are left to the PSX analyzer.
+        assert!(!is_generic_cd_image(
+            &generate_cd_image(offset, "PLAYSTATION", "DISC", ""),
+            false
+        ));
+        assert!(!is_generic_cd_image(
+            &generate_cd_image(offset, "", "DISC", ""),
+            true
+        ));
+        assert!(!is_generic_cd_image(&[0; 0x10000], false));
+    }
+}
@@ -0,0 +1,325 @@
+//! Provides header analysis functionality for Nintendo GameCube and Wii discs.
+//!
+//! Both consoles start their discs with the same header: a six-character game ID (game code
+//! followed by the maker code), the disc number and version, a magic word telling the two
+//! consoles apart and the game title. The region is derived from the 4th character of the game
+//! ID. Only this header is read, so the data can come from a plain `.iso` image or the disc
+//! header copy stored in a WIA/RVZ container (see [`crate::archive::rvz`]).
+//!
+//! Disc header documentation referenced here:
+//! <https://wiibrew.org/wiki/Wii_disc#Header>
+
+use serde::Serialize;
+
+use crate::console::{RomMetadata, archive_source_field, field, region_unknown_field};
+use crate::error::RomAnalyzerError;
+use crate::publisher::publisher_name;
+use crate::region::{Confidence, Region, check_region_mismatch};
+use crate::util::sanitize_title;
+
+// The disc header fields needed for analysis end with the 64-byte title at 0x20. The full
+// title field runs to 0x400, but real titles fit well within the first 0x60 bytes.
+pub(crate) const HEADER_SIZE: usize = 0x80;
+
+const GAME_ID_LENGTH: usize = 6;
+const DISC_NUMBER_OFFSET: usize = 0x06;
+const VERSION_OFFSET: usize = 0x07;
+const WII_MAGIC_OFFSET: usize = 0x18;
+const GAMECUBE_MAGIC_OFFSET: usize = 0x1C;
+const WII_MAGIC: u32 = 0x5D1C_9EA3;
+const GAMECUBE_MAGIC: u32 = 0xC233_9F3D;
+const TITLE_START: usize = 0x20;
+
+// The 4th character of the game ID holds the destination region.
+const REGION_CHAR_OFFSET: usize = 0x03;
+
+/// Struct to hold the analysis results for a GameCube or Wii disc.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct GameCubeAnalysis {
+    /// The name of the source file.
+    pub source_name: String,
+    /// The identified region(s) as a region::Region bitmask.
+    pub region: Region,
+    /// The identified region name (e.g., "USA").
+    pub region_string: String,
+    /// If the region in the disc header doesn't match the region in the filename.
+    pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP, CHD, CSO or RVZ) the disc was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region character, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
+    /// If the disc carries the Wii magic word rather than the GameCube one.
+    pub is_wii: bool,
+    /// The six-character game ID (e.g., "GALE01").
+    pub game_id: String,
+    /// The maker code, the last two characters of the game ID.
+    pub maker_code: String,
+    /// The publisher name for the maker code, if it is a known publisher.
+    pub publisher: Option<String>,
+    /// The disc number of multi-disc games, starting at 0.
+    pub disc_number: u8,
+    /// The disc version.
+    pub version: u8,
+    /// The game title from the disc header.
+    pub game_title: String,
+}
+
+impl RomMetadata for GameCubeAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        let system = if self.is_wii {
+            "Nintendo Wii"
+        } else {
+            "Nintendo GameCube"
+        };
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", system)),
+            Some(field("Game Title", &self.game_title)),
+            Some(field("Game ID", &self.game_id)),
+            Some(field("Maker Code", &self.maker_code)),
+            self.publisher
+                .as_ref()
+                .map(|publisher| field("Publisher", publisher)),
+            (self.disc_number > 0).then(|| field("Disc", self.disc_number + 1)),
+            Some(field("Version", self.version)),
+            Some(field("Region", self.region)),
+            region_unknown_field(self.region_confidence),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Determines the GameCube or Wii game region from the 4th character of the game ID.
+///
+/// # Arguments
+///
+/// * `region_char` - The 4th character of the game ID, as an ASCII byte.
+///
+/// # Returns
+///
+/// A tuple containing:
+/// - A `&'static str` representing the region (e.g., "USA", "Japan", "Germany") or "Unknown"
+///   if the region character is not recognized.
+/// - A [`Region`] bitmask representing the region(s) associated with the character.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::gamecube::map_region;
+/// use rom_analyzer::region::Region;
+///
+/// assert_eq!(map_region(b'E'), ("USA", Region::USA));
+/// assert_eq!(map_region(b'P'), ("Europe", Region::EUROPE));
+/// assert_eq!(map_region(b'J'), ("Japan", Region::JAPAN));
+/// assert_eq!(map_region(b'Z'), ("Unknown", Region::UNKNOWN));
+/// ```
+pub fn map_region(region_char: u8) -> (&'static str, Region) {
+    match region_char {
+        b'E' => ("USA", Region::USA),
+        b'J' => ("Japan", Region::JAPAN),
+        b'P' => ("Europe", Region::EUROPE),
+        b'D' => ("Germany", Region::EUROPE),
+        b'F' => ("France", Region::EUROPE),
+        b'I' => ("Italy", Region::EUROPE),
+        b'S' => ("Spain", Region::EUROPE),
+        b'U' => ("Australia", Region::EUROPE),
+        b'X' | b'Y' => ("Europe", Region::EUROPE),
+        b'K' => ("Korea", Region::KOREA),
+        _ => ("Unknown", Region::UNKNOWN),
+    }
+}
+
+fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Helper function to check whether data starts with a GameCube or Wii disc header.
+pub(crate) fn is_gamecube_disc(data: &[u8]) -> bool {
+    read_u32_be(data, GAMECUBE_MAGIC_OFFSET) == Some(GAMECUBE_MAGIC)
+        || read_u32_be(data, WII_MAGIC_OFFSET) == Some(WII_MAGIC)
+}
+
+/// Analyzes the disc header of a Nintendo GameCube or Wii disc.
+///
+/// This function checks the header for the GameCube or Wii magic word, then reads the game ID,
+/// disc number, version and title. The region is derived from the 4th character of the game ID,
+/// and the publisher from the maker code in its last two characters.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) starting with the disc header (e.g., from a `.iso` file).
+/// * `source_name` - The name of the disc image, used for region mismatch checks.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`GameCubeAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError::DataTooSmall`]) if the data is shorter than the disc header.
+/// - `Err`([`RomAnalyzerError::InvalidHeader`]) if neither magic word is present.
+pub fn analyze_gamecube_data(
+    data: &[u8],
+    source_name: &str,
+) -> Result<GameCubeAnalysis, RomAnalyzerError> {
+    if data.len() < HEADER_SIZE {
+        return Err(RomAnalyzerError::DataTooSmall {
+            file_size: data.len(),
+            required_size: HEADER_SIZE,
+            details: "GameCube disc header".to_string(),
+        });
+    }
+    if !is_gamecube_disc(data) {
+        return Err(RomAnalyzerError::InvalidHeader(
+            "No GameCube or Wii disc magic found".to_string(),
+        ));
+    }
+
+    let game_id = String::from_utf8_lossy(&data[..GAME_ID_LENGTH])
+        .trim_matches(char::from(0))
+        .to_string();
+    let maker_code = game_id.get(4..).unwrap_or_default().to_string();
+    let publisher = publisher_name(&maker_code).map(str::to_string);
+
+    let (region_name, region) = map_region(data[REGION_CHAR_OFFSET]);
+    let raw_region_info =
+        (region == Region::UNKNOWN).then_some((REGION_CHAR_OFFSET, data[REGION_CHAR_OFFSET]));
+    let region_mismatch = check_region_mismatch(source_name, region);
+
+    Ok(GameCubeAnalysis {
+        source_name: source_name.to_string(),
+        region,
+        region_string: region_name.to_string(),
+        region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
+        raw_region_info,
+        is_wii: read_u32_be(data, WII_MAGIC_OFFSET) == Some(WII_MAGIC),
+        game_id,
+        maker_code,
+        publisher,
+        disc_number: data[DISC_NUMBER_OFFSET],
+        version: data[VERSION_OFFSET],
+        game_title: sanitize_title(&data[TITLE_START..HEADER_SIZE]),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Builds a minimal GameCube disc header with the given game ID and title.
+    pub(crate) fn generate_gamecube_header(game_id: &str, title: &str) -> Vec<u8> {
+        let mut data = vec![0; HEADER_SIZE];
+        data[..game_id.len()].copy_from_slice(game_id.as_bytes());
+        data[GAMECUBE_MAGIC_OFFSET..GAMECUBE_MAGIC_OFFSET + 4]
+            .copy_from_slice(&GAMECUBE_MAGIC.to_be_bytes());
+        data[TITLE_START..TITLE_START + title.len()].copy_from_slice(title.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_analyze_gamecube_data() -> Result<(), RomAnalyzerError> {
+        let data = generate_gamecube_header("GALE01", "Super Smash Bros Melee");
+        let analysis = analyze_gamecube_data(&data, "melee.iso")?;
+
+        assert_eq!(analysis.game_id, "GALE01");
+        assert_eq!(analysis.maker_code, "01");
+        assert_eq!(analysis.publisher.as_deref(), Some("Nintendo"));
+        assert_eq!(analysis.game_title, "Super Smash Bros Melee");
+        assert_eq!(analysis.region, Region::USA);
+        assert_eq!(analysis.region_string, "USA");
+        assert_eq!(analysis.region_confidence, Confidence::FromHeader);
+        assert!(!analysis.is_wii);
+        assert_eq!(analysis.raw_region_info, None);
+        assert_eq!(
+            analysis.print(),
+            "melee.iso\n\
+             System:       Nintendo GameCube\n\
+             Game Title:   Super Smash Bros Melee\n\
+             Game ID:      GALE01\n\
+             Maker Code:   01\n\
+             Publisher:    Nintendo\n\
+             Version:      0\n\
+             Region:       USA"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gamecube_data_regions() -> Result<(), RomAnalyzerError> {
+        for (game_id, region) in [
+            ("GALP01", Region::EUROPE),
+            ("GALJ01", Region::JAPAN),
+            ("GALK01", Region::KOREA),
+        ] {
+            let data = generate_gamecube_header(game_id, "Game");
+            assert_eq!(analyze_gamecube_data(&data, "game.iso")?.region, region);
+        }
+
+        let data = generate_gamecube_header("GALZ01", "Game");
+        let analysis = analyze_gamecube_data(&data, "game.iso")?;
+        assert_eq!(analysis.region, Region::UNKNOWN);
+        assert_eq!(analysis.raw_region_info, Some((0x03, b'Z')));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gamecube_data_wii() -> Result<(), RomAnalyzerError> {
+        let mut data = vec![0; HEADER_SIZE];
+        data[..6].copy_from_slice(b"RSBE01");
+        data[WII_MAGIC_OFFSET..WII_MAGIC_OFFSET + 4].copy_from_slice(&WII_MAGIC.to_be_bytes());
+        data[DISC_NUMBER_OFFSET] = 1;
+        let analysis = analyze_gamecube_data(&data, "brawl.iso")?;
+
+        assert!(analysis.is_wii);
+        assert_eq!(analysis.disc_number, 1);
+        assert!(analysis.print().contains("System:       Nintendo Wii"));
+        assert!(analysis.print().contains("Disc:         2"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gamecube_data_invalid() {
+        let data = vec![0; HEADER_SIZE];
+        assert!(matches!(
+            analyze_gamecube_data(&data, "game.iso"),
+            Err(RomAnalyzerError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            analyze_gamecube_data(&data[..0x40], "game.iso"),
+            Err(RomAnalyzerError::DataTooSmall { .. })
+        ));
+    }
+}
@@ -1,7 +1,7 @@
 //! Provides header analysis functionality for Sega Game Gear ROMs.
 //!
-//! This module can parse Game Gear ROM headers to extract region information
-//! and compare it with region inferences made from the filename.
+//! This module can parse Game Gear ROM headers to extract region information, the product code
+//! and version, and compare the region with region inferences made from the filename.
 //!
 //! Game Gear header documentation referenced here:
 //! <https://www.smspower.org/Development/ROMHeader>
@@ -10,11 +10,35 @@ use log::debug;
 use serde::Serialize;
 
 use crate::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch, infer_region_from_filename};
+use crate::console::{RomMetadata, archive_source_field, field};
+use crate::region::{Confidence, Region, check_region_mismatch, infer_region_from_filename};
 
 const POSSIBLE_HEADER_STARTS: &[usize] = &[0x7ff0, 0x3ff0, 0x1ff0];
-const REGION_CODE_OFFSET: usize = 0xf;
-const SEGA_HEADER_SIGNATURE: &[u8] = b"TMR SEGA";
+pub(crate) const REGION_CODE_OFFSET: usize = 0xf;
+// The BCD product code, relative to the header start. The last 4 digits are stored little-endian
+// in the first two bytes, and any leading digits in the upper nibble of the third byte.
+const PRODUCT_CODE_OFFSET: usize = 0xc;
+// The version is stored in the lower nibble of this byte, relative to the header start.
+const VERSION_OFFSET: usize = 0xe;
+pub(crate) const SEGA_HEADER_SIGNATURE: &[u8] = b"TMR SEGA";
+
+/// Helper function to locate the 'TMR SEGA' header shared by Sega 8-bit ROMs.
+///
+/// The header can exist at one of three locations (0x7ff0, 0x3ff0 or 0x1ff0), depending on the
+/// size of the ROM. They are searched in that order.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+///
+/// # Returns
+///
+/// The offset of the header, or `None` if the signature isn't found at any location.
+pub(crate) fn find_sega_header(data: &[u8]) -> Option<usize> {
+    POSSIBLE_HEADER_STARTS.iter().copied().find(|&offset| {
+        data.get(offset..offset + SEGA_HEADER_SIGNATURE.len()) == Some(SEGA_HEADER_SIGNATURE)
+    })
+}
 
 /// Struct to hold the analysis results for a Game Gear ROM.
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -27,25 +51,66 @@ pub struct GameGearAnalysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
-    /// If the region is found in the header, or inferred from the filename.
-    pub region_found: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region byte, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
+    /// The product code identifying the title, decoded from BCD, if a valid one is in the header.
+    pub product_code: Option<String>,
+    /// The version of the title, if the header contains one.
+    pub version: Option<u8>,
 }
 
-impl GameGearAnalysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        let region_not_in_rom_header = if !self.region_found {
-            "\nNote:         Region information not in ROM header, inferred from filename."
-        } else {
-            ""
-        };
-        format!(
-            "{}\n\
-             System:       Sega Game Gear\n\
-             Region:       {}\
-             {}",
-            self.source_name, self.region, region_not_in_rom_header
-        )
+impl RomMetadata for GameGearAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Sega Game Gear")),
+            Some(field("Region", self.region)),
+            self.product_code
+                .as_ref()
+                .map(|code| field("Product Code", code)),
+            self.version.map(|version| field("Version", version)),
+            (self.region_confidence != Confidence::FromHeader).then(|| {
+                field(
+                    "Note",
+                    "Region information not in ROM header, inferred from filename.",
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -95,6 +160,35 @@ pub fn map_region(region_byte: u8) -> (&'static str, Region) {
     }
 }
 
+/// Decodes the BCD product code stored in a 'TMR SEGA' header.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `header_start` - The offset of the header within the ROM data.
+///
+/// # Returns
+///
+/// The product code as a decimal string (e.g. "2500"), or `None` if the bytes are out of bounds
+/// or are not valid BCD.
+fn decode_product_code(data: &[u8], header_start: usize) -> Option<String> {
+    let start = header_start + PRODUCT_CODE_OFFSET;
+    let bytes = data.get(start..start + 3)?;
+    let is_bcd = |byte: u8| byte >> 4 <= 9 && byte & 0x0f <= 9;
+    if !is_bcd(bytes[0]) || !is_bcd(bytes[1]) {
+        return None;
+    }
+
+    // The leading digits are a plain value between 0 and 15, rather than a BCD digit.
+    let leading = bytes[2] >> 4;
+    let prefix = if leading > 0 {
+        leading.to_string()
+    } else {
+        String::new()
+    };
+    Some(format!("{}{:02X}{:02X}", prefix, bytes[1], bytes[0]))
+}
+
 /// Analyzes a Game Gear ROM and returns a struct containing the analysis results.
 ///
 /// This function attempts to locate the 'TMR SEGA' header signature within the ROM data at
@@ -103,7 +197,7 @@ pub fn map_region(region_byte: u8) -> (&'static str, Region) {
 /// the region from the `source_name`.
 ///
 /// If a region is found in the header it also checks for mismatches between the inferred and
-/// header-derived regions.
+/// header-derived regions. When the header is found, its product code and version are decoded too.
 ///
 /// # Arguments
 ///
@@ -121,13 +215,12 @@ pub fn analyze_gamegear_data(
 ) -> Result<GameGearAnalysis, RomAnalyzerError> {
     // All headered Sega 8-bit ROMs should begin with 'TMR SEGA'
     // This can exist at one of three locations; 0x1ff0, 0x3ff0 or 0x7ff0
-    let header_start_opt = POSSIBLE_HEADER_STARTS.iter().copied().find(|&offset| {
-        data.get(offset..offset + SEGA_HEADER_SIGNATURE.len()) == Some(SEGA_HEADER_SIGNATURE)
-    });
+    let header_start_opt = find_sega_header(data);
 
     let mut region = Region::UNKNOWN;
     let mut region_name = "Unknown".to_string();
     let mut region_found = false;
+    let mut raw_region_info = None;
 
     if let Some(header_start) = header_start_opt {
         debug!("Found signature at 0x{:x}", header_start);
@@ -137,6 +230,8 @@ pub fn analyze_gamegear_data(
             region = region_val;
             if region != Region::UNKNOWN {
                 region_found = true;
+            } else {
+                raw_region_info = Some((header_start + REGION_CODE_OFFSET, region_byte));
             }
         } else {
             debug!(
@@ -146,10 +241,20 @@ pub fn analyze_gamegear_data(
         }
     }
 
-    if !region_found {
+    let region_confidence = if region_found {
+        Confidence::FromHeader
+    } else {
         region = infer_region_from_filename(source_name);
         region_name = region.to_string();
-    }
+        Confidence::from_filename(region)
+    };
+
+    let product_code =
+        header_start_opt.and_then(|header_start| decode_product_code(data, header_start));
+    let version = header_start_opt.and_then(|header_start| {
+        data.get(header_start + VERSION_OFFSET)
+            .map(|&byte| byte & 0x0f)
+    });
 
     let region_mismatch = check_region_mismatch(source_name, region);
 
@@ -158,7 +263,11 @@ pub fn analyze_gamegear_data(
         region,
         region_string: region_name.to_string(),
         region_mismatch,
-        region_found,
+        region_confidence,
+        archive_source: None,
+        raw_region_info,
+        product_code,
+        version,
     })
 }
 
@@ -192,7 +301,7 @@ mod tests {
         assert_eq!(analysis.source_name, "my_game_usa.gg");
         assert_eq!(analysis.region, Region::USA);
         assert_eq!(analysis.region_string, "USA");
-        assert!(!analysis.region_found); // Region should be inferred, not found in header
+        assert_eq!(analysis.region_confidence, Confidence::FromFilename);
         Ok(())
     }
 
@@ -204,12 +313,14 @@ mod tests {
         assert_eq!(analysis.source_name, "test_rom.gg");
         assert_eq!(analysis.region, Region::JAPAN);
         assert_eq!(analysis.region_string, "GameGear Japan");
-        assert!(analysis.region_found);
+        assert_eq!(analysis.region_confidence, Confidence::FromHeader);
         assert_eq!(
             analysis.print(),
             "test_rom.gg\n\
              System:       Sega Game Gear\n\
-             Region:       Japan"
+             Region:       Japan\n\
+             Product Code: 0000\n\
+             Version:      0"
         );
         Ok(())
     }
@@ -222,12 +333,14 @@ mod tests {
         assert_eq!(analysis.source_name, "test_rom.gg");
         assert_eq!(analysis.region, Region::USA | Region::EUROPE);
         assert_eq!(analysis.region_string, "GameGear Export");
-        assert!(analysis.region_found);
+        assert_eq!(analysis.region_confidence, Confidence::FromHeader);
         assert_eq!(
             analysis.print(),
             "test_rom.gg\n\
              System:       Sega Game Gear\n\
-             Region:       USA/Europe"
+             Region:       USA/Europe\n\
+             Product Code: 0000\n\
+             Version:      0"
         );
         Ok(())
     }
@@ -240,7 +353,7 @@ mod tests {
         assert_eq!(analysis.source_name, "test_rom.gg");
         assert_eq!(analysis.region, Region::USA | Region::EUROPE);
         assert_eq!(analysis.region_string, "GameGear International");
-        assert!(analysis.region_found);
+        assert_eq!(analysis.region_confidence, Confidence::FromHeader);
         Ok(())
     }
 
@@ -251,7 +364,7 @@ mod tests {
         assert_eq!(analysis.source_name, "my_game_usa.gg");
         assert_eq!(analysis.region, Region::USA);
         assert_eq!(analysis.region_string, "USA");
-        assert!(!analysis.region_found);
+        assert_eq!(analysis.region_confidence, Confidence::FromFilename);
         assert_eq!(
             analysis.print(),
             "my_game_usa.gg\n\
@@ -271,7 +384,54 @@ mod tests {
         assert_eq!(analysis.source_name, "my_game_japan.gg");
         assert_eq!(analysis.region, Region::JAPAN);
         assert_eq!(analysis.region_string, "Japan");
-        assert!(!analysis.region_found); // Still false because the header didn't provide a known region
+        assert_eq!(analysis.region_confidence, Confidence::FromFilename);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gamegear_data_product_code_and_version() -> Result<(), RomAnalyzerError> {
+        let mut data = create_rom_data_with_header(0x7ff0, 0x70);
+        // Product code 12345, version 2.
+        data[0x7ffc] = 0x45;
+        data[0x7ffd] = 0x23;
+        data[0x7ffe] = 0x12;
+        let analysis = analyze_gamegear_data(&data, "test_rom.gg")?;
+        assert_eq!(analysis.product_code, Some("12345".to_string()));
+        assert_eq!(analysis.version, Some(2));
+        assert_eq!(
+            analysis.print(),
+            "test_rom.gg\n\
+             System:       Sega Game Gear\n\
+             Region:       USA/Europe\n\
+             Product Code: 12345\n\
+             Version:      2"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gamegear_data_product_code_not_bcd() -> Result<(), RomAnalyzerError> {
+        let mut data = create_rom_data_with_header(0x7ff0, 0x70);
+        data[0x7ffc] = 0xAB;
+        let analysis = analyze_gamegear_data(&data, "test_rom.gg")?;
+        assert_eq!(analysis.product_code, None);
+        assert_eq!(analysis.version, Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gamegear_data_product_code_absent() -> Result<(), RomAnalyzerError> {
+        // Without a header, or with a header truncated before these bytes, neither is decoded.
+        let analysis = analyze_gamegear_data(&vec![0; 0x8000], "test_rom.gg")?;
+        assert_eq!(analysis.product_code, None);
+        assert_eq!(analysis.version, None);
+
+        let header_start = 0x7ff0;
+        let mut data = vec![0; header_start + SEGA_HEADER_SIGNATURE.len()];
+        data[header_start..].copy_from_slice(SEGA_HEADER_SIGNATURE);
+        let analysis = analyze_gamegear_data(&data, "test_rom.gg")?;
+        assert_eq!(analysis.product_code, None);
+        assert_eq!(analysis.version, None);
         Ok(())
     }
 
@@ -8,14 +8,32 @@
 
 use serde::Serialize;
 
+use crate::console::{
+    RomMetadata, archive_source_field, field, region_unknown_field, size_mismatch_field,
+};
 use crate::error::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch};
+use crate::region::{Confidence, Region, check_region_mismatch};
+use crate::util::sanitize_title;
+
+// The Game Boy header is located at offset 0x100.
+// The relevant information for region and system type are within the first 0x150 bytes.
+pub(crate) const HEADER_SIZE: usize = 0x150;
 
 const GB_TITLE_START: usize = 0x134;
 const GB_TITLE_END: usize = 0x143;
 const GB_DESTINATION: usize = 0x14A;
+const GB_SGB_FLAG: usize = 0x146;
+const GB_OLD_LICENSEE: usize = 0x14B;
+const GB_ROM_SIZE: usize = 0x148;
+
+// The SGB flag value enabling Super Game Boy functions, which the SGB BIOS only honours when
+// the old licensee code defers to the new one.
+const SGB_FUNCTIONS: u8 = 0x03;
+const USE_NEW_LICENSEE: u8 = 0x33;
 
 const GBC_SYSTEM_TYPE: usize = 0x143;
+const GBC_ENHANCED: u8 = 0x80;
+const GBC_EXCLUSIVE: u8 = 0xC0;
 const GBC_TITLE_END: usize = 0x13F;
 
 /// Struct to hold the analysis results for a Game Boy ROM.
@@ -29,25 +47,75 @@ pub struct GbAnalysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
-    /// The identified system type (e.g., "Game Boy (GB)" or "Game Boy Color (GBC)").
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region byte, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
+    /// The identified system type ("Game Boy (GB)", "Game Boy Color (enhanced)" or
+    /// "Game Boy Color (exclusive)").
     pub system_type: String,
     /// The game title extracted from the ROM header.
     pub game_title: String,
     /// The raw destination code byte.
     pub destination_code: u8,
+    /// If the cartridge supports Super Game Boy functions.
+    pub sgb_support: bool,
+    /// The ROM size declared in the header, if it holds a known size code.
+    pub declared_size: Option<usize>,
+    /// The size of the ROM data.
+    pub rom_size: usize,
+    /// If the ROM size doesn't match the declared size, as for overdumped or truncated ROMs.
+    pub size_mismatch: bool,
 }
 
-impl GbAnalysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        format!(
-            "{}\n\
-             System:       {}\n\
-             Game Title:   {}\n\
-             Region Code:  0x{:02X}\n\
-             Region:       {}",
-            self.source_name, self.system_type, self.game_title, self.destination_code, self.region
-        )
+impl RomMetadata for GbAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", &self.system_type)),
+            self.sgb_support.then(|| field("Features", "SGB Enhanced")),
+            Some(field("Game Title", &self.game_title)),
+            Some(field(
+                "Region Code",
+                format!("0x{:02X}", self.destination_code),
+            )),
+            Some(field("Region", self.region)),
+            region_unknown_field(self.region_confidence),
+            size_mismatch_field(self.size_mismatch, self.declared_size, self.rom_size),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -93,11 +161,39 @@ pub fn map_region(region_byte: u8) -> (&'static str, Region) {
     }
 }
 
+/// Determines the ROM size declared by the ROM size byte of a Game Boy header.
+///
+/// # Returns
+///
+/// The size in bytes, or `None` if the byte is not a known size code.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::gb::map_rom_size;
+///
+/// assert_eq!(map_rom_size(0x00), Some(32 * 1024));
+/// assert_eq!(map_rom_size(0x05), Some(1024 * 1024));
+/// assert_eq!(map_rom_size(0xFF), None);
+/// ```
+pub fn map_rom_size(rom_size_byte: u8) -> Option<usize> {
+    match rom_size_byte {
+        0x00..=0x08 => Some(0x8000 << rom_size_byte),
+        // Sizes of 72, 80 and 96 banks of 16KB.
+        0x52 => Some(0x12_0000),
+        0x53 => Some(0x14_0000),
+        0x54 => Some(0x18_0000),
+        _ => None,
+    }
+}
+
 /// Analyzes Game Boy (GB) and Game Boy Color (GBC) ROM data.
 ///
 /// This function reads the ROM header to determine the system type (GB or GBC),
 /// extract the game title and identify the destination code which indicates the region.
-/// It also performs a region mismatch check against the `source_name`.
+/// Super Game Boy support is detected from the SGB flag, which only counts when the old
+/// licensee code is 0x33. It also performs a region mismatch check against the `source_name`,
+/// and compares the declared ROM size to the length of `data` to flag overdumps and truncations.
 ///
 /// # Arguments
 ///
@@ -110,9 +206,6 @@ pub fn map_region(region_byte: u8) -> (&'static str, Region) {
 /// - `Ok`([`GbAnalysis`]) containing the detailed analysis results.
 /// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to contain a valid header.
 pub fn analyze_gb_data(data: &[u8], source_name: &str) -> Result<GbAnalysis, RomAnalyzerError> {
-    // The Game Boy header is located at offset 0x100.
-    // The relevant information for region and system type are within the first 0x150 bytes.
-    const HEADER_SIZE: usize = 0x150;
     if data.len() < HEADER_SIZE {
         return Err(RomAnalyzerError::DataTooSmall {
             file_size: data.len(),
@@ -122,24 +215,24 @@ pub fn analyze_gb_data(data: &[u8], source_name: &str) -> Result<GbAnalysis, Rom
     }
 
     // System type is determined by a specific byte in the header.
-    // 0x80 or 0xC0 indicates GBC
-    let system_type = if data[GBC_SYSTEM_TYPE] == 0x80 || data[GBC_SYSTEM_TYPE] == 0xC0 {
-        "Game Boy Color (GBC)"
-    } else {
-        "Game Boy (GB)"
-    };
-
-    let title_end = if system_type == "Game Boy Color (GBC)" {
-        GBC_TITLE_END
-    } else {
-        GB_TITLE_END
+    // 0x80 marks a GBC-enhanced cartridge that still runs on the original Game Boy, while 0xC0
+    // marks a GBC-exclusive cartridge. Both use the shorter 11 character GBC title.
+    let (system_type, title_end) = match data[GBC_SYSTEM_TYPE] {
+        GBC_ENHANCED => ("Game Boy Color (enhanced)", GBC_TITLE_END),
+        GBC_EXCLUSIVE => ("Game Boy Color (exclusive)", GBC_TITLE_END),
+        _ => ("Game Boy (GB)", GB_TITLE_END),
     };
-    let game_title = String::from_utf8_lossy(&data[GB_TITLE_START..title_end])
-        .trim_matches(char::from(0))
-        .to_string();
+    let game_title = sanitize_title(&data[GB_TITLE_START..title_end]);
 
     let destination_code = data[GB_DESTINATION];
     let (region_name, region) = map_region(destination_code);
+    let raw_region_info = (region == Region::UNKNOWN).then_some((GB_DESTINATION, destination_code));
+
+    let sgb_support =
+        data[GB_SGB_FLAG] == SGB_FUNCTIONS && data[GB_OLD_LICENSEE] == USE_NEW_LICENSEE;
+
+    let declared_size = map_rom_size(data[GB_ROM_SIZE]);
+    let size_mismatch = declared_size.is_some_and(|declared_size| declared_size != data.len());
 
     let region_mismatch = check_region_mismatch(source_name, region);
 
@@ -148,9 +241,16 @@ pub fn analyze_gb_data(data: &[u8], source_name: &str) -> Result<GbAnalysis, Rom
         region,
         region_string: region_name.to_string(),
         region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
+        raw_region_info,
         system_type: system_type.to_string(),
         game_title,
         destination_code,
+        sgb_support,
+        declared_size,
+        rom_size: data.len(),
+        size_mismatch,
     })
 }
 
@@ -160,7 +260,7 @@ mod tests {
 
     /// Helper function to generate a minimal Game Boy header for testing.
     fn generate_gb_header(destination_code: u8, system_byte: u8, title: &str) -> Vec<u8> {
-        let mut data = vec![0; 0x150]; // Ensure enough space for header
+        let mut data = vec![0; 0x8000]; // The 32KB ROM size the header declares
 
         // Signature (usually present, but not strictly required for region/system analysis)
         data[0x100..0x104].copy_from_slice(b"LOGO"); // Dummy signature
@@ -205,6 +305,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_gb_data_padded() -> Result<(), RomAnalyzerError> {
+        let data = generate_gb_header(0x00, 0x00, "GAMETITLE");
+        let analysis = analyze_gb_data(&data, "game.gb")?;
+        assert_eq!(analysis.declared_size, Some(0x8000));
+        assert!(!analysis.size_mismatch);
+
+        // An overdump padded to double the declared 32KB.
+        let mut data = data;
+        data.resize(0x10000, 0xFF);
+        let analysis = analyze_gb_data(&data, "game.gb")?;
+        assert_eq!(analysis.rom_size, 0x10000);
+        assert!(analysis.size_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "game.gb\n\
+             System:       Game Boy (GB)\n\
+             Game Title:   GAMETITLE\n\
+             Region Code:  0x00\n\
+             Region:       Japan\n\
+             ROM Size:     65536 bytes, header declares 32768 (overdumped or truncated)"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_gb_data_non_japan() -> Result<(), RomAnalyzerError> {
         let data = generate_gb_header(0x01, 0x00, "GAMETITLE"); // Non-Japan, GB
@@ -233,7 +358,7 @@ mod tests {
         let analysis = analyze_gb_data(&data, "test_rom_jp.gbc")?;
 
         assert_eq!(analysis.source_name, "test_rom_jp.gbc");
-        assert_eq!(analysis.system_type, "Game Boy Color (GBC)");
+        assert_eq!(analysis.system_type, "Game Boy Color (enhanced)");
         assert_eq!(analysis.game_title, "GBC TITLE");
         assert_eq!(analysis.destination_code, 0x00);
         assert_eq!(analysis.region, Region::JAPAN);
@@ -247,7 +372,7 @@ mod tests {
         let analysis = analyze_gb_data(&data, "test_rom_eur.gbc")?;
 
         assert_eq!(analysis.source_name, "test_rom_eur.gbc");
-        assert_eq!(analysis.system_type, "Game Boy Color (GBC)");
+        assert_eq!(analysis.system_type, "Game Boy Color (exclusive)");
         assert_eq!(analysis.game_title, "GBC TITLE");
         assert_eq!(analysis.destination_code, 0x01);
         assert_eq!(analysis.region, Region::USA | Region::EUROPE);
@@ -277,7 +402,7 @@ mod tests {
         let analysis = analyze_gb_data(&data, "test_rom_jp.gbc")?;
 
         assert_eq!(analysis.source_name, "test_rom_jp.gbc");
-        assert_eq!(analysis.system_type, "Game Boy Color (GBC)");
+        assert_eq!(analysis.system_type, "Game Boy Color (enhanced)");
         assert_eq!(analysis.game_title, "LOONG TITLE");
         assert_eq!(analysis.destination_code, 0x00);
         assert_eq!(analysis.region, Region::JAPAN);
@@ -285,6 +410,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_gbc_exclusive_long_title() -> Result<(), RomAnalyzerError> {
+        // GBC-exclusive cartridges also stop the title at 11 characters.
+        let mut data = generate_gb_header(0x00, 0xC0, "LOONG TITLE");
+        data[GBC_TITLE_END..GBC_SYSTEM_TYPE].copy_from_slice(b"ABCD"); // Manufacturer code
+        let analysis = analyze_gb_data(&data, "test_rom_jp.gbc")?;
+
+        assert_eq!(analysis.system_type, "Game Boy Color (exclusive)");
+        assert_eq!(analysis.game_title, "LOONG TITLE");
+        assert!(
+            analysis
+                .print()
+                .contains("System:       Game Boy Color (exclusive)")
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_gb_unknown_code() -> Result<(), RomAnalyzerError> {
         let data = generate_gb_header(0x02, 0x00, "UNKNOWN REG"); // Unknown region code
@@ -296,6 +438,62 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_gb_non_japan_europe_filename_no_mismatch() -> Result<(), RomAnalyzerError> {
+        // Non-Japan maps to USA|Europe, so a European filename must not be flagged.
+        let data = generate_gb_header(0x01, 0x00, "GAMETITLE");
+        let analysis = analyze_gb_data(&data, "Game (E).gb")?;
+
+        assert_eq!(analysis.region, Region::USA | Region::EUROPE);
+        assert!(!analysis.region_mismatch);
+
+        let analysis = analyze_gb_data(&data, "Game (J).gb")?;
+        assert!(analysis.region_mismatch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gb_data_sgb_enhanced() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_gb_header(0x01, 0x00, "SGB GAME");
+        data[GB_SGB_FLAG] = SGB_FUNCTIONS;
+        data[GB_OLD_LICENSEE] = USE_NEW_LICENSEE;
+        let analysis = analyze_gb_data(&data, "sgb.gb")?;
+
+        assert!(analysis.sgb_support);
+        assert_eq!(
+            analysis.print(),
+            "sgb.gb\n\
+             System:       Game Boy (GB)\n\
+             Features:     SGB Enhanced\n\
+             Game Title:   SGB GAME\n\
+             Region Code:  0x01\n\
+             Region:       USA/Europe"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gb_data_sgb_requires_new_licensee() -> Result<(), RomAnalyzerError> {
+        // The SGB flag is ignored unless the old licensee code is 0x33.
+        let mut data = generate_gb_header(0x01, 0x00, "SGB GAME");
+        data[GB_SGB_FLAG] = SGB_FUNCTIONS;
+        data[GB_OLD_LICENSEE] = 0x01;
+        let analysis = analyze_gb_data(&data, "sgb.gb")?;
+        assert!(!analysis.sgb_support);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_gb_data_plain_dmg() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_gb_header(0x00, 0x00, "DMG GAME");
+        data[GB_OLD_LICENSEE] = USE_NEW_LICENSEE;
+        let analysis = analyze_gb_data(&data, "dmg.gb")?;
+
+        assert!(!analysis.sgb_support);
+        assert!(!analysis.print().contains("SGB"));
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_gb_data_too_small() {
         // Test with data smaller than the minimum required size for analysis.
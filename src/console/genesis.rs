@@ -1,25 +1,51 @@
 //! Provides header analysis functionality for Sega Genesis (also known as Mega Drive) ROMs.
 //!
 //! This module can parse Genesis ROM headers to extract system type, game titles
-//! (domestic and international), and region information.
+//! (domestic and international), and region information. Dumps in the interleaved SMD (Super
+//! Magic Drive) copier format are deinterleaved before their header is read.
 //!
 //! Genesis header documentation referenced here:
 //! <https://plutiedev.com/rom-header#system>
 
+use std::borrow::Cow;
+
 use log::error;
 use serde::Serialize;
 
+use crate::console::{
+    RomMetadata, archive_source_field, field, region_unknown_field, size_mismatch_field,
+};
 use crate::error::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch};
+use crate::region::{Confidence, Region, VideoSystem, check_region_mismatch};
+use crate::util::sanitize_title;
 use crate::{SEGA_GENESIS_SIG, SEGA_MEGA_DRIVE_SIG};
 
+// Sega Genesis/Mega Drive header is at offset 0x100. It's 256 bytes long, ending with the region
+// byte at 0x1F0, so 0x200 bytes are needed to contain it.
+pub(crate) const HEADER_SIZE: usize = 0x200;
+
 const SYSTEM_TYPE_START: usize = 0x100;
 const SYSTEM_TYPE_END: usize = 0x110;
 const DOMESTIC_TITLE_START: usize = 0x120;
 const DOMESTIC_TITLE_END: usize = 0x150;
 const INTL_TITLE_START: usize = 0x150;
 const INTL_TITLE_END: usize = 0x180;
+// Product type and serial number, e.g. "GM 00001009-00".
+const SERIAL_START: usize = 0x180;
+const SERIAL_END: usize = 0x18E;
+// Big-endian address of the last byte of the ROM.
+const ROM_END_ADDRESS: usize = 0x1A4;
 const REGION_CODE_BYTE: usize = 0x1F0;
+// Bits of the newer hex-nibble region code.
+const REGION_NIBBLE_JAPAN: u8 = 1 << 0;
+const REGION_NIBBLE_USA: u8 = 1 << 2;
+const REGION_NIBBLE_EUROPE: u8 = 1 << 3;
+// SMD copier dumps start with a 512-byte header identified by 0xAA 0xBB at offset 8, followed by
+// the ROM in 16KB blocks that each hold the odd bytes of the block, then the even bytes.
+pub(crate) const SMD_HEADER_SIZE: usize = 0x200;
+pub(crate) const SMD_BLOCK_SIZE: usize = 0x4000;
+const SMD_MAGIC_OFFSET: usize = 8;
+const SMD_MAGIC: &[u8] = &[0xAA, 0xBB];
 
 /// Struct to hold the analysis results for a Sega cartridge (Genesis/Mega Drive) ROM.
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -32,6 +58,12 @@ pub struct GenesisAnalysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region byte, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
     /// The raw region code byte.
     pub region_code_byte: u8,
     /// The detected console name (e.g., "SEGA MEGA DRIVE", "SEGA GENESIS").
@@ -40,33 +72,88 @@ pub struct GenesisAnalysis {
     pub game_title_domestic: String,
     /// The international game title extracted from the ROM header.
     pub game_title_international: String,
+    /// The product type and serial number (e.g., "GM 00001009-00"), without padding.
+    pub serial: String,
+    /// The video timing implied by the region code.
+    pub video_system: VideoSystem,
+    /// The ROM size declared by the ROM end address in the header, if it is set.
+    pub declared_size: Option<usize>,
+    /// The size of the ROM data, without any copier header.
+    pub rom_size: usize,
+    /// If the ROM size doesn't match the declared size, as for overdumped or truncated ROMs.
+    pub size_mismatch: bool,
+    /// If the ROM was stored in the interleaved SMD format and had to be deinterleaved.
+    pub interleaved: bool,
+    /// If the ROM starts with a 512-byte SMD copier header.
+    pub copier_header_present: bool,
 }
 
-impl GenesisAnalysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        format!(
-            "{}\n\
-             System:       {}\n\
-             Game Title (Domestic): {}\n\
-             Game Title (Int.):   {}\n\
-             Region Code:  0x{:02X} ('{}')\n\
-             Region:       {}",
-            self.source_name,
-            self.console_name,
-            self.game_title_domestic,
-            self.game_title_international,
-            self.region_code_byte,
-            self.region_code_byte as char,
-            self.region
-        )
+impl RomMetadata for GenesisAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", &self.console_name)),
+            Some(field("Game Title (Domestic)", &self.game_title_domestic)),
+            Some(field("Game Title (Int.)", &self.game_title_international)),
+            (!self.serial.is_empty()).then(|| field("Serial", &self.serial)),
+            match (self.interleaved, self.copier_header_present) {
+                (true, _) => Some(field("Storage", "Interleaved (SMD)")),
+                (false, true) => Some(field("Storage", "Copier Header")),
+                (false, false) => None,
+            },
+            Some(field(
+                "Region Code",
+                format!(
+                    "0x{:02X} ('{}')",
+                    self.region_code_byte, self.region_code_byte as char
+                ),
+            )),
+            Some(field("Region", self.region)),
+            region_unknown_field(self.region_confidence),
+            size_mismatch_field(self.size_mismatch, self.declared_size, self.rom_size),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
 /// Determines the Sega Genesis/Mega Drive game region name based on a given region byte.
 ///
-/// The region byte typically comes from the ROM header. This function extracts the relevant bits
-/// from the byte and maps it to a human-readable region string and a Region bitmask.
+/// The region byte typically comes from the ROM header. Older ROMs store a letter code (e.g. 'J',
+/// 'U', 'E'), while newer ones store a hex nibble whose bits 0, 2 and 3 mark Japan, USA and Europe
+/// (e.g. 'F' for all three). As the letter codes overlap with hex digits, a byte is only read as a
+/// nibble when it isn't a known letter code: a digit, 'D', 'F', or a raw value up to 0x0F. 'F' is
+/// read as the common all-regions nibble rather than as France. The '4' (0x34) code is kept as
+/// the combined USA/Europe region it is used for in practice.
 ///
 /// # Arguments
 ///
@@ -101,6 +188,10 @@ impl GenesisAnalysis {
 /// assert_eq!(region_str, "USA/Europe (NTSC/PAL)");
 /// assert!(region_mask.contains(Region::USA));
 /// assert!(region_mask.contains(Region::EUROPE));
+///
+/// let (region_str, region_mask) = map_region(b'5');
+/// assert_eq!(region_str, "Japan/USA (NTSC)");
+/// assert_eq!(region_mask, Region::JAPAN | Region::USA);
 /// ```
 pub fn map_region(region_byte: u8) -> (&'static str, Region) {
     match region_byte {
@@ -110,40 +201,133 @@ pub fn map_region(region_byte: u8) -> (&'static str, Region) {
         b'A' => ("Asia (NTSC)", Region::ASIA),
         b'B' => ("Brazil (PAL-M)", Region::EUROPE),
         b'C' => ("China (NTSC)", Region::CHINA),
-        b'F' => ("France (PAL)", Region::EUROPE),
         b'K' => ("Korea (NTSC)", Region::KOREA),
         b'L' => ("UK (PAL)", Region::EUROPE),
         b'S' => ("Scandinavia (PAL)", Region::EUROPE),
         b'T' => ("Taiwan (NTSC)", Region::ASIA),
         0x34 => ("USA/Europe (NTSC/PAL)", Region::USA | Region::EUROPE),
+        _ => region_nibble(region_byte).map_or(("Unknown", Region::UNKNOWN), map_region_nibble),
+    }
+}
+
+/// Reads a region byte that isn't a letter code as a hex-nibble region code.
+fn region_nibble(region_byte: u8) -> Option<u8> {
+    match region_byte {
+        0x00..=0x0F => Some(region_byte),
+        b'0'..=b'9' => Some(region_byte - b'0'),
+        b'D' => Some(0xD),
+        b'F' => Some(0xF),
+        _ => None,
+    }
+}
+
+/// Maps the Japan, USA and Europe bits of a hex-nibble region code.
+fn map_region_nibble(nibble: u8) -> (&'static str, Region) {
+    match nibble & (REGION_NIBBLE_JAPAN | REGION_NIBBLE_USA | REGION_NIBBLE_EUROPE) {
+        REGION_NIBBLE_JAPAN => ("Japan (NTSC-J)", Region::JAPAN),
+        REGION_NIBBLE_USA => ("USA (NTSC-U)", Region::USA),
+        0b0101 => ("Japan/USA (NTSC)", Region::JAPAN | Region::USA),
+        REGION_NIBBLE_EUROPE => ("Europe (PAL)", Region::EUROPE),
+        0b1001 => ("Japan/Europe (NTSC/PAL)", Region::JAPAN | Region::EUROPE),
+        0b1100 => ("USA/Europe (NTSC/PAL)", Region::USA | Region::EUROPE),
+        0b1101 => (
+            "Japan/USA/Europe (NTSC/PAL)",
+            Region::JAPAN | Region::USA | Region::EUROPE,
+        ),
         _ => ("Unknown", Region::UNKNOWN),
     }
 }
 
+/// Determines the Genesis video timing implied by a region code byte.
+///
+/// # Arguments
+///
+/// * `region_byte` - The region code byte from the ROM header.
+///
+/// # Returns
+///
+/// The [`VideoSystem`] for the region. Brazilian consoles use PAL-M, which has NTSC timing.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::genesis::map_video_system;
+/// use rom_analyzer::region::VideoSystem;
+///
+/// assert_eq!(map_video_system(b'U'), VideoSystem::Ntsc);
+/// assert_eq!(map_video_system(b'E'), VideoSystem::Pal);
+/// assert_eq!(map_video_system(b'B'), VideoSystem::Ntsc);
+/// ```
+pub fn map_video_system(region_byte: u8) -> VideoSystem {
+    match region_byte {
+        b'J' | b'U' | b'A' | b'B' | b'C' | b'K' | b'T' => VideoSystem::Ntsc,
+        b'E' | b'L' | b'S' => VideoSystem::Pal,
+        0x34 => VideoSystem::Multi,
+        _ => match region_nibble(region_byte).map(|nibble| {
+            (
+                nibble & (REGION_NIBBLE_JAPAN | REGION_NIBBLE_USA) != 0,
+                nibble & REGION_NIBBLE_EUROPE != 0,
+            )
+        }) {
+            Some((true, true)) => VideoSystem::Multi,
+            Some((true, false)) => VideoSystem::Ntsc,
+            Some((false, true)) => VideoSystem::Pal,
+            Some((false, false)) | None => VideoSystem::Unknown,
+        },
+    }
+}
+
 /// Analyzes Sega Genesis/Mega Drive ROM data.
 ///
+/// This is a lenient wrapper around [`analyze_genesis_data_with_options`] which logs a warning,
+/// rather than failing, when an unexpected Sega header signature is found.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `source_name` - The name of the ROM file, used for logging and region mismatch checks.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`GenesisAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to contain a valid Sega header.
+pub fn analyze_genesis_data(
+    data: &[u8],
+    source_name: &str,
+) -> Result<GenesisAnalysis, RomAnalyzerError> {
+    analyze_genesis_data_with_options(data, source_name, false)
+}
+
+/// Analyzes Sega Genesis/Mega Drive ROM data, optionally rejecting unexpected signatures.
+///
 /// This function reads the ROM header to extract the console name (e.g., "SEGA MEGA DRIVE", "SEGA
 /// GENESIS"), domestic and international game titles, and the region code byte. It then maps the
 /// region code to a human-readable region name and performs a region mismatch check against the
-/// `source_name`.  A warning is logged if an unexpected Sega header signature is found.
+/// `source_name`. An unexpected Sega header signature is logged, or returned as an error when
+/// `strict` is set.
 ///
 /// # Arguments
 ///
 /// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
 /// * `source_name` - The name of the ROM file, used for logging and region mismatch checks.
+/// * `strict` - Whether an unexpected Sega header signature should fail the analysis.
 ///
 /// # Returns
 ///
 /// A `Result` which is:
 /// - `Ok`([`GenesisAnalysis`]) containing the detailed analysis results.
-/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to contain a valid Sega header.
-pub fn analyze_genesis_data(
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to contain a valid Sega header, or
+///   [`RomAnalyzerError::InvalidSignature`] if `strict` is set and the signature is unexpected.
+pub fn analyze_genesis_data_with_options(
     data: &[u8],
     source_name: &str,
+    strict: bool,
 ) -> Result<GenesisAnalysis, RomAnalyzerError> {
-    // Sega Genesis/Mega Drive header is at offset 0x100. It's 256 bytes long.
-    // The region byte is at offset 0x1F0 (relative to ROM start).
-    const HEADER_SIZE: usize = 0x200; // Minimum size to contain the header and region byte.
+    // SMD dumps are stripped of their copier header and deinterleaved first.
+    let (data, copier_header_present, interleaved) = strip_smd_header(data);
+    let data = data.as_ref();
+
     if data.len() < HEADER_SIZE {
         return Err(RomAnalyzerError::DataTooSmall {
             file_size: data.len(),
@@ -162,31 +346,47 @@ pub fn analyze_genesis_data(
 
     // If the signature doesn't match, it might still be a valid ROM but with a different header convention.
     // We'll proceed with analysis but log a warning if the console name is unexpected.
-    let is_valid_signature = console_name_bytes.starts_with(SEGA_MEGA_DRIVE_SIG)
-        || console_name_bytes.starts_with(SEGA_GENESIS_SIG);
+    let is_valid_signature = has_sega_signature(data);
+    if !is_valid_signature && strict {
+        return Err(RomAnalyzerError::InvalidSignature {
+            expected: "SEGA MEGA DRIVE or SEGA GENESIS".to_string(),
+            found: console_name,
+            offset: SYSTEM_TYPE_START,
+        });
+    }
     if !is_valid_signature {
         error!(
+            console = "genesis",
+            reason = "signature_unexpected",
+            path = source_name;
             "[!] Warning: Unexpected Sega header signature for {} at 0x{:x}. Found: '{}'",
             source_name, SYSTEM_TYPE_START, console_name
         );
     }
 
     // Game Title - Domestic (48 bytes, null-terminated)
-    let game_title_domestic =
-        String::from_utf8_lossy(&data[DOMESTIC_TITLE_START..DOMESTIC_TITLE_END])
-            .trim_matches(char::from(0))
-            .trim()
-            .to_string();
+    let game_title_domestic = sanitize_title(&data[DOMESTIC_TITLE_START..DOMESTIC_TITLE_END]);
     // Game Title - International (48 bytes, null-terminated)
-    let game_title_international = String::from_utf8_lossy(&data[INTL_TITLE_START..INTL_TITLE_END])
-        .trim_matches(char::from(0))
-        .trim()
-        .to_string();
+    let game_title_international = sanitize_title(&data[INTL_TITLE_START..INTL_TITLE_END]);
+    // Product type and serial number (14 bytes, space padded)
+    let serial = sanitize_title(&data[SERIAL_START..SERIAL_END]);
 
     // Region Code byte is at offset 0x1F0 (which is 0xF0 relative to header_start)
     let region_code_byte = data[REGION_CODE_BYTE];
 
     let (region_name, region) = map_region(region_code_byte);
+    let raw_region_info =
+        (region == Region::UNKNOWN).then_some((REGION_CODE_BYTE, region_code_byte));
+
+    // The ROM end address is 0 in headers that don't set it.
+    let rom_end_address = u32::from_be_bytes([
+        data[ROM_END_ADDRESS],
+        data[ROM_END_ADDRESS + 1],
+        data[ROM_END_ADDRESS + 2],
+        data[ROM_END_ADDRESS + 3],
+    ]);
+    let declared_size = (rom_end_address != 0).then(|| rom_end_address as usize + 1);
+    let size_mismatch = declared_size.is_some_and(|declared_size| declared_size != data.len());
 
     let region_mismatch = check_region_mismatch(source_name, region);
 
@@ -195,13 +395,91 @@ pub fn analyze_genesis_data(
         region,
         region_string: region_name.to_string(),
         region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
+        raw_region_info,
         region_code_byte,
         console_name,
         game_title_domestic,
         game_title_international,
+        serial,
+        video_system: map_video_system(region_code_byte),
+        declared_size,
+        rom_size: data.len(),
+        size_mismatch,
+        interleaved,
+        copier_header_present,
     })
 }
 
+/// Helper function to check for the "SEGA MEGA DRIVE" or "SEGA GENESIS" signature at 0x100.
+fn has_sega_signature(data: &[u8]) -> bool {
+    data.get(SYSTEM_TYPE_START..SYSTEM_TYPE_END)
+        .is_some_and(|sig| {
+            sig.starts_with(SEGA_MEGA_DRIVE_SIG) || sig.starts_with(SEGA_GENESIS_SIG)
+        })
+}
+
+/// Checks for a Genesis ROM, with its Sega signature at 0x100 or, behind a 512-byte copier
+/// header, at 0x300.
+///
+/// Interleaved SMD dumps have no contiguous signature, so they aren't recognized by this check.
+pub(crate) fn has_genesis_signature(data: &[u8]) -> bool {
+    has_sega_signature(data) || data.get(SMD_HEADER_SIZE..).is_some_and(has_sega_signature)
+}
+
+/// Helper function to strip the copier header of an SMD dump, deinterleaving it if needed.
+///
+/// Data without a Sega signature at 0x100 has its first 512 bytes stripped as a copier header
+/// if the signature follows them at 0x300, whatever the copier header holds. Otherwise it is
+/// only treated as an interleaved SMD dump when its first 512 bytes carry the SMD header magic.
+///
+/// # Returns
+///
+/// A tuple containing the ROM data, whether a copier header was stripped and whether the ROM was
+/// deinterleaved.
+fn strip_smd_header(data: &[u8]) -> (Cow<'_, [u8]>, bool, bool) {
+    if has_sega_signature(data) || data.len() <= SMD_HEADER_SIZE {
+        return (Cow::Borrowed(data), false, false);
+    }
+    let rom_data = &data[SMD_HEADER_SIZE..];
+    if has_sega_signature(rom_data) {
+        (Cow::Borrowed(rom_data), true, false)
+    } else if data.get(SMD_MAGIC_OFFSET..SMD_MAGIC_OFFSET + SMD_MAGIC.len()) == Some(SMD_MAGIC) {
+        (Cow::Owned(deinterleave_smd(rom_data)), true, true)
+    } else {
+        (Cow::Borrowed(data), false, false)
+    }
+}
+
+/// Deinterleaves ROM data stored in the SMD format, without its copier header.
+///
+/// Each 16KB block holds the odd bytes of the block in its first half and the even bytes in its
+/// second half. A trailing partial block, as left by reading only the start of a file, is
+/// deinterleaved as far as both of its halves go.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::genesis::deinterleave_smd;
+///
+/// let mut block = vec![0; 0x4000];
+/// block[0] = b'E'; // First odd byte
+/// block[0x2000] = b'S'; // First even byte
+/// assert_eq!(&deinterleave_smd(&block)[..2], b"SE");
+/// ```
+pub fn deinterleave_smd(rom_data: &[u8]) -> Vec<u8> {
+    let mut deinterleaved = Vec::with_capacity(rom_data.len());
+    for block in rom_data.chunks(SMD_BLOCK_SIZE) {
+        let (odd, even) = block.split_at(block.len().min(SMD_BLOCK_SIZE / 2));
+        for (&even_byte, &odd_byte) in even.iter().zip(odd) {
+            deinterleaved.push(even_byte);
+            deinterleaved.push(odd_byte);
+        }
+    }
+    deinterleaved
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,15 +525,62 @@ mod tests {
         assert_eq!(analysis.region_code_byte, b'U');
         assert_eq!(analysis.region, Region::USA);
         assert_eq!(analysis.region_string, "USA (NTSC-U)");
+        assert_eq!(analysis.video_system, VideoSystem::Ntsc);
         assert_eq!(
             analysis.print(),
             "test_rom_us.md\n\
              System:       SEGA MEGA DRIVE\n\
              Game Title (Domestic): DOMESTIC US\n\
-             Game Title (Int.):   INTERNATIONAL US\n\
+             Game Title (Int.): INTERNATIONAL US\n\
+             Region Code:  0x55 ('U')\n\
+             Region:       USA"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_unknown_region() -> Result<(), RomAnalyzerError> {
+        let data = generate_genesis_header(b"SEGA MEGA DRIVE ", b'Z', "DOMESTIC", "INTERNATIONAL");
+        let analysis = analyze_genesis_data(&data, "test_rom.md")?;
+
+        assert_eq!(analysis.region, Region::UNKNOWN);
+        assert_eq!(analysis.raw_region_info, Some((0x1F0, b'Z')));
+
+        // Recognized regions don't report their raw byte.
+        let data = generate_genesis_header(b"SEGA MEGA DRIVE ", b'U', "DOMESTIC", "INTERNATIONAL");
+        assert_eq!(
+            analyze_genesis_data(&data, "test_rom.md")?.raw_region_info,
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_serial() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_genesis_header(b"SEGA GENESIS    ", b'U', "GAME", "GAME");
+        data[SERIAL_START..SERIAL_END].copy_from_slice(b" GM MK-1234 -0");
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert_eq!(analysis.serial, "GM MK-1234 -0");
+
+        data[SERIAL_START..SERIAL_END].copy_from_slice(b"GM MK-1234 -00");
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert_eq!(analysis.serial, "GM MK-1234 -00");
+        assert_eq!(
+            analysis.print(),
+            "game.md\n\
+             System:       SEGA GENESIS\n\
+             Game Title (Domestic): GAME\n\
+             Game Title (Int.): GAME\n\
+             Serial:       GM MK-1234 -00\n\
              Region Code:  0x55 ('U')\n\
              Region:       USA"
         );
+
+        // An all-space serial field is empty.
+        data[SERIAL_START..SERIAL_END].copy_from_slice(&[b' '; SERIAL_END - SERIAL_START]);
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert_eq!(analysis.serial, "");
+        assert!(!analysis.print().contains("Serial:"));
         Ok(())
     }
 
@@ -284,6 +609,8 @@ mod tests {
         assert_eq!(analysis.region, Region::EUROPE); // Brazil is PAL
         assert_eq!(analysis.region_string, "Brazil (PAL-M)");
         assert_eq!(analysis.region_code_byte, b'B');
+        // PAL-M has NTSC timing, despite Brazil being grouped with PAL regions.
+        assert_eq!(analysis.video_system, VideoSystem::Ntsc);
         Ok(())
     }
 
@@ -325,6 +652,168 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_genesis_data_region_nibble() -> Result<(), RomAnalyzerError> {
+        let data = generate_genesis_header(b"SEGA MEGA DRIVE ", b'F', "DOMESTIC", "INTERNATIONAL");
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert_eq!(
+            analysis.region,
+            Region::JAPAN | Region::USA | Region::EUROPE
+        );
+        assert_eq!(analysis.region_string, "Japan/USA/Europe (NTSC/PAL)");
+        assert_eq!(analysis.video_system, VideoSystem::Multi);
+
+        let data = generate_genesis_header(b"SEGA MEGA DRIVE ", b'5', "DOMESTIC", "INTERNATIONAL");
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert_eq!(analysis.region, Region::JAPAN | Region::USA);
+        assert_eq!(analysis.region_string, "Japan/USA (NTSC)");
+        assert_eq!(analysis.video_system, VideoSystem::Ntsc);
+
+        // Legacy letter codes take precedence over the hex-nibble reading.
+        let data = generate_genesis_header(b"SEGA MEGA DRIVE ", b'U', "DOMESTIC", "INTERNATIONAL");
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert_eq!(analysis.region, Region::USA);
+        assert_eq!(analysis.region_string, "USA (NTSC-U)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_size_mismatch() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_genesis_header(b"SEGA MEGA DRIVE ", b'U', "DOMESTIC", "INT");
+        data[ROM_END_ADDRESS..ROM_END_ADDRESS + 4].copy_from_slice(&0x1FFu32.to_be_bytes());
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert_eq!(analysis.declared_size, Some(0x200));
+        assert!(!analysis.size_mismatch);
+
+        data.resize(0x400, 0);
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert!(analysis.size_mismatch);
+        assert!(analysis.print().ends_with(
+            "\nROM Size:     1024 bytes, header declares 512 (overdumped or truncated)"
+        ));
+        Ok(())
+    }
+
+    /// Helper function to store ROM data in the SMD format, behind an SMD copier header.
+    fn interleave_smd(rom_data: &[u8]) -> Vec<u8> {
+        let mut data = vec![0; SMD_HEADER_SIZE];
+        data[0] = rom_data.len().div_ceil(SMD_BLOCK_SIZE) as u8;
+        data[SMD_MAGIC_OFFSET..SMD_MAGIC_OFFSET + 2].copy_from_slice(SMD_MAGIC);
+        for block in rom_data.chunks(SMD_BLOCK_SIZE) {
+            data.extend(block.iter().skip(1).step_by(2));
+            data.extend(block.iter().step_by(2));
+        }
+        data
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_smd_interleaved() -> Result<(), RomAnalyzerError> {
+        let mut rom_data = generate_genesis_header(b"SEGA GENESIS    ", b'U', "SONIC", "SONIC");
+        rom_data.resize(2 * SMD_BLOCK_SIZE, 0);
+        let data = interleave_smd(&rom_data);
+        assert_ne!(&data[0x100..0x104], b"SEGA");
+
+        let analysis = analyze_genesis_data(&data, "sonic (USA).smd")?;
+        assert!(analysis.interleaved);
+        assert!(analysis.copier_header_present);
+        assert_eq!(analysis.console_name, "SEGA GENESIS");
+        assert_eq!(analysis.game_title_international, "SONIC");
+        assert_eq!(analysis.region, Region::USA);
+        assert_eq!(analysis.rom_size, 2 * SMD_BLOCK_SIZE);
+        assert!(
+            analysis
+                .print()
+                .contains("\nStorage:      Interleaved (SMD)\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_copier_header_not_interleaved() -> Result<(), RomAnalyzerError> {
+        let mut data = vec![0; SMD_HEADER_SIZE];
+        data[SMD_MAGIC_OFFSET..SMD_MAGIC_OFFSET + 2].copy_from_slice(SMD_MAGIC);
+        data.extend(generate_genesis_header(
+            b"SEGA MEGA DRIVE ",
+            b'J',
+            "DOMESTIC",
+            "INT",
+        ));
+
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert!(!analysis.interleaved);
+        assert!(analysis.copier_header_present);
+        assert_eq!(analysis.console_name, "SEGA MEGA DRIVE");
+        assert_eq!(analysis.region, Region::JAPAN);
+        assert!(analysis.print().contains("\nStorage:      Copier Header\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_copier_header_without_magic() -> Result<(), RomAnalyzerError> {
+        // Copier headers without the SMD magic are stripped when the signature follows them.
+        let mut data = vec![0xFF; SMD_HEADER_SIZE];
+        data.extend(generate_genesis_header(
+            b"SEGA MEGA DRIVE ",
+            b'E',
+            "DOMESTIC",
+            "INT",
+        ));
+        assert_eq!(&data[0x300..0x304], b"SEGA");
+
+        let analysis = analyze_genesis_data(&data, "game (Europe).bin")?;
+        assert!(!analysis.interleaved);
+        assert!(analysis.copier_header_present);
+        assert_eq!(analysis.console_name, "SEGA MEGA DRIVE");
+        assert_eq!(analysis.game_title_domestic, "DOMESTIC");
+        assert_eq!(analysis.region, Region::EUROPE);
+        assert_eq!(analysis.rom_size, 0x200);
+        assert!(has_genesis_signature(&data));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_plain_rom_not_stripped() -> Result<(), RomAnalyzerError> {
+        // A plain ROM whose vector table happens to hold the SMD magic keeps its data.
+        let mut data = generate_genesis_header(b"SEGA MEGA DRIVE ", b'U', "DOMESTIC", "INT");
+        data[SMD_MAGIC_OFFSET..SMD_MAGIC_OFFSET + 2].copy_from_slice(SMD_MAGIC);
+        let analysis = analyze_genesis_data(&data, "game.md")?;
+        assert!(!analysis.interleaved);
+        assert!(!analysis.copier_header_present);
+        assert_eq!(analysis.game_title_domestic, "DOMESTIC");
+        Ok(())
+    }
+
+    #[test]
+    fn test_deinterleave_smd_round_trip() {
+        let rom_data: Vec<u8> = (0..3 * SMD_BLOCK_SIZE).map(|i| (i % 251) as u8).collect();
+        let data = interleave_smd(&rom_data);
+        assert_eq!(deinterleave_smd(&data[SMD_HEADER_SIZE..]), rom_data);
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_region_mismatch() -> Result<(), RomAnalyzerError> {
+        let data = generate_genesis_header(b"SEGA MEGA DRIVE ", b'U', "DOMESTIC", "INTERNATIONAL");
+
+        let analysis = analyze_genesis_data(&data, "Game (J).md")?;
+        assert!(analysis.region_mismatch);
+
+        let analysis = analyze_genesis_data(&data, "Game (U).md")?;
+        assert!(!analysis.region_mismatch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_video_system() {
+        assert_eq!(map_video_system(b'E'), VideoSystem::Pal);
+        assert_eq!(map_video_system(b'J'), VideoSystem::Ntsc);
+        assert_eq!(map_video_system(0x34), VideoSystem::Multi);
+        assert_eq!(map_video_system(b'?'), VideoSystem::Unknown);
+        assert_eq!(map_video_system(b'F'), VideoSystem::Multi);
+        assert_eq!(map_video_system(0x0F), VideoSystem::Multi);
+        assert_eq!(map_video_system(b'5'), VideoSystem::Ntsc);
+        assert_eq!(map_video_system(b'8'), VideoSystem::Pal);
+    }
+
     #[test]
     fn test_analyze_genesis_data_too_small() {
         // Test with data smaller than the minimum required size for analysis.
@@ -334,6 +823,40 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("too small"));
     }
 
+    #[test]
+    fn test_analyze_genesis_data_bad_signature_lenient() -> Result<(), RomAnalyzerError> {
+        let data = generate_genesis_header(b"NOT A SEGA ROM  ", b'U', "DOMESTIC", "INTL");
+        let analysis = analyze_genesis_data(&data, "bad_sig.md")?;
+        assert_eq!(analysis.console_name, "NOT A SEGA ROM");
+        assert_eq!(analysis.region, Region::USA);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_bad_signature_strict() {
+        let data = generate_genesis_header(b"NOT A SEGA ROM  ", b'U', "DOMESTIC", "INTL");
+        match analyze_genesis_data_with_options(&data, "bad_sig.md", true) {
+            Err(RomAnalyzerError::InvalidSignature {
+                expected,
+                found,
+                offset,
+            }) => {
+                assert_eq!(expected, "SEGA MEGA DRIVE or SEGA GENESIS");
+                assert_eq!(found, "NOT A SEGA ROM");
+                assert_eq!(offset, SYSTEM_TYPE_START);
+            }
+            other => panic!("Expected InvalidSignature error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_analyze_genesis_data_strict_accepts_valid_signature() -> Result<(), RomAnalyzerError> {
+        let data = generate_genesis_header(b"SEGA GENESIS    ", b'E', "DOMESTIC", "INTL");
+        let analysis = analyze_genesis_data_with_options(&data, "good_sig.md", true)?;
+        assert_eq!(analysis.console_name, "SEGA GENESIS");
+        Ok(())
+    }
+
     #[test]
     fn test_map_region_all_codes() {
         // Test all known region codes to catch "delete match arm" mutations
@@ -344,13 +867,31 @@ mod tests {
             (b'A', "Asia (NTSC)", Region::ASIA),
             (b'B', "Brazil (PAL-M)", Region::EUROPE),
             (b'C', "China (NTSC)", Region::CHINA),
-            (b'F', "France (PAL)", Region::EUROPE),
+            (
+                b'F',
+                "Japan/USA/Europe (NTSC/PAL)",
+                Region::JAPAN | Region::USA | Region::EUROPE,
+            ),
             (b'K', "Korea (NTSC)", Region::KOREA),
             (b'L', "UK (PAL)", Region::EUROPE),
             (b'S', "Scandinavia (PAL)", Region::EUROPE),
             (b'T', "Taiwan (NTSC)", Region::ASIA),
             (0x34, "USA/Europe (NTSC/PAL)", Region::USA | Region::EUROPE),
+            (b'1', "Japan (NTSC-J)", Region::JAPAN),
+            (b'8', "Europe (PAL)", Region::EUROPE),
+            (
+                b'9',
+                "Japan/Europe (NTSC/PAL)",
+                Region::JAPAN | Region::EUROPE,
+            ),
+            (0x0C, "USA/Europe (NTSC/PAL)", Region::USA | Region::EUROPE),
+            (
+                b'D',
+                "Japan/USA/Europe (NTSC/PAL)",
+                Region::JAPAN | Region::USA | Region::EUROPE,
+            ),
             (b'Z', "Unknown", Region::UNKNOWN), // Unknown byte
+            (0x00, "Unknown", Region::UNKNOWN), // Empty header
         ];
         for (code, expected_name, expected_region) in test_cases {
             let (name, region) = map_region(code);
@@ -0,0 +1,246 @@
+//! Provides header analysis functionality for Mattel Intellivision ROMs.
+//!
+//! Intellivision `.int` ROMs hold the cartridge's 16-bit words in big-endian order, mapped from
+//! address $5000. Cartridges written for the Executive ROM (EXEC) start with a header whose
+//! title pointer leads to the release year and the game title. The header has no region
+//! information, so the region is inferred from the filename.
+
+use serde::Serialize;
+
+use crate::console::{RomMetadata, archive_source_field, field};
+use crate::error::RomAnalyzerError;
+use crate::region::{Confidence, Region, check_region_mismatch, infer_region_from_filename};
+
+// The address the start of the ROM is mapped to.
+const ROM_BASE_ADDRESS: usize = 0x5000;
+// The EXEC header fills the first 20 words of the ROM.
+pub(crate) const EXEC_HEADER_WORDS: usize = 0x14;
+// The title pointer is split across two words, low byte first.
+const TITLE_POINTER_LOW: usize = 0x0A;
+const TITLE_POINTER_HIGH: usize = 0x0B;
+// The year is stored as an offset from 1900.
+const YEAR_BASE: u16 = 1900;
+const MAX_TITLE_LENGTH: usize = 0x40;
+
+/// Struct to hold the analysis results for an Intellivision ROM.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct IntellivisionAnalysis {
+    /// The name of the source file.
+    pub source_name: String,
+    /// The region(s) inferred from the filename as a region::Region bitmask.
+    pub region: Region,
+    /// The inferred region name (e.g., "USA").
+    pub region_string: String,
+    /// If the region in the ROM header doesn't match the region in the filename.
+    pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The game title from the EXEC header, if the ROM has one.
+    pub game_title: Option<String>,
+    /// The release year from the EXEC header, if the ROM has one.
+    pub year: Option<u16>,
+}
+
+impl RomMetadata for IntellivisionAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Mattel Intellivision")),
+            self.game_title
+                .as_ref()
+                .map(|title| field("Game Title", title)),
+            self.year.map(|year| field("Year", year)),
+            Some(field("Region", self.region)),
+            Some(field(
+                "Note",
+                "Region information not in ROM header, inferred from filename.",
+            )),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Reads the big-endian word at `index`, or `None` if it is past the end of the ROM.
+fn read_word(data: &[u8], index: usize) -> Option<u16> {
+    let bytes = data.get(index * 2..index * 2 + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads the year and title the EXEC header's title pointer leads to.
+///
+/// The pointed-to word holds the year, followed by the title with one character per word and a
+/// terminating zero word. Returns `None` if the pointer leads outside the ROM or to something
+/// that isn't a printable title, as is the case for ROMs that don't use the EXEC header.
+fn read_exec_title(data: &[u8]) -> Option<(u16, String)> {
+    let low = read_word(data, TITLE_POINTER_LOW)? & 0xFF;
+    let high = read_word(data, TITLE_POINTER_HIGH)? & 0xFF;
+    let index = ((high << 8) | low) as usize;
+    let index = index.checked_sub(ROM_BASE_ADDRESS)?;
+
+    let year = YEAR_BASE.checked_add(read_word(data, index)?)?;
+    let mut title = String::new();
+    for offset in 1..=MAX_TITLE_LENGTH {
+        match read_word(data, index + offset)? {
+            0 => break,
+            c @ 0x20..=0x7E => title.push(c as u8 as char),
+            _ => return None,
+        }
+    }
+    let title = title.trim().to_string();
+    (!title.is_empty() && title.len() < MAX_TITLE_LENGTH).then_some((year, title))
+}
+
+/// Analyzes Intellivision ROM data in the `.int` format.
+///
+/// This function follows the title pointer in the EXEC header to extract the game title and
+/// release year. ROMs that don't use the EXEC header (or that rely on a separate `.cfg` memory
+/// map) have no title, and are still analyzed. As the header has no region information, the
+/// region is inferred from the `source_name`.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `source_name` - The name of the ROM file, used for region inference.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`IntellivisionAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to hold an EXEC header.
+pub fn analyze_intellivision_data(
+    data: &[u8],
+    source_name: &str,
+) -> Result<IntellivisionAnalysis, RomAnalyzerError> {
+    if data.len() < EXEC_HEADER_WORDS * 2 {
+        return Err(RomAnalyzerError::DataTooSmall {
+            file_size: data.len(),
+            required_size: EXEC_HEADER_WORDS * 2,
+            details: "Intellivision EXEC header".to_string(),
+        });
+    }
+
+    let (year, game_title) = read_exec_title(data).unzip();
+
+    let region = infer_region_from_filename(source_name);
+    let region_mismatch = check_region_mismatch(source_name, region);
+
+    Ok(IntellivisionAnalysis {
+        source_name: source_name.to_string(),
+        region,
+        region_string: region.to_string(),
+        region_mismatch,
+        region_confidence: Confidence::from_filename(region),
+        archive_source: None,
+        game_title,
+        year,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a ROM of 16-bit words with an EXEC header whose title pointer leads to `title`.
+    fn generate_int_rom(year: u16, title: &str) -> Vec<u8> {
+        let title_index = EXEC_HEADER_WORDS + 0x10;
+        let title_address = (ROM_BASE_ADDRESS + title_index) as u16;
+        let mut words = vec![0u16; 0x100];
+        words[TITLE_POINTER_LOW] = title_address & 0xFF;
+        words[TITLE_POINTER_HIGH] = title_address >> 8;
+        words[title_index] = year - YEAR_BASE;
+        for (i, c) in title.bytes().enumerate() {
+            words[title_index + 1 + i] = c as u16;
+        }
+        words.iter().flat_map(|word| word.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_analyze_intellivision_data_with_title() -> Result<(), RomAnalyzerError> {
+        let data = generate_int_rom(1982, "Astrosmash");
+        let analysis = analyze_intellivision_data(&data, "Astrosmash (USA).int")?;
+
+        assert_eq!(analysis.game_title.as_deref(), Some("Astrosmash"));
+        assert_eq!(analysis.year, Some(1982));
+        assert_eq!(analysis.region, Region::USA);
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "Astrosmash (USA).int\n\
+             System:       Mattel Intellivision\n\
+             Game Title:   Astrosmash\n\
+             Year:         1982\n\
+             Region:       USA\n\
+             Note:         Region information not in ROM header, inferred from filename."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_intellivision_data_without_title() -> Result<(), RomAnalyzerError> {
+        // A zeroed header points below the ROM, so there is no title.
+        let analysis = analyze_intellivision_data(&vec![0; 0x200], "homebrew.int")?;
+
+        assert_eq!(analysis.game_title, None);
+        assert_eq!(analysis.year, None);
+        assert_eq!(analysis.region, Region::UNKNOWN);
+        assert_eq!(
+            analysis.print(),
+            "homebrew.int\n\
+             System:       Mattel Intellivision\n\
+             Region:       Unknown\n\
+             Note:         Region information not in ROM header, inferred from filename."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_intellivision_data_invalid_title() -> Result<(), RomAnalyzerError> {
+        // Words that aren't printable characters aren't taken as a title.
+        let mut data = generate_int_rom(1982, "Astrosmash");
+        data[(EXEC_HEADER_WORDS + 0x12) * 2] = 0x01;
+        let analysis = analyze_intellivision_data(&data, "game.int")?;
+        assert_eq!(analysis.game_title, None);
+
+        // Neither is a title that runs off the end of the ROM.
+        let data = generate_int_rom(1982, "Astrosmash");
+        let analysis =
+            analyze_intellivision_data(&data[..(EXEC_HEADER_WORDS + 0x14) * 2], "game.int")?;
+        assert_eq!(analysis.game_title, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_intellivision_data_too_small() {
+        let result = analyze_intellivision_data(&[0; 0x10], "game.int");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("too small"));
+    }
+}
@@ -1,14 +1,28 @@
 //! Provides header analysis functionality for Sega Master System ROMs.
 //!
-//! This module can parse Master System ROM headers to extract region information.
+//! This module can parse Master System ROM headers to extract region information and validate the
+//! header checksum, and falls back to inferring the region from the filename for ROMs without a
+//! header.
 //!
 //! Master System header documentation referenced here:
 //! <https://www.smspower.org/Development/ROMHeader>
 
+use log::{debug, error};
 use serde::Serialize;
 
+use crate::console::gamegear::{REGION_CODE_OFFSET, find_sega_header};
+use crate::console::{RomMetadata, archive_source_field, field};
 use crate::error::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch};
+use crate::region::{Confidence, Region, check_region_mismatch, infer_region_from_filename};
+
+// The smallest ROM that can hold a header, which then sits at 0x1ff0.
+pub(crate) const MIN_HEADER_ROM_SIZE: usize = 0x2000;
+// The little-endian checksum, relative to the header start.
+const CHECKSUM_OFFSET: usize = 0xa;
+// The header itself is never included in the checksum, so ROMs larger than 32KB are summed in
+// two parts: everything before the header at 0x7ff0, then everything from 0x8000 onwards.
+const CHECKSUM_SPLIT_START: usize = 0x7ff0;
+const CHECKSUM_SPLIT_END: usize = 0x8000;
 
 /// Struct to hold the analysis results for a Master System ROM.
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -21,27 +35,77 @@ pub struct MasterSystemAnalysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
-    /// The raw region byte value.
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region byte, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
+    /// The raw region byte value, or 0 if no header was found.
     pub region_byte: u8,
+    /// If the 'TMR SEGA' header signature was found.
+    pub header_found: bool,
+    /// If the header checksum matches the ROM data, or `None` if it couldn't be checked: no
+    /// header was found, its ROM size code is unknown, or the analyzed data (e.g. a ROM cut short
+    /// by ZIP extraction or `--max-file-size`) doesn't cover the checksummed range.
+    pub checksum_valid: Option<bool>,
 }
 
-impl MasterSystemAnalysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        format!(
-            "{}\n\
-             System:       Sega Master System\n\
-             Region Code:  0x{:02X}\n\
-             Region:       {}",
-            self.source_name, self.region_byte, self.region
-        )
+impl RomMetadata for MasterSystemAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Sega Master System")),
+            Some(field("Region Code", format!("0x{:02X}", self.region_byte))),
+            Some(field("Region", self.region)),
+            (self.region_confidence != Confidence::FromHeader).then(|| {
+                field(
+                    "Note",
+                    "Region information not in ROM header, inferred from filename.",
+                )
+            }),
+            (self.checksum_valid == Some(false))
+                .then(|| field("Checksum", "Invalid (possible bad dump)")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
 /// Determines the Sega Master System game region name based on a given region byte.
 ///
-/// The region byte typically comes from the ROM header. This function extracts the relevant bits
-/// from the byte and maps it to a human-readable region string and a Region bitmask.
+/// The region byte typically comes from the ROM header. The region code is stored in its upper
+/// nibble (the lower nibble holds the ROM size), which this function maps to a human-readable
+/// region string and a Region bitmask.
 ///
 /// # Arguments
 ///
@@ -73,78 +137,189 @@ impl MasterSystemAnalysis {
 /// assert_eq!(region_mask, Region::UNKNOWN);
 /// ```
 pub fn map_region(region_byte: u8) -> (&'static str, Region) {
-    match region_byte {
-        0x30 => ("Japan (NTSC)", Region::JAPAN),
-        0x4C => ("Europe / Overseas (PAL/NTSC)", Region::USA | Region::EUROPE),
+    match region_byte >> 4 {
+        0x3 => ("Japan (NTSC)", Region::JAPAN),
+        0x4 => ("Europe / Overseas (PAL/NTSC)", Region::USA | Region::EUROPE),
         _ => ("Unknown", Region::UNKNOWN),
     }
 }
 
+/// Returns the number of bytes covered by the header checksum for a given ROM size code.
+///
+/// The ROM size code is stored in the lower nibble of the header's region byte. Codes that don't
+/// correspond to a known size return `None`.
+fn checksum_range_size(size_code: u8) -> Option<usize> {
+    match size_code {
+        0xa => Some(0x2000),
+        0xb => Some(0x4000),
+        0xc => Some(0x8000),
+        0xd => Some(0xc000),
+        0xe => Some(0x10000),
+        0xf => Some(0x20000),
+        0x0 => Some(0x40000),
+        0x1 => Some(0x80000),
+        0x2 => Some(0x100000),
+        _ => None,
+    }
+}
+
+/// Helper function to validate the Master System header checksum.
+///
+/// The checksum is the 16-bit sum of every byte in the range given by the header's ROM size code,
+/// excluding the header itself. It is stored little-endian at offset 0xa of the header.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `header_start` - The offset of the 'TMR SEGA' header within the ROM data.
+///
+/// # Returns
+///
+/// `Some(true)` if the stored checksum matches the computed one, `Some(false)` if it doesn't, or
+/// `None` if the ROM size code is unknown or the data is smaller than the checksummed range.
+fn validate_checksum(data: &[u8], header_start: usize) -> Option<bool> {
+    let region_byte = *data.get(header_start + REGION_CODE_OFFSET)?;
+    let range_size = checksum_range_size(region_byte & 0x0f)?;
+    if data.len() < range_size {
+        debug!(
+            "Checksum range of 0x{:X} bytes not covered by 0x{:X} bytes of data",
+            range_size,
+            data.len()
+        );
+        return None;
+    }
+
+    let stored = u16::from_le_bytes([
+        data[header_start + CHECKSUM_OFFSET],
+        data[header_start + CHECKSUM_OFFSET + 1],
+    ]);
+
+    // Smaller ROMs keep their header in their last 16 bytes, and are summed up to it.
+    let (first, second) = if range_size <= CHECKSUM_SPLIT_END {
+        (&data[..range_size - 0x10], &[][..])
+    } else {
+        (
+            &data[..CHECKSUM_SPLIT_START],
+            &data[CHECKSUM_SPLIT_END..range_size],
+        )
+    };
+    let computed = first
+        .iter()
+        .chain(second)
+        .fold(0u16, |sum, &byte| sum.wrapping_add(byte as u16));
+
+    debug!(
+        "Checksum: stored 0x{:04X}, computed 0x{:04X}",
+        stored, computed
+    );
+    Some(stored == computed)
+}
+
 /// Analyzes Master System ROM data.
 ///
-/// This function reads the Master System ROM header to extract the region byte.
-/// It then maps the region byte to a human-readable region name and performs
-/// a region mismatch check against the `source_name`.
+/// This function locates the 'TMR SEGA' header at 0x7ff0, 0x3ff0 or 0x1ff0, the same locations
+/// used by Game Gear ROMs, so smaller 8KB and 16KB ROMs are supported. It then maps the region
+/// byte at the end of the header to a human-readable region name, validates the header checksum
+/// and performs a region mismatch check against the `source_name`. If no header is found, or its
+/// region code is not recognized, the region is inferred from the `source_name` instead.
 ///
 /// # Arguments
 ///
 /// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
-/// * `source_name` - The name of the ROM file, used for region mismatch checks.
+/// * `source_name` - The name of the ROM file, used for region mismatch checks and inference.
 ///
 /// # Returns
 ///
 /// A `Result` which is:
 /// - `Ok`([`MasterSystemAnalysis`]) containing the detailed analysis results.
-/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to contain the region byte.
+/// - `Err`([`RomAnalyzerError`]) if no header is found and the ROM data is too small to hold one.
 pub fn analyze_mastersystem_data(
     data: &[u8],
     source_name: &str,
 ) -> Result<MasterSystemAnalysis, RomAnalyzerError> {
-    // SMS Region/Language byte is at offset 0x7FFC.
-    // The header size for SMS is not strictly defined in a way that guarantees a fixed length for all ROMs,
-    // but 0x7FFD is a common size for the data containing this byte.
-    const REQUIRED_SIZE: usize = 0x7FFD;
-    if data.len() < REQUIRED_SIZE {
+    let header_start = find_sega_header(data);
+    let region_byte = header_start.and_then(|header_start| {
+        debug!("Found signature at 0x{:x}", header_start);
+        data.get(header_start + REGION_CODE_OFFSET).copied()
+    });
+    let header_found = region_byte.is_some();
+    let checksum_valid =
+        header_start.and_then(|header_start| validate_checksum(data, header_start));
+    if checksum_valid == Some(false) {
+        error!(
+            console = "mastersystem",
+            reason = "checksum_invalid",
+            path = source_name;
+            "[!] Master System header checksum mismatch for {}.",
+            source_name
+        );
+    }
+
+    if !header_found && data.len() < MIN_HEADER_ROM_SIZE {
         return Err(RomAnalyzerError::DataTooSmall {
             file_size: data.len(),
-            required_size: REQUIRED_SIZE,
-            details: "Master System region byte".to_string(),
+            required_size: MIN_HEADER_ROM_SIZE,
+            details: "Master System header".to_string(),
         });
     }
 
-    let sms_region_byte = data[0x7FFC];
-    let (region_name, region) = map_region(sms_region_byte);
+    let (region_name, mut region) = region_byte.map_or(("Unknown", Region::UNKNOWN), map_region);
+    let raw_region_info = header_start
+        .zip(region_byte)
+        .filter(|_| region == Region::UNKNOWN)
+        .map(|(header_start, region_byte)| (header_start + REGION_CODE_OFFSET, region_byte));
+    let (region_string, region_confidence) = if region != Region::UNKNOWN {
+        (region_name.to_string(), Confidence::FromHeader)
+    } else {
+        region = infer_region_from_filename(source_name);
+        (region.to_string(), Confidence::from_filename(region))
+    };
 
     let region_mismatch = check_region_mismatch(source_name, region);
 
     Ok(MasterSystemAnalysis {
         source_name: source_name.to_string(),
         region,
-        region_string: region_name.to_string(),
+        region_string,
         region_mismatch,
-        region_byte: sms_region_byte,
+        region_confidence,
+        archive_source: None,
+        raw_region_info,
+        region_byte: region_byte.unwrap_or_default(),
+        header_found,
+        checksum_valid,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::console::gamegear::SEGA_HEADER_SIGNATURE;
+
+    fn generate_sms_header(size: usize, header_start: usize, region_byte: u8) -> Vec<u8> {
+        let mut data = vec![0; size];
+        data[header_start..header_start + SEGA_HEADER_SIGNATURE.len()]
+            .copy_from_slice(SEGA_HEADER_SIGNATURE);
+        data[header_start + REGION_CODE_OFFSET] = region_byte;
+        data
+    }
 
     #[test]
     fn test_analyze_mastersystem_data_japan() -> Result<(), RomAnalyzerError> {
-        let mut data = vec![0; 0x7FFD];
-        data[0x7FFC] = 0x30; // Japan region
+        let data = generate_sms_header(0x8000, 0x7FF0, 0x3C); // Japan region, 32KB
         let analysis = analyze_mastersystem_data(&data, "test_rom_jp.sms")?;
 
         assert_eq!(analysis.source_name, "test_rom_jp.sms");
-        assert_eq!(analysis.region_byte, 0x30);
+        assert_eq!(analysis.region_byte, 0x3C);
         assert_eq!(analysis.region, Region::JAPAN);
         assert_eq!(analysis.region_string, "Japan (NTSC)");
+        assert_eq!(analysis.region_confidence, Confidence::FromHeader);
+        assert!(analysis.header_found);
         assert_eq!(
             analysis.print(),
             "test_rom_jp.sms\n\
              System:       Sega Master System\n\
-             Region Code:  0x30\n\
+             Region Code:  0x3C\n\
              Region:       Japan"
         );
         Ok(())
@@ -152,8 +327,7 @@ mod tests {
 
     #[test]
     fn test_analyze_mastersystem_data_europe() -> Result<(), RomAnalyzerError> {
-        let mut data = vec![0; 0x7FFD];
-        data[0x7FFC] = 0x4C; // Europe / Overseas region
+        let data = generate_sms_header(0x8000, 0x7FF0, 0x4C); // Europe / Overseas region
         let analysis = analyze_mastersystem_data(&data, "test_rom_eur.sms")?;
 
         assert_eq!(analysis.source_name, "test_rom_eur.sms");
@@ -165,21 +339,105 @@ mod tests {
 
     #[test]
     fn test_analyze_mastersystem_data_unknown() -> Result<(), RomAnalyzerError> {
-        let mut data = vec![0; 0x7FFD];
-        data[0x7FFC] = 0x00; // Unknown region
+        let data = generate_sms_header(0x8000, 0x7FF0, 0x00); // Unknown region
         let analysis = analyze_mastersystem_data(&data, "test_rom.sms")?;
 
         assert_eq!(analysis.source_name, "test_rom.sms");
         assert_eq!(analysis.region_byte, 0x00);
         assert_eq!(analysis.region, Region::UNKNOWN);
         assert_eq!(analysis.region_string, "Unknown");
+        assert_eq!(analysis.region_confidence, Confidence::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_mastersystem_data_16kb_header() -> Result<(), RomAnalyzerError> {
+        // 16KB ROMs store the header at 0x3ff0.
+        let data = generate_sms_header(0x4000, 0x3FF0, 0x4A);
+        let analysis = analyze_mastersystem_data(&data, "small_game.sms")?;
+
+        assert_eq!(analysis.region_byte, 0x4A);
+        assert_eq!(analysis.region, Region::USA | Region::EUROPE);
+        assert_eq!(analysis.region_confidence, Confidence::FromHeader);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_mastersystem_data_no_header_infers_from_filename()
+    -> Result<(), RomAnalyzerError> {
+        let data = vec![0; 0x8000];
+        let analysis = analyze_mastersystem_data(&data, "game (Japan).sms")?;
+
+        assert_eq!(analysis.region_byte, 0x00);
+        assert_eq!(analysis.region, Region::JAPAN);
+        assert_eq!(analysis.region_string, "Japan");
+        assert_eq!(analysis.region_confidence, Confidence::FromFilename);
+        assert!(!analysis.header_found);
+        assert_eq!(analysis.checksum_valid, None);
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "game (Japan).sms\n\
+             System:       Sega Master System\n\
+             Region Code:  0x00\n\
+             Region:       Japan\n\
+             Note:         Region information not in ROM header, inferred from filename."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_mastersystem_data_valid_checksum() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_sms_header(0x10000, 0x7FF0, 0x4E); // 64KB size code
+        data[0x100] = 0x12;
+        data[0x9000] = 0x34;
+        data[0x7FFA..0x7FFC].copy_from_slice(&0x46u16.to_le_bytes());
+        let analysis = analyze_mastersystem_data(&data, "game.sms")?;
+
+        assert!(analysis.header_found);
+        assert_eq!(analysis.checksum_valid, Some(true));
+        assert_eq!(analysis.region, Region::USA | Region::EUROPE);
+        assert!(!analysis.print().contains("Checksum:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_mastersystem_data_corrupted_checksum() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_sms_header(0x8000, 0x7FF0, 0x4C);
+        data[0x7FFA..0x7FFC].copy_from_slice(&0x1234u16.to_le_bytes());
+        data[0x200] = 0xFF; // Doesn't add up to the stored checksum.
+        let analysis = analyze_mastersystem_data(&data, "game.sms")?;
+
+        assert!(analysis.header_found);
+        assert_eq!(analysis.checksum_valid, Some(false));
+        assert_eq!(analysis.region_confidence, Confidence::FromHeader);
+        assert_eq!(
+            analysis.print(),
+            "game.sms\n\
+             System:       Sega Master System\n\
+             Region Code:  0x4C\n\
+             Region:       USA/Europe\n\
+             Checksum:     Invalid (possible bad dump)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_mastersystem_data_checksum_range_not_covered() -> Result<(), RomAnalyzerError> {
+        // A 256KB size code with only the first 128KB available, as extracted from a ZIP.
+        let data = generate_sms_header(0x20000, 0x7FF0, 0x40);
+        let analysis = analyze_mastersystem_data(&data, "game.sms")?;
+
+        assert!(analysis.header_found);
+        assert_eq!(analysis.checksum_valid, None);
+        assert!(!analysis.print().contains("Checksum:"));
         Ok(())
     }
 
     #[test]
     fn test_analyze_mastersystem_data_too_small() {
-        // Test with data smaller than the minimum required size for analysis.
-        let data = vec![0; 100]; // Smaller than 0x7FFD
+        // Test with data too small to hold a header at any known location.
+        let data = vec![0; 100];
         let result = analyze_mastersystem_data(&data, "too_small.sms");
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("too small"));
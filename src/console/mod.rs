@@ -4,13 +4,183 @@
 //! and data structures for parsing ROM headers, extracting metadata, and performing
 //! other console-specific analyses.
 
+pub mod atari7800;
+pub mod cdimage;
+pub mod gamecube;
 pub mod gamegear;
 pub mod gb;
 pub mod gba;
 pub mod genesis;
+pub mod intellivision;
 pub mod mastersystem;
 pub mod n64;
+pub mod neogeo;
 pub mod nes;
+pub mod psp;
 pub mod psx;
 pub mod segacd;
 pub mod snes;
+pub mod threedo;
+pub mod unknown;
+
+use crate::region::{Confidence, Region};
+
+/// Common metadata shared by every console-specific analysis struct.
+///
+/// This allows callers to read the fields every analysis has in common without matching on the
+/// console type, e.g. through [`crate::RomAnalysisResult::metadata`].
+pub trait RomMetadata {
+    /// Returns the name of the source file.
+    fn source_name(&self) -> &str;
+    /// Returns the identified region(s) as a [`Region`] bitmask.
+    fn region(&self) -> Region;
+    /// Returns the region name as written in the ROM header (e.g. "Japan (NTSC)").
+    fn region_string(&self) -> &str;
+    /// Returns whether the region in the ROM header doesn't match the region in the filename.
+    fn region_mismatch(&self) -> bool;
+    /// Returns how the region was determined.
+    fn region_confidence(&self) -> Confidence;
+    /// Returns the path of the archive (ZIP, CHD or CSO) the ROM was read from, if any.
+    fn archive_source(&self) -> Option<&str>;
+    /// Returns the offset and value of the header's region byte when it maps to no known region,
+    /// for debugging odd ROMs. The offset is relative to the analyzed ROM data.
+    ///
+    /// Consoles without a region byte always return `None`.
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        None
+    }
+    /// Returns the analysis results as ordered label/value pairs (e.g. "System" and "Super
+    /// Nintendo (SNES)"), for callers that render them their own way.
+    ///
+    /// The source name is not included, see [`RomMetadata::source_name`].
+    fn to_fields(&self) -> Vec<(String, String)>;
+    /// Returns a printable String of the analysis results.
+    ///
+    /// This lists the source name, followed by an aligned "Label: value" line for each of the
+    /// [`RomMetadata::to_fields`].
+    fn print(&self) -> String {
+        self.to_fields()
+            .iter()
+            .fold(self.source_name().to_string(), |output, (label, value)| {
+                format!("{}\n{:<13} {}", output, format!("{}:", label), value)
+            })
+    }
+}
+
+/// Builds a label/value pair for [`RomMetadata::to_fields`].
+pub(crate) fn field(label: &str, value: impl ToString) -> (String, String) {
+    (label.to_string(), value.to_string())
+}
+
+/// Returns the "Archive" field listed first in each analysis.
+///
+/// Returns `None` when the ROM was not read from an archive.
+pub(crate) fn archive_source_field(archive_source: Option<&str>) -> Option<(String, String)> {
+    archive_source.map(|archive| field("Archive", archive))
+}
+
+/// Returns the "ROM Size" field listed when the ROM size doesn't match the size in its header.
+///
+/// Returns `None` unless `size_mismatch` is set.
+pub(crate) fn size_mismatch_field(
+    size_mismatch: bool,
+    declared_size: Option<usize>,
+    rom_size: usize,
+) -> Option<(String, String)> {
+    declared_size
+        .filter(|_| size_mismatch)
+        .map(|declared_size| {
+            field(
+                "ROM Size",
+                format!(
+                    "{} bytes, header declares {} (overdumped or truncated)",
+                    rom_size, declared_size
+                ),
+            )
+        })
+}
+
+/// Returns the "Note" field listed when a header-based analyzer found no known region.
+///
+/// Returns `None` unless `confidence` is [`Confidence::Unknown`].
+pub(crate) fn region_unknown_field(confidence: Confidence) -> Option<(String, String)> {
+    match confidence {
+        Confidence::Unknown => Some(field(
+            "Note",
+            "Region could not be determined from the ROM header.",
+        )),
+        Confidence::FromHeader | Confidence::FromFilename => None,
+    }
+}
+
+/// Test helpers to capture the log records emitted by analyzers.
+#[cfg(test)]
+pub(crate) mod test_logger {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::sync::Once;
+
+    use log::kv::{Error, Key, Value, VisitSource};
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+
+    /// A log record captured by [`capture_logs`], along with its key-value fields.
+    #[derive(Debug)]
+    pub(crate) struct CapturedRecord {
+        pub(crate) level: Level,
+        pub(crate) message: String,
+        pub(crate) fields: BTreeMap<String, String>,
+    }
+
+    thread_local! {
+        static RECORDS: RefCell<Option<Vec<CapturedRecord>>> = const { RefCell::new(None) };
+    }
+
+    struct CaptureLogger;
+
+    struct FieldCollector<'a>(&'a mut BTreeMap<String, String>);
+
+    impl<'kvs> VisitSource<'kvs> for FieldCollector<'_> {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+            self.0.insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+    }
+
+    impl Log for CaptureLogger {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            RECORDS.with(|records| {
+                if let Some(records) = records.borrow_mut().as_mut() {
+                    let mut fields = BTreeMap::new();
+                    let _ = record.key_values().visit(&mut FieldCollector(&mut fields));
+                    records.push(CapturedRecord {
+                        level: record.level(),
+                        message: record.args().to_string(),
+                        fields,
+                    });
+                }
+            });
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger;
+    static INIT: Once = Once::new();
+
+    /// Runs `f` and returns the log records it emitted on the current thread.
+    ///
+    /// Records are kept per thread, so tests running in parallel don't see each other's logs.
+    pub(crate) fn capture_logs<F: FnOnce()>(f: F) -> Vec<CapturedRecord> {
+        INIT.call_once(|| {
+            let _ = log::set_logger(&LOGGER);
+            log::set_max_level(LevelFilter::Trace);
+        });
+        RECORDS.with(|records| *records.borrow_mut() = Some(Vec::new()));
+        f();
+        RECORDS.with(|records| records.borrow_mut().take().unwrap_or_default())
+    }
+}
@@ -1,15 +1,45 @@
 //! Provides header analysis functionality for Nintendo 64 (N64) ROMs.
 //!
-//! This module can parse N64 ROM headers to extract country code and infer
-//! the geographical region.
+//! This module can parse N64 ROM headers to extract the internal name, game code
+//! and country code, and infer the geographical region. The two header CRC words
+//! are also verified against the first 1MB of the game, using the CIC-6102 seed.
 //!
 //! N64 header documentation referenced here:
 //! <https://en64.shoutwiki.com/wiki/ROM>
 
+use log::debug;
 use serde::Serialize;
 
+use crate::console::{RomMetadata, archive_source_field, field, region_unknown_field};
 use crate::error::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch};
+use crate::region::{Confidence, Region, check_region_mismatch};
+use crate::util::sanitize_title;
+
+/// N64 header is at offset 0x0, ending with the country code at 0x3E (2 bytes).
+pub(crate) const HEADER_SIZE: usize = 0x40;
+/// The internal game name fills 20 bytes starting at 0x20.
+const INTERNAL_NAME_START: usize = 0x20;
+const INTERNAL_NAME_END: usize = 0x34;
+/// The game code is the media type (e.g. 'N' for a cartridge), the two-character unique code and
+/// the country code, starting at 0x3B.
+const GAME_CODE_START: usize = 0x3B;
+const GAME_CODE_END: usize = 0x3F;
+
+/// The country code follows the game code's unique code, and is also its region letter.
+const COUNTRY_CODE_OFFSET: usize = 0x3E;
+
+/// The CRC words computed by the boot code, stored big-endian at 0x10 and 0x14.
+const CRC1_OFFSET: usize = 0x10;
+const CRC2_OFFSET: usize = 0x14;
+/// The CRC covers the 1MB following the 4KB header and boot code.
+const CRC_START: usize = 0x1000;
+const CRC_LENGTH: usize = 0x100000;
+/// The seed of the CIC-6102 (and its PAL twin, the CIC-7101), used by most retail games.
+const CIC_6102_SEED: u32 = 0xF8CA4DDC;
+/// The first word of byte-swapped (.v64) and little-endian (.n64) dumps, which are only
+/// identified to skip the CRC check.
+const BYTESWAPPED_MAGIC: [u8; 4] = [0x37, 0x80, 0x40, 0x12];
+const LITTLE_ENDIAN_MAGIC: [u8; 4] = [0x40, 0x12, 0x37, 0x80];
 
 /// Struct to hold the analysis results for an N64 ROM.
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -22,20 +52,71 @@ pub struct N64Analysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region byte, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
     /// The country code extracted from the ROM header (e.g., "E", "J").
     pub country_code: String,
+    /// The internal game name from the ROM header (e.g., "SUPER MARIO 64").
+    pub internal_name: String,
+    /// The four-character game code from the ROM header (e.g., "NSME"), or empty if it's blank.
+    pub game_code: String,
+    /// If the header CRC words match the CRC computed with the CIC-6102 seed. `None` if the ROM
+    /// is smaller than the 1MB the CRC covers or isn't stored big-endian.
+    pub crc_valid: Option<bool>,
 }
 
-impl N64Analysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        format!(
-            "{}\n\
-             System:       Nintendo 64 (N64)\n\
-             Region:       {}\n\
-             Code:         {}",
-            self.source_name, self.region, self.country_code
-        )
+impl RomMetadata for N64Analysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Nintendo 64 (N64)")),
+            (!self.internal_name.is_empty()).then(|| field("Internal Name", &self.internal_name)),
+            (!self.game_code.is_empty()).then(|| field("Game Code", &self.game_code)),
+            Some(field("Region", self.region)),
+            Some(field("Code", &self.country_code)),
+            region_unknown_field(self.region_confidence),
+            (self.crc_valid == Some(false)).then(|| {
+                field(
+                    "Checksum",
+                    "Invalid CRC (possible bad dump, hack or non-6102 CIC)",
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -89,11 +170,65 @@ pub fn map_region(country_code: &str) -> (&'static str, Region) {
     }
 }
 
+/// Computes the two N64 header CRC words the way the CIC-6102 boot code does.
+///
+/// The CRC is seeded by the CIC lockout chip, so ROMs booting with another CIC (such as the
+/// CIC-6105 used by Banjo-Kazooie or The Legend of Zelda: Ocarina of Time) don't match the
+/// result of this function even when they are good dumps.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw, big-endian (.z64) ROM data.
+///
+/// # Returns
+///
+/// The CRC1 and CRC2 words, or `None` if the data is smaller than the 1MB after the first 4KB
+/// that the CRC covers.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::n64::calculate_crc_6102;
+///
+/// assert_eq!(
+///     calculate_crc_6102(&vec![0; 0x101000]),
+///     Some((0xF8CA4DDC, 0x303A4DDC))
+/// );
+/// assert_eq!(calculate_crc_6102(&[0; 0x40]), None);
+/// ```
+pub fn calculate_crc_6102(data: &[u8]) -> Option<(u32, u32)> {
+    let checked = data.get(CRC_START..CRC_START + CRC_LENGTH)?;
+    let [mut t1, mut t2, mut t3, mut t4, mut t5, mut t6] = [CIC_6102_SEED; 6];
+    for word in checked.chunks_exact(4) {
+        let d = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        let (sum, overflowed) = t6.overflowing_add(d);
+        if overflowed {
+            t4 = t4.wrapping_add(1);
+        }
+        t6 = sum;
+        t3 ^= d;
+        let r = d.rotate_left(d & 0x1F);
+        t5 = t5.wrapping_add(r);
+        if t2 > d {
+            t2 ^= r;
+        } else {
+            t2 ^= t6 ^ d;
+        }
+        t1 = t1.wrapping_add(t5 ^ d);
+    }
+    Some((t6 ^ t4 ^ t3, t5 ^ t2 ^ t1))
+}
+
 /// Analyzes N64 ROM data.
 ///
-/// This function reads the N64 ROM header to extract the country code.
-/// It then maps the country code to a human-readable region name and performs
-/// a region mismatch check against the `source_name`.
+/// This function reads the N64 ROM header to extract the internal name, game code and
+/// country code. It then maps the country code to a human-readable region name and performs
+/// a region mismatch check against the `source_name`. When the two-byte country code isn't
+/// recognized (e.g. because the version byte following it is set), the region letter ending the
+/// game code is used instead.
+///
+/// If the data covers the first 1MB of the game, the header CRC words are also verified with
+/// [`calculate_crc_6102`]. Games using another CIC chip report an invalid CRC.
 ///
 /// # Arguments
 ///
@@ -106,8 +241,6 @@ pub fn map_region(country_code: &str) -> (&'static str, Region) {
 /// - `Ok`([`N64Analysis`]) containing the detailed analysis results.
 /// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to contain a valid N64 header.
 pub fn analyze_n64_data(data: &[u8], source_name: &str) -> Result<N64Analysis, RomAnalyzerError> {
-    // N64 header is at offset 0x0. Country code is at offset 0x3E (2 bytes).
-    const HEADER_SIZE: usize = 0x40;
     if data.len() < HEADER_SIZE {
         return Err(RomAnalyzerError::DataTooSmall {
             file_size: data.len(),
@@ -118,21 +251,65 @@ pub fn analyze_n64_data(data: &[u8], source_name: &str) -> Result<N64Analysis, R
 
     // Extract Country Code (2 bytes, ASCII)
     // The second byte is often a null terminator, or part of a two-character code.
-    let country_code = String::from_utf8_lossy(&data[0x3E..0x40])
+    let country_code = String::from_utf8_lossy(&data[COUNTRY_CODE_OFFSET..COUNTRY_CODE_OFFSET + 2])
         .trim_matches(char::from(0))
         .to_string();
 
-    // Determine region name based on the country code.
-    let (region_name, region) = map_region(&country_code);
+    let internal_name = sanitize_title(&data[INTERNAL_NAME_START..INTERNAL_NAME_END]);
+    // Homebrew and prototype headers often leave the game code blank, so a partial code is
+    // dropped rather than reported.
+    let game_code_bytes = &data[GAME_CODE_START..GAME_CODE_END];
+    let game_code = if game_code_bytes.iter().all(u8::is_ascii_alphanumeric) {
+        String::from_utf8_lossy(game_code_bytes).to_string()
+    } else {
+        String::new()
+    };
+
+    // Determine region name based on the country code, falling back to the game code's region
+    // letter, which is the first byte of the country code.
+    let (mut region_name, mut region) = map_region(&country_code);
+    let raw_region_info =
+        (region == Region::UNKNOWN).then_some((COUNTRY_CODE_OFFSET, data[COUNTRY_CODE_OFFSET]));
+    if region == Region::UNKNOWN && !game_code.is_empty() {
+        (region_name, region) = map_region(&game_code[3..]);
+    }
 
     let region_mismatch = check_region_mismatch(source_name, region);
 
+    let crc_valid =
+        if data.starts_with(&BYTESWAPPED_MAGIC) || data.starts_with(&LITTLE_ENDIAN_MAGIC) {
+            None
+        } else {
+            calculate_crc_6102(data).map(|(crc1, crc2)| {
+                let stored = |offset: usize| {
+                    u32::from_be_bytes([
+                        data[offset],
+                        data[offset + 1],
+                        data[offset + 2],
+                        data[offset + 3],
+                    ])
+                };
+                let (stored1, stored2) = (stored(CRC1_OFFSET), stored(CRC2_OFFSET));
+                debug!(
+                    "CRC: stored 0x{:08X} 0x{:08X}, computed 0x{:08X} 0x{:08X}",
+                    stored1, stored2, crc1, crc2
+                );
+                (stored1, stored2) == (crc1, crc2)
+            })
+        };
+
     Ok(N64Analysis {
         source_name: source_name.to_string(),
         region,
         region_string: region_name.to_string(),
         region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
+        raw_region_info,
         country_code,
+        internal_name,
+        game_code,
+        crc_valid,
     })
 }
 
@@ -171,6 +348,103 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_n64_data_internal_name_and_game_code() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_n64_header("E");
+        data[0x20..0x34].copy_from_slice(b"SUPER MARIO 64      ");
+        data[0x3B..0x3E].copy_from_slice(b"NSM");
+        let analysis = analyze_n64_data(&data, "Super Mario 64 (USA).z64")?;
+
+        assert_eq!(analysis.internal_name, "SUPER MARIO 64");
+        assert_eq!(analysis.game_code, "NSME");
+        assert_eq!(analysis.region, Region::USA);
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "Super Mario 64 (USA).z64\n\
+             System:       Nintendo 64 (N64)\n\
+             Internal Name: SUPER MARIO 64\n\
+             Game Code:    NSME\n\
+             Region:       USA\n\
+             Code:         E"
+        );
+
+        // A set version byte spoils the two-byte country code, so the game code decides.
+        data[0x3F] = 0x01;
+        let analysis = analyze_n64_data(&data, "Super Mario 64 (USA) (Rev 1).z64")?;
+        assert_eq!(analysis.country_code, "E\u{1}");
+        assert_eq!(analysis.region, Region::USA);
+        assert_eq!(analysis.region_string, "USA (NTSC)");
+        Ok(())
+    }
+
+    /// Helper function to generate a big-endian ROM covering the CRC, filled with a pattern whose
+    /// CIC-6102 CRC was computed independently.
+    fn generate_n64_crc_fixture() -> Vec<u8> {
+        let mut data: Vec<u8> = (0..CRC_START + CRC_LENGTH)
+            .map(|i| (i * 31 + 7) as u8)
+            .collect();
+        data[0..4].copy_from_slice(&[0x80, 0x37, 0x12, 0x40]);
+        data[0x10..0x14].copy_from_slice(&0xF6C453DEu32.to_be_bytes());
+        data[0x14..0x18].copy_from_slice(&0xD7A09970u32.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn test_analyze_n64_data_crc_valid() -> Result<(), RomAnalyzerError> {
+        let data = generate_n64_crc_fixture();
+        assert_eq!(calculate_crc_6102(&data), Some((0xF6C453DE, 0xD7A09970)));
+        let analysis = analyze_n64_data(&data, "game.z64")?;
+        assert_eq!(analysis.crc_valid, Some(true));
+        assert!(!analysis.print().contains("Checksum:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_n64_data_crc_invalid() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_n64_crc_fixture();
+        data[0x80000] ^= 0x01;
+        let analysis = analyze_n64_data(&data, "game.z64")?;
+        assert_eq!(analysis.crc_valid, Some(false));
+        assert!(analysis.print().contains("\nChecksum:     Invalid CRC"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_n64_data_crc_skipped() -> Result<(), RomAnalyzerError> {
+        // ROMs smaller than the checked range aren't verified.
+        let analysis = analyze_n64_data(&generate_n64_header("E"), "game.z64")?;
+        assert_eq!(analysis.crc_valid, None);
+
+        // Neither are byte-swapped dumps.
+        let mut data = generate_n64_crc_fixture();
+        data[0..4].copy_from_slice(&BYTESWAPPED_MAGIC);
+        assert_eq!(analyze_n64_data(&data, "game.v64")?.crc_valid, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_n64_to_fields() -> Result<(), RomAnalyzerError> {
+        let data = generate_n64_header("E");
+        let mut analysis = analyze_n64_data(&data, "test_rom_us.n64")?;
+        assert_eq!(
+            analysis.to_fields(),
+            vec![
+                field("System", "Nintendo 64 (N64)"),
+                field("Region", "USA"),
+                field("Code", "E"),
+            ]
+        );
+
+        // Optional fields are only listed when set.
+        analysis.archive_source = Some("roms.zip".to_string());
+        analysis.region_confidence = Confidence::Unknown;
+        let fields = analysis.to_fields();
+        assert_eq!(fields.first(), Some(&field("Archive", "roms.zip")));
+        assert_eq!(fields.last().map(|(label, _)| label.as_str()), Some("Note"));
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_n64_data_japan() -> Result<(), RomAnalyzerError> {
         let data = generate_n64_header("J"); // Japan region
@@ -240,6 +514,15 @@ mod tests {
         assert_eq!(analysis.region, Region::UNKNOWN);
         assert_eq!(analysis.region_string, "Unknown");
         assert_eq!(analysis.country_code, "X");
+        assert_eq!(analysis.region_confidence, Confidence::Unknown);
+        assert_eq!(
+            analysis.print(),
+            "test_rom.n64\n\
+             System:       Nintendo 64 (N64)\n\
+             Region:       Unknown\n\
+             Code:         X\n\
+             Note:         Region could not be determined from the ROM header."
+        );
         Ok(())
     }
 
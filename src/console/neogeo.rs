@@ -0,0 +1,291 @@
+//! Provides header analysis functionality for Neo Geo (MVS/AES) ROMs.
+//!
+//! Neo Geo games are dumped as a set of separate chip ROMs with no single-file header, so only
+//! the `.neo` single-file format used by the NeoSD cartridge is supported. Its 4096-byte header
+//! carries the game name, manufacturer, year, genre and NGH number. The header has no region
+//! information, so the region is inferred from the filename.
+
+use serde::Serialize;
+
+use crate::console::{RomMetadata, archive_source_field, field};
+use crate::error::RomAnalyzerError;
+use crate::region::{Confidence, Region, check_region_mismatch, infer_region_from_filename};
+use crate::util::sanitize_title;
+
+const NEO_SIGNATURE: &[u8] = b"NEO";
+// The header is followed by the chip ROMs, all fields are little-endian.
+pub(crate) const NEO_HEADER_SIZE: usize = 0x1000;
+const NEO_YEAR: usize = 0x1C;
+const NEO_GENRE: usize = 0x20;
+const NEO_NGH: usize = 0x28;
+const NEO_NAME_START: usize = 0x2C;
+const NEO_NAME_END: usize = 0x4D;
+const NEO_MANUFACTURER_START: usize = 0x4D;
+const NEO_MANUFACTURER_END: usize = 0x5E;
+
+/// Struct to hold the analysis results for a Neo Geo ROM.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct NeoGeoAnalysis {
+    /// The name of the source file.
+    pub source_name: String,
+    /// The region(s) inferred from the filename as a region::Region bitmask.
+    pub region: Region,
+    /// The inferred region name (e.g., "Japan").
+    pub region_string: String,
+    /// If the region in the ROM header doesn't match the region in the filename.
+    pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The game name extracted from the header.
+    pub game_name: String,
+    /// The manufacturer extracted from the header.
+    pub manufacturer: String,
+    /// The release year extracted from the header.
+    pub year: u32,
+    /// The genre extracted from the header (e.g., "Fighting").
+    pub genre: String,
+    /// The NGH number identifying the game (e.g., "201").
+    pub ngh_number: String,
+}
+
+impl RomMetadata for NeoGeoAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Neo Geo")),
+            Some(field("Game Name", &self.game_name)),
+            Some(field("Manufacturer", &self.manufacturer)),
+            Some(field("Year", self.year)),
+            Some(field("Genre", &self.genre)),
+            Some(field("NGH Number", &self.ngh_number)),
+            Some(field("Region", self.region)),
+            Some(field(
+                "Note",
+                "Region information not in ROM header, inferred from filename.",
+            )),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Determines the genre name of a Neo Geo game from the genre code in a `.neo` header.
+///
+/// # Arguments
+///
+/// * `genre_code` - The genre code from the header.
+///
+/// # Returns
+///
+/// A `&'static str` naming the genre, or "Unknown" if the code is not recognized.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::neogeo::map_genre;
+///
+/// assert_eq!(map_genre(9), "Fighting");
+/// assert_eq!(map_genre(0), "Other");
+/// assert_eq!(map_genre(99), "Unknown");
+/// ```
+pub fn map_genre(genre_code: u32) -> &'static str {
+    match genre_code {
+        0 => "Other",
+        1 => "Action",
+        2 => "Beat 'em up",
+        3 => "Sports",
+        4 => "Driving",
+        5 => "Platformer",
+        6 => "Mahjong",
+        7 => "Shooter",
+        8 => "Quiz",
+        9 => "Fighting",
+        10 => "Puzzle",
+        _ => "Unknown",
+    }
+}
+
+/// Reads a little-endian u32 from the header at the given offset.
+fn read_u32_le(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// Analyzes Neo Geo ROM data in the NeoSD `.neo` format.
+///
+/// This function validates the `NEO` signature, then extracts the game name, manufacturer,
+/// year, genre and NGH number from the header. The NGH number is stored the same way as in the
+/// cartridge's own header, as BCD, so it is shown in hexadecimal (e.g. 0x201 as "201"). As the
+/// header has no region information, the region is inferred from the `source_name`.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `source_name` - The name of the ROM file, used for region inference.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`NeoGeoAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small, or
+///   [`RomAnalyzerError::InvalidSignature`] if the `NEO` signature is missing.
+pub fn analyze_neogeo_data(
+    data: &[u8],
+    source_name: &str,
+) -> Result<NeoGeoAnalysis, RomAnalyzerError> {
+    if data.len() < NEO_HEADER_SIZE {
+        return Err(RomAnalyzerError::DataTooSmall {
+            file_size: data.len(),
+            required_size: NEO_HEADER_SIZE,
+            details: "Neo Geo .neo header".to_string(),
+        });
+    }
+
+    let signature = &data[0..NEO_SIGNATURE.len()];
+    if signature != NEO_SIGNATURE {
+        return Err(RomAnalyzerError::InvalidSignature {
+            expected: NEO_SIGNATURE.escape_ascii().to_string(),
+            found: signature.escape_ascii().to_string(),
+            offset: 0,
+        });
+    }
+
+    let game_name = sanitize_title(&data[NEO_NAME_START..NEO_NAME_END]);
+    let manufacturer = sanitize_title(&data[NEO_MANUFACTURER_START..NEO_MANUFACTURER_END]);
+    let year = read_u32_le(data, NEO_YEAR);
+    let genre = map_genre(read_u32_le(data, NEO_GENRE)).to_string();
+    let ngh_number = format!("{:03X}", read_u32_le(data, NEO_NGH));
+
+    let region = infer_region_from_filename(source_name);
+    let region_mismatch = check_region_mismatch(source_name, region);
+
+    Ok(NeoGeoAnalysis {
+        source_name: source_name.to_string(),
+        region,
+        region_string: region.to_string(),
+        region_mismatch,
+        region_confidence: Confidence::from_filename(region),
+        archive_source: None,
+        game_name,
+        manufacturer,
+        year,
+        genre,
+        ngh_number,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_neo_header(
+        name: &str,
+        manufacturer: &str,
+        year: u32,
+        genre: u32,
+        ngh: u32,
+    ) -> Vec<u8> {
+        let mut data = vec![0; NEO_HEADER_SIZE];
+        data[0..3].copy_from_slice(NEO_SIGNATURE);
+        data[3] = 1; // Version
+        data[NEO_YEAR..NEO_YEAR + 4].copy_from_slice(&year.to_le_bytes());
+        data[NEO_GENRE..NEO_GENRE + 4].copy_from_slice(&genre.to_le_bytes());
+        data[NEO_NGH..NEO_NGH + 4].copy_from_slice(&ngh.to_le_bytes());
+        data[NEO_NAME_START..NEO_NAME_START + name.len()].copy_from_slice(name.as_bytes());
+        data[NEO_MANUFACTURER_START..NEO_MANUFACTURER_START + manufacturer.len()]
+            .copy_from_slice(manufacturer.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_analyze_neogeo_data() -> Result<(), RomAnalyzerError> {
+        let data = generate_neo_header("Metal Slug", "Nazca", 1996, 7, 0x201);
+        let analysis = analyze_neogeo_data(&data, "mslug (Japan).neo")?;
+
+        assert_eq!(analysis.game_name, "Metal Slug");
+        assert_eq!(analysis.manufacturer, "Nazca");
+        assert_eq!(analysis.year, 1996);
+        assert_eq!(analysis.genre, "Shooter");
+        assert_eq!(analysis.ngh_number, "201");
+        assert_eq!(analysis.region, Region::JAPAN);
+        assert_eq!(analysis.region_string, "Japan");
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "mslug (Japan).neo\n\
+             System:       Neo Geo\n\
+             Game Name:    Metal Slug\n\
+             Manufacturer: Nazca\n\
+             Year:         1996\n\
+             Genre:        Shooter\n\
+             NGH Number:   201\n\
+             Region:       Japan\n\
+             Note:         Region information not in ROM header, inferred from filename."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_neogeo_data_full_length_name() -> Result<(), RomAnalyzerError> {
+        // The name field holds 33 bytes, and must not run into the manufacturer.
+        let name = "The King of Fighters '98 Dream M!";
+        let data = generate_neo_header(name, "SNK", 1998, 9, 0x242);
+        let analysis = analyze_neogeo_data(&data, "kof98.neo")?;
+
+        assert_eq!(analysis.game_name, name);
+        assert_eq!(analysis.manufacturer, "SNK");
+        assert_eq!(analysis.genre, "Fighting");
+        assert_eq!(analysis.ngh_number, "242");
+        assert_eq!(analysis.region, Region::UNKNOWN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_neogeo_data_invalid_signature() {
+        let data = vec![0; NEO_HEADER_SIZE];
+        let result = analyze_neogeo_data(&data, "game.neo");
+        assert!(matches!(
+            result,
+            Err(RomAnalyzerError::InvalidSignature { offset: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_analyze_neogeo_data_too_small() {
+        let data = generate_neo_header("Metal Slug", "Nazca", 1996, 7, 0x201);
+        let result = analyze_neogeo_data(&data[..0x100], "game.neo");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("too small"));
+    }
+}
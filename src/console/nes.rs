@@ -1,7 +1,8 @@
 //! Provides header analysis functionality for Nintendo Entertainment System (NES) ROMs.
 //!
 //! This module supports both iNES and NES 2.0 header formats to extract region
-//! and other relevant information.
+//! and other relevant information. Headerless raw dumps are recognized by their size, and
+//! their region is inferred from the filename.
 //!
 //! NES header documentation referenced here:
 //! <https://www.nesdev.org/wiki/INES>
@@ -9,17 +10,28 @@
 
 use serde::Serialize;
 
+use crate::console::{RomMetadata, archive_source_field, field};
 use crate::error::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch};
+use crate::region::{
+    Confidence, Region, VideoSystem, check_region_mismatch, infer_region_from_filename,
+};
 
+pub(crate) const INES_SIGNATURE: &[u8] = b"NES\x1a";
 const INES_REGION_BYTE: usize = 9;
 const INES_REGION_MASK: u8 = 0x01;
+pub(crate) const INES_HEADER_SIZE: usize = 16;
+const INES_FLAGS_6_BYTE: usize = 6;
+const INES_TRAINER_MASK: u8 = 0x04;
+// A trainer sits between the header and the PRG ROM.
+const TRAINER_SIZE: usize = 512;
 
 const NES2_REGION_BYTE: usize = 12;
 const NES2_REGION_MASK: u8 = 0x03;
 const NES2_FORMAT_BYTE: usize = 7;
 const NES2_FORMAT_MASK: u8 = 0x0C;
 const NES2_FORMAT_EXPECTED_VALUE: u8 = 0x08;
+// Headerless dumps are made of whole 16KB PRG ROM banks.
+const HEADERLESS_BANK_SIZE: usize = 0x4000;
 
 /// Struct to hold the analysis results for a NES ROM.
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -32,28 +44,77 @@ pub struct NesAnalysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region byte, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
+    /// Whether the ROM has an iNES header, `false` for headerless raw dumps.
+    pub has_header: bool,
+    /// Whether a 512-byte trainer precedes the PRG ROM (iNES flag 6, bit 2).
+    pub has_trainer: bool,
+    /// The offset of the PRG ROM in the file, past the header and any trainer. No-Intro
+    /// checksums cover the data from here on.
+    pub payload_offset: usize,
     /// The raw byte value used for region determination (from iNES flag 9 or NES2 flag 12).
     pub region_byte_value: u8,
     /// Whether the ROM header is in NES 2.0 format.
     pub is_nes2_format: bool,
+    /// The video timing encoded in the header.
+    pub video_system: VideoSystem,
 }
 
-impl NesAnalysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        let nes_flag_display = if self.is_nes2_format {
-            format!("\nNES2.0 Flag 12: 0x{:02X}", self.region_byte_value)
+impl RomMetadata for NesAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        let nes_flag = if !self.has_header {
+            field("Note", "No iNES header, region inferred from filename.")
+        } else if self.is_nes2_format {
+            field(
+                "NES2.0 Flag 12",
+                format!("0x{:02X}", self.region_byte_value),
+            )
         } else {
-            format!("\niNES Flag 9:  0x{:02X}", self.region_byte_value)
+            field("iNES Flag 9", format!("0x{:02X}", self.region_byte_value))
         };
-
-        format!(
-            "{}\n\
-             System:       Nintendo Entertainment System (NES)\n\
-             Region:       {}\
-             {}",
-            self.source_name, self.region, nes_flag_display
-        )
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Nintendo Entertainment System (NES)")),
+            Some(field("Region", self.region)),
+            Some(nes_flag),
+            self.has_trainer.then(|| field("Trainer", "present")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -113,58 +174,164 @@ pub fn map_region(region_byte: u8, nes2_format: bool) -> (&'static str, Region)
     }
 }
 
+/// Determines the NES video timing based on a given region byte.
+///
+/// Both header formats encode the CPU/PPU timing directly, so this is exact rather than
+/// inferred from the region.
+///
+/// # Arguments
+///
+/// * `region_byte` - The byte containing the timing bits (iNES byte 9 or NES 2.0 byte 12).
+/// * `nes2_format` - Whether the header is in NES 2.0 format.
+///
+/// # Returns
+///
+/// The [`VideoSystem`] encoded in the header. The Dendy timing (NES 2.0 value 3) runs at 50Hz,
+/// so it is reported as [`VideoSystem::Pal`].
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::nes::map_video_system;
+/// use rom_analyzer::region::VideoSystem;
+///
+/// assert_eq!(map_video_system(0x00, false), VideoSystem::Ntsc);
+/// assert_eq!(map_video_system(0x01, false), VideoSystem::Pal);
+/// assert_eq!(map_video_system(0x02, true), VideoSystem::Multi);
+/// ```
+pub fn map_video_system(region_byte: u8, nes2_format: bool) -> VideoSystem {
+    if nes2_format {
+        match region_byte & NES2_REGION_MASK {
+            0 => VideoSystem::Ntsc,
+            1 | 3 => VideoSystem::Pal,
+            _ => VideoSystem::Multi,
+        }
+    } else if region_byte & INES_REGION_MASK == 0 {
+        VideoSystem::Ntsc
+    } else {
+        VideoSystem::Pal
+    }
+}
+
 /// Analyzes NES ROM data.
 ///
+/// This is [`analyze_nes_data_with_options`] with `strict` unset, so headerless raw dumps are
+/// accepted.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `source_name` - The name of the ROM file, used for region mismatch checks.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`NesAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small, or
+///   [`RomAnalyzerError::InvalidSignature`] if the iNES signature is missing from a file that
+///   isn't a headerless dump.
+pub fn analyze_nes_data(data: &[u8], source_name: &str) -> Result<NesAnalysis, RomAnalyzerError> {
+    analyze_nes_data_with_options(data, source_name, false)
+}
+
+/// Analyzes NES ROM data, optionally rejecting headerless dumps.
+///
 /// This function first validates the iNES header signature. It then determines
 /// if the ROM uses the NES 2.0 format or the older iNES format. Based on the
 /// detected format, it extracts the relevant region byte and maps it to a
 /// human-readable region name. A region mismatch check is also performed
 /// against the `source_name`.
 ///
+/// A trainer flagged in byte 6 is reported, and moves the [`NesAnalysis::payload_offset`] of
+/// the PRG ROM 512 bytes further into the file.
+///
+/// When the signature is missing but the data is a whole number of 16KB banks, the file is
+/// taken to be a headerless raw dump, and its region is inferred from the `source_name`. With
+/// `strict` set, such files fail with [`RomAnalyzerError::InvalidSignature`] instead.
+///
 /// # Arguments
 ///
 /// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
 /// * `source_name` - The name of the ROM file, used for region mismatch checks.
+/// * `strict` - Whether a missing iNES header should always fail the analysis.
 ///
 /// # Returns
 ///
 /// A `Result` which is:
 /// - `Ok`([`NesAnalysis`]) containing the detailed analysis results.
-/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small or has an invalid iNES signature.
-pub fn analyze_nes_data(data: &[u8], source_name: &str) -> Result<NesAnalysis, RomAnalyzerError> {
-    if data.len() < 16 {
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small, or
+///   [`RomAnalyzerError::InvalidSignature`] if the iNES signature is missing and the file isn't
+///   accepted as a headerless dump.
+pub fn analyze_nes_data_with_options(
+    data: &[u8],
+    source_name: &str,
+    strict: bool,
+) -> Result<NesAnalysis, RomAnalyzerError> {
+    if data.len() < INES_HEADER_SIZE {
         return Err(RomAnalyzerError::DataTooSmall {
             file_size: data.len(),
-            required_size: 16,
+            required_size: INES_HEADER_SIZE,
             details: "iNES header".to_string(),
         });
     }
 
     // All headered NES ROMs should begin with 'NES<EOF>'
     let signature = &data[0..4];
-    if signature != b"NES\x1a" {
-        return Err(RomAnalyzerError::InvalidHeader(
-            "Invalid iNES header signature. Not a valid NES ROM.".to_string(),
-        ));
+    if signature != INES_SIGNATURE && !strict && data.len().is_multiple_of(HEADERLESS_BANK_SIZE) {
+        let region = infer_region_from_filename(source_name);
+        return Ok(NesAnalysis {
+            source_name: source_name.to_string(),
+            region,
+            region_string: region.to_string(),
+            region_mismatch: false,
+            region_confidence: Confidence::from_filename(region),
+            archive_source: None,
+            raw_region_info: None,
+            has_header: false,
+            has_trainer: false,
+            payload_offset: 0,
+            region_byte_value: 0,
+            is_nes2_format: false,
+            video_system: VideoSystem::Unknown,
+        });
+    }
+    if signature != INES_SIGNATURE {
+        return Err(RomAnalyzerError::InvalidSignature {
+            expected: INES_SIGNATURE.escape_ascii().to_string(),
+            found: signature.escape_ascii().to_string(),
+            offset: 0,
+        });
     }
 
-    let mut region_byte_val = data[INES_REGION_BYTE];
     let is_nes2_format = (data[NES2_FORMAT_BYTE] & NES2_FORMAT_MASK) == NES2_FORMAT_EXPECTED_VALUE;
-
-    if is_nes2_format {
-        region_byte_val = data[NES2_REGION_BYTE];
-    }
+    let region_offset = if is_nes2_format {
+        NES2_REGION_BYTE
+    } else {
+        INES_REGION_BYTE
+    };
+    let region_byte_val = data[region_offset];
 
     let (region_name, region) = map_region(region_byte_val, is_nes2_format);
+    let raw_region_info = (region == Region::UNKNOWN).then_some((region_offset, region_byte_val));
     let region_mismatch = check_region_mismatch(source_name, region);
 
+    let has_trainer = data[INES_FLAGS_6_BYTE] & INES_TRAINER_MASK != 0;
+    let payload_offset = INES_HEADER_SIZE + if has_trainer { TRAINER_SIZE } else { 0 };
+
     Ok(NesAnalysis {
         source_name: source_name.to_string(),
         region,
         region_string: region_name.to_string(),
         region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
+        raw_region_info,
+        has_header: true,
+        has_trainer,
+        payload_offset,
         region_byte_value: region_byte_val,
         is_nes2_format,
+        video_system: map_video_system(region_byte_val, is_nes2_format),
     })
 }
 
@@ -236,6 +403,7 @@ mod tests {
         assert_eq!(analysis.region_string, "PAL (Europe/Oceania)");
         assert!(!analysis.is_nes2_format);
         assert_eq!(analysis.region_byte_value, 0x01);
+        assert_eq!(analysis.video_system, VideoSystem::Pal);
         Ok(())
     }
 
@@ -250,6 +418,7 @@ mod tests {
         assert_eq!(analysis.region_string, "NTSC (USA/Japan)");
         assert!(analysis.is_nes2_format);
         assert_eq!(analysis.region_byte_value, 0x00);
+        assert_eq!(analysis.video_system, VideoSystem::Ntsc);
         assert_eq!(
             analysis.print(),
             "test_rom_nes2_ntsc.nes\n\
@@ -271,6 +440,7 @@ mod tests {
         assert_eq!(analysis.region_string, "PAL (Europe/Oceania)");
         assert!(analysis.is_nes2_format);
         assert_eq!(analysis.region_byte_value, 0x01);
+        assert_eq!(analysis.video_system, VideoSystem::Pal);
         Ok(())
     }
 
@@ -288,6 +458,7 @@ mod tests {
         assert_eq!(analysis.region_string, "Multi-region");
         assert!(analysis.is_nes2_format);
         assert_eq!(analysis.region_byte_value, 0x02);
+        assert_eq!(analysis.video_system, VideoSystem::Multi);
         assert_eq!(
             analysis.print(),
             "test_rom_nes2_world.nes\n\
@@ -309,6 +480,20 @@ mod tests {
         assert_eq!(analysis.region_string, "Dendy (Russia)");
         assert!(analysis.is_nes2_format);
         assert_eq!(analysis.region_byte_value, 0x03);
+        assert_eq!(analysis.video_system, VideoSystem::Pal);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_nes_region_mismatch_uses_bitmask() -> Result<(), RomAnalyzerError> {
+        // NTSC covers both USA and Japan, so either filename region matches.
+        let data = generate_nes_header(NesHeaderType::Ines, 0x00);
+        assert!(!analyze_nes_data(&data, "Game (U).nes")?.region_mismatch);
+        assert!(!analyze_nes_data(&data, "Game (J).nes")?.region_mismatch);
+        assert!(analyze_nes_data(&data, "Game (E).nes")?.region_mismatch);
+
+        let data = generate_nes_header(NesHeaderType::Nes2, 0x02); // Multi-region
+        assert!(!analyze_nes_data(&data, "Game (E).nes")?.region_mismatch);
         Ok(())
     }
 
@@ -327,12 +512,82 @@ mod tests {
         let mut data = vec![0; 16];
         data[0..4].copy_from_slice(b"XXXX"); // Invalid signature
         let result = analyze_nes_data(&data, "invalid_sig.nes");
-        assert!(result.is_err());
-        assert!(
-            result
-                .unwrap_err()
-                .to_string()
-                .contains("Invalid iNES header signature")
+        match result {
+            Err(RomAnalyzerError::InvalidSignature {
+                expected,
+                found,
+                offset,
+            }) => {
+                assert_eq!(expected, "NES\\x1a");
+                assert_eq!(found, "XXXX");
+                assert_eq!(offset, 0);
+            }
+            other => panic!("Expected InvalidSignature error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_analyze_nes_headerless() -> Result<(), RomAnalyzerError> {
+        // A raw 32KB dump: two PRG banks and no iNES header.
+        let data = vec![0xEA; 0x8000];
+        let analysis = analyze_nes_data(&data, "Raw Game (Europe).nes")?;
+
+        assert!(!analysis.has_header);
+        assert_eq!(analysis.region, Region::EUROPE);
+        assert_eq!(analysis.region_string, "Europe");
+        assert!(!analysis.region_mismatch);
+        assert_eq!(analysis.video_system, VideoSystem::Unknown);
+        assert_eq!(
+            analysis.print(),
+            "Raw Game (Europe).nes\n\
+             System:       Nintendo Entertainment System (NES)\n\
+             Region:       Europe\n\
+             Note:         No iNES header, region inferred from filename."
         );
+
+        // Strict analysis still requires the header.
+        let result = analyze_nes_data_with_options(&data, "Raw Game (Europe).nes", true);
+        assert!(matches!(
+            result,
+            Err(RomAnalyzerError::InvalidSignature { offset: 0, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_nes_headered_is_not_headerless() -> Result<(), RomAnalyzerError> {
+        // A valid iNES file is analyzed from its header, strict or not.
+        let mut data = generate_nes_header(NesHeaderType::Ines, 0x01);
+        data.resize(16 + 0x8000, 0);
+        let analysis = analyze_nes_data_with_options(&data, "game.nes", true)?;
+
+        assert!(analysis.has_header);
+        assert!(!analysis.has_trainer);
+        assert_eq!(analysis.payload_offset, 16);
+        assert_eq!(analysis.region, Region::EUROPE);
+        assert_eq!(analysis, analyze_nes_data(&data, "game.nes")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_nes_trainer() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_nes_header(NesHeaderType::Ines, 0x00);
+        data[INES_FLAGS_6_BYTE] = 0x04 | 0x01; // Trainer, vertical mirroring
+        data.extend(vec![0xFF; TRAINER_SIZE]);
+        data.extend(vec![0xA9; 0x4000]);
+        let analysis = analyze_nes_data(&data, "trainer.nes")?;
+
+        assert!(analysis.has_trainer);
+        assert_eq!(analysis.payload_offset, 16 + 512);
+        assert_eq!(data[analysis.payload_offset], 0xA9);
+        assert_eq!(
+            analysis.print(),
+            "trainer.nes\n\
+             System:       Nintendo Entertainment System (NES)\n\
+             Region:       Japan/USA\n\
+             iNES Flag 9:  0x00\n\
+             Trainer:      present"
+        );
+        Ok(())
     }
 }
@@ -0,0 +1,511 @@
+//! Provides header analysis functionality for Sony PlayStation Portable (PSP) UMD images.
+//!
+//! PSP ISOs are ISO9660 images whose primary volume descriptor names the system as
+//! "PSP GAME". The disc ID (e.g. "ULUS10336") and title are read from `PSP_GAME/PARAM.SFO`,
+//! falling back to the disc ID at the start of `UMD_DATA.BIN` when the PARAM.SFO is not within
+//! the analyzed data. The region is derived from the disc ID prefix.
+
+use serde::Serialize;
+
+use crate::console::{RomMetadata, archive_source_field, field, region_unknown_field};
+use crate::error::RomAnalyzerError;
+use crate::region::{Confidence, Region, check_region_mismatch};
+
+const SECTOR_SIZE: usize = 0x800;
+// The primary volume descriptor lives in sector 16.
+const PVD_OFFSET: usize = 16 * SECTOR_SIZE;
+const PVD_SIGNATURE: &[u8] = b"\x01CD001";
+const PVD_SYSTEM_ID_START: usize = 0x08;
+const PSP_SYSTEM_ID: &[u8] = b"PSP GAME";
+const PVD_ROOT_RECORD: usize = 0x9C;
+
+// Directory record layout, all multi-byte fields are read from their little-endian half.
+const RECORD_EXTENT: usize = 0x02;
+const RECORD_DATA_LENGTH: usize = 0x0A;
+const RECORD_NAME_LENGTH: usize = 0x20;
+const RECORD_NAME: usize = 0x21;
+
+const SFO_MAGIC: &[u8] = b"\0PSF";
+const SFO_HEADER_SIZE: usize = 0x14;
+const SFO_INDEX_ENTRY_SIZE: usize = 0x10;
+const SFO_FORMAT_UTF8: u16 = 0x0204;
+
+/// Struct to hold the analysis results for a PSP UMD image.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct PspAnalysis {
+    /// The name of the source file.
+    pub source_name: String,
+    /// The identified region(s) as a region::Region bitmask.
+    pub region: Region,
+    /// The identified region name (e.g., "North America (NTSC-U)").
+    pub region_string: String,
+    /// If the region in the disc ID doesn't match the region in the filename.
+    pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The disc ID (e.g., "ULUS10336"), if found.
+    pub disc_id: Option<String>,
+    /// The game title from PARAM.SFO, if found.
+    pub title: Option<String>,
+}
+
+impl RomMetadata for PspAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        let note = if self.disc_id.is_none() {
+            Some(field(
+                "Note",
+                "PARAM.SFO and UMD_DATA.BIN not found in the analyzed data.",
+            ))
+        } else {
+            region_unknown_field(self.region_confidence)
+        };
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Sony PlayStation Portable (PSP)")),
+            self.title.as_ref().map(|title| field("Title", title)),
+            Some(field("Disc ID", self.disc_id.as_deref().unwrap_or("N/A"))),
+            Some(field("Region", self.region)),
+            note,
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Determines the PSP game region from the prefix of a disc ID.
+///
+/// The third letter of the four-letter prefix identifies the region, e.g. "ULUS" and "UCUS"
+/// are North American while "ULJM" and "UCJS" are Japanese.
+///
+/// # Arguments
+///
+/// * `disc_id` - The disc ID, or just its prefix.
+///
+/// # Returns
+///
+/// A tuple containing:
+/// - A `&'static str` representing the region (e.g., "North America (NTSC-U)", "Europe (PAL)", etc)
+///   or "Unknown" if the prefix is not recognized.
+/// - A [`Region`] bitmask representing the region(s) associated with the prefix.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::psp::map_region;
+/// use rom_analyzer::region::Region;
+///
+/// assert_eq!(map_region("ULUS10336"), ("North America (NTSC-U)", Region::USA));
+/// assert_eq!(map_region("UCES"), ("Europe (PAL)", Region::EUROPE));
+/// assert_eq!(map_region("ULJM"), ("Japan (NTSC-J)", Region::JAPAN));
+/// assert_eq!(map_region("UL"), ("Unknown", Region::UNKNOWN));
+/// ```
+pub fn map_region(disc_id: &str) -> (&'static str, Region) {
+    match disc_id.as_bytes().get(2).map(u8::to_ascii_uppercase) {
+        Some(b'U') => ("North America (NTSC-U)", Region::USA),
+        Some(b'E') => ("Europe (PAL)", Region::EUROPE),
+        Some(b'J') => ("Japan (NTSC-J)", Region::JAPAN),
+        Some(b'K') => ("Korea (NTSC)", Region::KOREA),
+        Some(b'A') | Some(b'H') => ("Asia (NTSC)", Region::ASIA),
+        _ => ("Unknown", Region::UNKNOWN),
+    }
+}
+
+fn read_u16_le(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32_le(data: &[u8], offset: usize) -> Option<usize> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+}
+
+/// Helper function to check whether a disc image is a PSP UMD image.
+pub(crate) fn is_psp_image(data: &[u8]) -> bool {
+    data.get(PVD_OFFSET..PVD_OFFSET + PVD_SIGNATURE.len()) == Some(PVD_SIGNATURE)
+        && data
+            .get(PVD_OFFSET + PVD_SYSTEM_ID_START..)
+            .is_some_and(|system_id| system_id.starts_with(PSP_SYSTEM_ID))
+}
+
+/// A file or directory extent as a byte range of the image.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Extent {
+    offset: usize,
+    length: usize,
+}
+
+/// Reads the extent of an ISO9660 directory record.
+fn record_extent(record: &[u8]) -> Option<Extent> {
+    Some(Extent {
+        offset: read_u32_le(record, RECORD_EXTENT)?.checked_mul(SECTOR_SIZE)?,
+        length: read_u32_le(record, RECORD_DATA_LENGTH)?,
+    })
+}
+
+/// Looks up `name` in the directory at `directory`, ignoring the ";1" version suffix.
+///
+/// Returns `None` if the entry isn't found, or if the directory isn't within `data`.
+fn find_entry(data: &[u8], directory: Extent, name: &str) -> Option<Extent> {
+    let entries = data.get(directory.offset..directory.offset.checked_add(directory.length)?)?;
+    let mut pos = 0;
+    while pos < entries.len() {
+        let record_len = entries[pos] as usize;
+        if record_len == 0 {
+            // Records don't span sectors, the rest of this sector is padding.
+            pos = (pos / SECTOR_SIZE + 1) * SECTOR_SIZE;
+            continue;
+        }
+        let record = entries.get(pos..pos + record_len)?;
+        let name_len = *record.get(RECORD_NAME_LENGTH)? as usize;
+        let record_name = record.get(RECORD_NAME..RECORD_NAME + name_len)?;
+        let record_name = record_name
+            .split(|&b| b == b';')
+            .next()
+            .unwrap_or(record_name);
+        if record_name.eq_ignore_ascii_case(name.as_bytes()) {
+            return record_extent(record);
+        }
+        pos += record_len;
+    }
+    None
+}
+
+/// Resolves a path like `PSP_GAME/PARAM.SFO` from the root directory of a PSP image.
+fn find_file(data: &[u8], path: &str) -> Option<Extent> {
+    let root = data.get(PVD_OFFSET + PVD_ROOT_RECORD..)?;
+    path.split('/')
+        .try_fold(record_extent(root)?, |directory, name| {
+            find_entry(data, directory, name)
+        })
+}
+
+/// Returns the number of leading bytes of a PSP image that hold its `PSP_GAME/PARAM.SFO`.
+///
+/// This lets callers that only read the start of an image extend the read far enough to cover
+/// the PARAM.SFO. Returns `None` if `data` is not a PSP image or its directories aren't within
+/// `data`.
+pub(crate) fn param_sfo_end(data: &[u8]) -> Option<usize> {
+    if !is_psp_image(data) {
+        return None;
+    }
+    let extent = find_file(data, "PSP_GAME/PARAM.SFO")?;
+    extent.offset.checked_add(extent.length)
+}
+
+/// Reads a UTF-8 string value from a PARAM.SFO blob.
+///
+/// # Returns
+///
+/// The value of `key`, or `None` if the blob is malformed or doesn't hold a string `key`.
+fn sfo_string(sfo: &[u8], key: &str) -> Option<String> {
+    if !sfo.starts_with(SFO_MAGIC) {
+        return None;
+    }
+    let key_table = read_u32_le(sfo, 0x08)?;
+    let data_table = read_u32_le(sfo, 0x0C)?;
+    let entry_count = read_u32_le(sfo, 0x10)?;
+
+    (0..entry_count).find_map(|i| {
+        let entry = SFO_HEADER_SIZE + i * SFO_INDEX_ENTRY_SIZE;
+        let key_start = key_table + read_u16_le(sfo, entry)? as usize;
+        let entry_key = sfo.get(key_start..)?.split(|&b| b == 0).next()?;
+        if entry_key != key.as_bytes() || read_u16_le(sfo, entry + 0x02)? != SFO_FORMAT_UTF8 {
+            return None;
+        }
+        let length = read_u32_le(sfo, entry + 0x04)?;
+        let value_start = data_table + read_u32_le(sfo, entry + 0x0C)?;
+        let value = sfo.get(value_start..value_start + length)?;
+        let value = value.split(|&b| b == 0).next().unwrap_or(value);
+        Some(String::from_utf8_lossy(value).trim().to_string())
+    })
+}
+
+/// Reads the disc ID from the start of `UMD_DATA.BIN`, e.g. "ULUS-10336|..." as "ULUS10336".
+fn umd_data_disc_id(umd_data: &[u8]) -> Option<String> {
+    let disc_id = umd_data.split(|&b| b == b'|').next()?;
+    let disc_id: String = String::from_utf8_lossy(disc_id)
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    (!disc_id.is_empty()).then_some(disc_id)
+}
+
+/// Returns the contents of the file at `path` if it is entirely within `data`.
+fn file_contents<'a>(data: &'a [u8], path: &str) -> Option<&'a [u8]> {
+    let extent = find_file(data, path)?;
+    data.get(extent.offset..extent.offset.checked_add(extent.length)?)
+}
+
+/// Analyzes PlayStation Portable (PSP) UMD image data.
+///
+/// This function checks the ISO9660 primary volume descriptor for the "PSP GAME" system
+/// identifier, then reads the `DISC_ID` and `TITLE` from `PSP_GAME/PARAM.SFO`. If the PARAM.SFO
+/// is not within `data`, the disc ID is read from `UMD_DATA.BIN` instead. The region is derived
+/// from the disc ID prefix, and is unknown if neither file could be read.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw disc data (e.g., from a `.iso` file).
+/// * `source_name` - The name of the disc image, used for region mismatch checks.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`PspAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError::InvalidHeader`]) if the data is not a PSP image.
+pub fn analyze_psp_data(data: &[u8], source_name: &str) -> Result<PspAnalysis, RomAnalyzerError> {
+    if !is_psp_image(data) {
+        return Err(RomAnalyzerError::InvalidHeader(
+            "No PSP GAME volume descriptor found".to_string(),
+        ));
+    }
+
+    let param_sfo = file_contents(data, "PSP_GAME/PARAM.SFO");
+    let title = param_sfo.and_then(|sfo| sfo_string(sfo, "TITLE"));
+    let disc_id = param_sfo
+        .and_then(|sfo| sfo_string(sfo, "DISC_ID"))
+        .or_else(|| file_contents(data, "UMD_DATA.BIN").and_then(umd_data_disc_id));
+
+    let (region_name, region) = disc_id
+        .as_deref()
+        .map_or(("Unknown", Region::UNKNOWN), map_region);
+    let region_mismatch = check_region_mismatch(source_name, region);
+
+    Ok(PspAnalysis {
+        source_name: source_name.to_string(),
+        region,
+        region_string: region_name.to_string(),
+        region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
+        disc_id,
+        title,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Builds a minimal PARAM.SFO holding the given UTF-8 string entries.
+    pub(crate) fn generate_param_sfo(entries: &[(&str, &str)]) -> Vec<u8> {
+        let key_table = SFO_HEADER_SIZE + entries.len() * SFO_INDEX_ENTRY_SIZE;
+        let mut keys = Vec::new();
+        let mut values = Vec::new();
+        let mut index = Vec::new();
+        for (key, value) in entries {
+            let max_len = (value.len() + 1).next_multiple_of(4);
+            index.extend_from_slice(&(keys.len() as u16).to_le_bytes());
+            index.extend_from_slice(&SFO_FORMAT_UTF8.to_le_bytes());
+            index.extend_from_slice(&(value.len() as u32 + 1).to_le_bytes());
+            index.extend_from_slice(&(max_len as u32).to_le_bytes());
+            index.extend_from_slice(&(values.len() as u32).to_le_bytes());
+            keys.extend_from_slice(key.as_bytes());
+            keys.push(0);
+            values.extend_from_slice(value.as_bytes());
+            values.resize(values.len() + max_len - value.len(), 0);
+        }
+        keys.resize(keys.len().next_multiple_of(4), 0);
+
+        let mut sfo = SFO_MAGIC.to_vec();
+        sfo.extend_from_slice(&0x0101u32.to_le_bytes());
+        sfo.extend_from_slice(&(key_table as u32).to_le_bytes());
+        sfo.extend_from_slice(&((key_table + keys.len()) as u32).to_le_bytes());
+        sfo.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        sfo.extend(index);
+        sfo.extend(keys);
+        sfo.extend(values);
+        sfo
+    }
+
+    fn directory_record(name: &str, lba: u32, length: u32, is_dir: bool) -> Vec<u8> {
+        let len = (RECORD_NAME + name.len()).next_multiple_of(2);
+        let mut record = vec![0; len];
+        record[0] = len as u8;
+        record[RECORD_EXTENT..RECORD_EXTENT + 4].copy_from_slice(&lba.to_le_bytes());
+        record[RECORD_EXTENT + 4..RECORD_EXTENT + 8].copy_from_slice(&lba.to_be_bytes());
+        record[RECORD_DATA_LENGTH..RECORD_DATA_LENGTH + 4].copy_from_slice(&length.to_le_bytes());
+        record[RECORD_DATA_LENGTH + 4..RECORD_DATA_LENGTH + 8]
+            .copy_from_slice(&length.to_be_bytes());
+        record[0x19] = if is_dir { 0x02 } else { 0x00 };
+        record[RECORD_NAME_LENGTH] = name.len() as u8;
+        record[RECORD_NAME..RECORD_NAME + name.len()].copy_from_slice(name.as_bytes());
+        record
+    }
+
+    /// Builds a minimal PSP image with the given PARAM.SFO and UMD_DATA.BIN contents.
+    ///
+    /// The root directory is in sector 22, PSP_GAME in sector 23, UMD_DATA.BIN in sector 24 and
+    /// PARAM.SFO in sector 25.
+    pub(crate) fn generate_psp_image(param_sfo: &[u8], umd_data: &[u8]) -> Vec<u8> {
+        generate_psp_image_with_sfo_at(param_sfo, umd_data, 25)
+    }
+
+    /// Like [`generate_psp_image`], with the PARAM.SFO in sector `sfo_lba` (at least 25).
+    pub(crate) fn generate_psp_image_with_sfo_at(
+        param_sfo: &[u8],
+        umd_data: &[u8],
+        sfo_lba: u32,
+    ) -> Vec<u8> {
+        const ROOT_LBA: u32 = 22;
+        let sector = |lba: u32| lba as usize * SECTOR_SIZE;
+        let mut data = vec![0; sector(sfo_lba + 1)];
+
+        data[PVD_OFFSET..PVD_OFFSET + PVD_SIGNATURE.len()].copy_from_slice(PVD_SIGNATURE);
+        let system_id = PVD_OFFSET + PVD_SYSTEM_ID_START;
+        data[system_id..system_id + PSP_SYSTEM_ID.len()].copy_from_slice(PSP_SYSTEM_ID);
+        let root = directory_record("\0", ROOT_LBA, SECTOR_SIZE as u32, true);
+        data[PVD_OFFSET + PVD_ROOT_RECORD..PVD_OFFSET + PVD_ROOT_RECORD + root.len()]
+            .copy_from_slice(&root);
+
+        let root_entries = [
+            directory_record("\0", ROOT_LBA, SECTOR_SIZE as u32, true),
+            directory_record("\x01", ROOT_LBA, SECTOR_SIZE as u32, true),
+            directory_record("PSP_GAME", 23, SECTOR_SIZE as u32, true),
+            directory_record("UMD_DATA.BIN;1", 24, umd_data.len() as u32, false),
+        ]
+        .concat();
+        data[sector(ROOT_LBA)..sector(ROOT_LBA) + root_entries.len()]
+            .copy_from_slice(&root_entries);
+        let game_entries = [
+            directory_record("\0", 23, SECTOR_SIZE as u32, true),
+            directory_record("\x01", ROOT_LBA, SECTOR_SIZE as u32, true),
+            directory_record("PARAM.SFO;1", sfo_lba, param_sfo.len() as u32, false),
+        ]
+        .concat();
+        data[sector(23)..sector(23) + game_entries.len()].copy_from_slice(&game_entries);
+
+        data[sector(24)..sector(24) + umd_data.len()].copy_from_slice(umd_data);
+        data[sector(sfo_lba)..sector(sfo_lba) + param_sfo.len()].copy_from_slice(param_sfo);
+        data
+    }
+
+    #[test]
+    fn test_sfo_string() {
+        let sfo = generate_param_sfo(&[
+            ("CATEGORY", "UG"),
+            ("DISC_ID", "ULUS10336"),
+            ("TITLE", "Crisis Core: Final Fantasy VII"),
+        ]);
+
+        assert_eq!(sfo_string(&sfo, "DISC_ID").as_deref(), Some("ULUS10336"));
+        assert_eq!(
+            sfo_string(&sfo, "TITLE").as_deref(),
+            Some("Crisis Core: Final Fantasy VII")
+        );
+        assert_eq!(sfo_string(&sfo, "PARENTAL_LEVEL"), None);
+        assert_eq!(sfo_string(&sfo[..0x10], "DISC_ID"), None);
+        assert_eq!(sfo_string(&[0; 0x40], "DISC_ID"), None);
+    }
+
+    #[test]
+    fn test_umd_data_disc_id() {
+        assert_eq!(
+            umd_data_disc_id(b"ULES-00151|0001|G|").as_deref(),
+            Some("ULES00151")
+        );
+        assert_eq!(umd_data_disc_id(b"|0001|G|"), None);
+    }
+
+    #[test]
+    fn test_analyze_psp_data() -> Result<(), RomAnalyzerError> {
+        let sfo = generate_param_sfo(&[("DISC_ID", "ULUS10336"), ("TITLE", "Crisis Core")]);
+        let data = generate_psp_image(&sfo, b"ULUS-10336|0001|G|");
+        let analysis = analyze_psp_data(&data, "Crisis Core (USA).iso")?;
+
+        assert_eq!(analysis.disc_id.as_deref(), Some("ULUS10336"));
+        assert_eq!(analysis.title.as_deref(), Some("Crisis Core"));
+        assert_eq!(analysis.region, Region::USA);
+        assert_eq!(analysis.region_string, "North America (NTSC-U)");
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "Crisis Core (USA).iso\n\
+             System:       Sony PlayStation Portable (PSP)\n\
+             Title:        Crisis Core\n\
+             Disc ID:      ULUS10336\n\
+             Region:       USA"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_psp_data_umd_data_fallback() -> Result<(), RomAnalyzerError> {
+        let sfo = generate_param_sfo(&[("DISC_ID", "ULJM05000"), ("TITLE", "Game")]);
+        let data = generate_psp_image(&sfo, b"ULJM-05000|0001|G|");
+        // Cut the image off before the PARAM.SFO sector.
+        let analysis = analyze_psp_data(&data[..25 * SECTOR_SIZE], "game (Europe).iso")?;
+
+        assert_eq!(analysis.disc_id.as_deref(), Some("ULJM05000"));
+        assert_eq!(analysis.title, None);
+        assert_eq!(analysis.region, Region::JAPAN);
+        assert!(analysis.region_mismatch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_psp_data_no_disc_id() -> Result<(), RomAnalyzerError> {
+        let sfo = generate_param_sfo(&[("TITLE", "Game")]);
+        let data = generate_psp_image(&sfo, b"");
+        let analysis = analyze_psp_data(&data[..24 * SECTOR_SIZE], "game.iso")?;
+
+        assert_eq!(analysis.disc_id, None);
+        assert_eq!(analysis.region, Region::UNKNOWN);
+        assert_eq!(
+            analysis.print(),
+            "game.iso\n\
+             System:       Sony PlayStation Portable (PSP)\n\
+             Disc ID:      N/A\n\
+             Region:       Unknown\n\
+             Note:         PARAM.SFO and UMD_DATA.BIN not found in the analyzed data."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_param_sfo_end() {
+        let sfo = generate_param_sfo(&[("DISC_ID", "ULUS10336")]);
+        let data = generate_psp_image(&sfo, b"");
+
+        // The directories are enough to locate the PARAM.SFO, even if its data is cut off.
+        assert_eq!(
+            param_sfo_end(&data[..24 * SECTOR_SIZE]),
+            Some(25 * SECTOR_SIZE + sfo.len())
+        );
+        assert_eq!(param_sfo_end(&vec![0; 0x10000]), None);
+    }
+
+    #[test]
+    fn test_analyze_psp_data_not_psp() {
+        let result = analyze_psp_data(&vec![0; 0x10000], "game.iso");
+        assert!(matches!(result, Err(RomAnalyzerError::InvalidHeader(_))));
+    }
+}
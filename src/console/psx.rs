@@ -5,8 +5,14 @@
 
 use serde::Serialize;
 
+use crate::console::{RomMetadata, archive_source_field, field};
 use crate::error::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch};
+use crate::region::{Confidence, Region, VideoSystem, check_region_mismatch};
+
+/// The default number of bytes scanned for an executable prefix, used by [`analyze_psx_data`].
+pub const DEFAULT_SCAN_WINDOW: usize = 0x20000;
+// Enough data for the Volume Descriptor/Boot file.
+pub(crate) const MIN_DATA_SIZE: usize = 0x2000;
 
 /// Struct to hold the analysis results for a PSX ROM.
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -19,26 +25,56 @@ pub struct PsxAnalysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
     /// The identified region code (e.g., "SLUS").
     pub code: String,
 }
 
-impl PsxAnalysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        let executable_prefix_not_found = if self.code == "N/A" {
-            "\nNote: Executable prefix (SLUS/SLES/SLPS) not found in header area. Requires main data track (.bin or .iso)."
-        } else {
-            ""
-        };
-        format!(
-            "{}\n\
-             System:       Sony PlayStation (PSX)\n\
-             Region:       {}\n\
-             Code:         {}\
-             {}",
-            self.source_name, self.region, self.code, executable_prefix_not_found
-        )
+impl RomMetadata for PsxAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Sony PlayStation (PSX)")),
+            Some(field("Region", self.region)),
+            Some(field("Code", &self.code)),
+            (self.code == "N/A").then(|| {
+                field(
+                    "Note",
+                    "Executable prefix (SLUS/SLES/SLPS) not found in header area. Requires main \
+                     data track (.bin or .iso).",
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -89,12 +125,32 @@ pub fn map_region(region_code: &str) -> (&'static str, Region) {
     }
 }
 
+/// Determines the video system implied by a PSX executable prefix.
+///
+/// European (SLES) releases are PAL, while North American (SLUS) and Japanese (SLPS) releases
+/// are NTSC.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::psx::map_video_system;
+/// use rom_analyzer::region::VideoSystem;
+///
+/// assert_eq!(map_video_system("SLES"), VideoSystem::Pal);
+/// assert_eq!(map_video_system("SLUS"), VideoSystem::Ntsc);
+/// assert_eq!(map_video_system("N/A"), VideoSystem::Unknown);
+/// ```
+pub fn map_video_system(region_code: &str) -> VideoSystem {
+    match region_code {
+        "SLUS" | "SLPS" => VideoSystem::Ntsc,
+        "SLES" => VideoSystem::Pal,
+        _ => VideoSystem::Unknown,
+    }
+}
+
 /// Analyzes PlayStation (PSX) ROM data, typically from CD images.
 ///
-/// This function scans a portion of the ROM data (up to `0x20000` bytes) for
-/// common PSX executable prefixes like "SLUS", "SLES", or "SLPS". These prefixes
-/// indicate the game's region. If a prefix is found, the corresponding region
-/// and code are extracted. A region mismatch check is also performed against the `source_name`.
+/// This is [`analyze_psx_data_with_window`] using a [`DEFAULT_SCAN_WINDOW`] of 128KB.
 ///
 /// # Arguments
 ///
@@ -107,39 +163,65 @@ pub fn map_region(region_code: &str) -> (&'static str, Region) {
 /// - `Ok`([`PsxAnalysis`]) containing the detailed analysis results.
 /// - `Err`([`RomAnalyzerError`]) if the ROM data is too small for reliable analysis.
 pub fn analyze_psx_data(data: &[u8], source_name: &str) -> Result<PsxAnalysis, RomAnalyzerError> {
-    // Check the first 128KB (0x20000 bytes)
-    let check_size = std::cmp::min(data.len(), 0x20000);
-    if check_size < 0x2000 {
-        // Need enough data for Volume Descriptor/Boot file
-        return Err(RomAnalyzerError::DataTooSmall {
-            file_size: data.len(),
-            required_size: 0x2000,
-            details: "PSX boot file analysis".to_string(),
-        });
-    }
-
-    let data_sample = &data[..check_size];
+    analyze_psx_data_with_window(data, source_name, DEFAULT_SCAN_WINDOW)
+}
 
-    let mut found_code = "N/A".to_string();
-    let mut region_name = "Unknown";
-    let mut region = Region::UNKNOWN;
+/// Helper function to find the first known executable prefix within the first `window` bytes.
+pub(crate) fn find_executable_prefix(data: &[u8], window: usize) -> Option<&'static str> {
+    let data_sample = &data[..data.len().min(window)];
 
     // TODO: Consider moving this somewhere else to centralize the logic into map_region()
     // For now we'll live with these hardcoded prefixes.
-    for prefix in ["SLUS", "SLES", "SLPS"] {
+    ["SLUS", "SLES", "SLPS"].into_iter().find(|prefix| {
         // Use windows to check for the prefix anywhere in the sample.
-        if data_sample
+        data_sample
             .windows(prefix.len())
             .any(|window| window.eq_ignore_ascii_case(prefix.as_bytes()))
-        {
-            found_code = prefix.to_string();
-            let (region_str, region_mask) = map_region(prefix);
-            region_name = region_str;
-            region = region_mask;
-            break;
-        }
+    })
+}
+
+/// Analyzes PlayStation (PSX) ROM data, scanning a caller-chosen number of bytes.
+///
+/// This function scans the first `window` bytes of the ROM data for common PSX executable
+/// prefixes like "SLUS", "SLES", or "SLPS". These prefixes indicate the game's region. If a
+/// prefix is found, the corresponding region and code are extracted. A region mismatch check is
+/// also performed against the `source_name`.
+///
+/// A larger window finds prefixes placed further into the disc at the cost of speed, while a
+/// smaller one is quicker for discs that keep them within the first few KB.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data (e.g., from a `.bin` or `.iso` file).
+/// * `source_name` - The name of the ROM file, used for region mismatch checks.
+/// * `window` - The number of bytes from the start of `data` to scan.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`PsxAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small for reliable analysis.
+pub fn analyze_psx_data_with_window(
+    data: &[u8],
+    source_name: &str,
+    window: usize,
+) -> Result<PsxAnalysis, RomAnalyzerError> {
+    if data.len() < MIN_DATA_SIZE {
+        return Err(RomAnalyzerError::DataTooSmall {
+            file_size: data.len(),
+            required_size: MIN_DATA_SIZE,
+            details: "PSX boot file analysis".to_string(),
+        });
     }
 
+    let (found_code, region_name, region) = match find_executable_prefix(data, window) {
+        Some(prefix) => {
+            let (region_name, region) = map_region(prefix);
+            (prefix.to_string(), region_name, region)
+        }
+        None => ("N/A".to_string(), "Unknown", Region::UNKNOWN),
+    };
+
     let region_mismatch = check_region_mismatch(source_name, region);
 
     Ok(PsxAnalysis {
@@ -147,6 +229,8 @@ pub fn analyze_psx_data(data: &[u8], source_name: &str) -> Result<PsxAnalysis, R
         region,
         region_string: region_name.to_string(),
         region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
         code: found_code,
     })
 }
@@ -219,11 +303,27 @@ mod tests {
              System:       Sony PlayStation (PSX)\n\
              Region:       Unknown\n\
              Code:         N/A\n\
-             Note: Executable prefix (SLUS/SLES/SLPS) not found in header area. Requires main data track (.bin or .iso)."
+             Note:         Executable prefix (SLUS/SLES/SLPS) not found in header area. Requires main data track (.bin or .iso)."
         );
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_psx_data_with_window() -> Result<(), RomAnalyzerError> {
+        // The prefix sits beyond the default 128KB window.
+        let mut data = vec![0; 0x40000];
+        data[0x30000..0x30004].copy_from_slice(b"SLES");
+
+        let analysis = analyze_psx_data(&data, "test_rom.iso")?;
+        assert_eq!(analysis.code, "N/A");
+        assert_eq!(analysis.region, Region::UNKNOWN);
+
+        let analysis = analyze_psx_data_with_window(&data, "test_rom.iso", 0x40000)?;
+        assert_eq!(analysis.code, "SLES");
+        assert_eq!(analysis.region, Region::EUROPE);
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_psx_data_too_small() {
         // Test with data smaller than the minimum required size for analysis.
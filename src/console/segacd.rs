@@ -8,8 +8,23 @@
 use log::error;
 use serde::Serialize;
 
+use crate::console::{RomMetadata, archive_source_field, field, region_unknown_field};
 use crate::error::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch};
+use crate::region::{Confidence, Region, check_region_mismatch};
+use crate::util::sanitize_title;
+
+// The Sega CD boot program header is at 0x100, like the Genesis header, so 0x200 bytes are needed
+// to hold the signature and region byte.
+pub(crate) const REQUIRED_SIZE: usize = 0x200;
+
+// The system header follows the Genesis layout: the product type and serial number
+// (e.g. "GM 00001009-00"), then the device support flags (e.g. "J" for a control pad).
+const SERIAL_START: usize = 0x180;
+const SERIAL_END: usize = 0x18E;
+const DEVICE_SUPPORT_START: usize = 0x190;
+const DEVICE_SUPPORT_END: usize = 0x1A0;
+// The region byte of the boot program.
+const REGION_CODE_OFFSET: usize = 0x10B;
 
 /// Struct to hold the analysis results for a Sega CD ROM.
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -22,23 +37,66 @@ pub struct SegaCdAnalysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region byte, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
     /// The raw region code byte.
     pub region_code: u8,
     /// The detected signature from the boot file (e.g., "SEGA CD", "SEGA MEGA").
     pub signature: String,
+    /// The product type and serial number (e.g., "GM T-12345 -00"), without padding.
+    pub serial: String,
+    /// The hardware the disc supports as device flags (e.g., "J" for a control pad, "M" for a
+    /// mouse), without padding.
+    pub device_support: String,
 }
 
-impl SegaCdAnalysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        format!(
-            "{}\n\
-             System:       Sega CD / Mega CD\n\
-             Signature:    {}\n\
-             Region Code:  0x{:02X}\n\
-             Region:       {}",
-            self.source_name, self.signature, self.region_code, self.region
-        )
+impl RomMetadata for SegaCdAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Sega CD / Mega CD")),
+            Some(field("Signature", &self.signature)),
+            (!self.serial.is_empty()).then(|| field("Serial", &self.serial)),
+            (!self.device_support.is_empty()).then(|| field("Devices", &self.device_support)),
+            Some(field("Region Code", format!("0x{:02X}", self.region_code))),
+            Some(field("Region", self.region)),
+            region_unknown_field(self.region_confidence),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -96,10 +154,8 @@ pub fn map_region(region_byte: u8) -> (&'static str, Region) {
 
 /// Analyzes Sega CD ROM data.
 ///
-/// This function reads the Sega CD boot program header to extract its signature
-/// (e.g., "SEGA CD", "SEGA MEGA") and the region code byte. It then maps the region
-/// code to a human-readable region name and performs a region mismatch check against
-/// the `source_name`. A warning is logged if an unexpected signature is found.
+/// This is a lenient wrapper around [`analyze_segacd_data_with_options`] which logs a warning,
+/// rather than failing, when an unexpected signature is found.
 ///
 /// # Arguments
 ///
@@ -115,9 +171,35 @@ pub fn analyze_segacd_data(
     data: &[u8],
     source_name: &str,
 ) -> Result<SegaCdAnalysis, RomAnalyzerError> {
-    // The Sega CD boot program header information is typically found early in the file.
-    // A common minimum size to check for the signature and region byte is 0x200 bytes.
-    const REQUIRED_SIZE: usize = 0x200;
+    analyze_segacd_data_with_options(data, source_name, false)
+}
+
+/// Analyzes Sega CD ROM data, optionally rejecting unexpected signatures.
+///
+/// This function reads the Sega CD boot program header to extract its signature
+/// (e.g., "SEGA CD", "SEGA MEGA"), serial number, device support flags and the region code
+/// byte. It then maps the region
+/// code to a human-readable region name and performs a region mismatch check against
+/// the `source_name`. An unexpected signature is logged, or returned as an error when
+/// `strict` is set.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data (e.g., from a `.bin` or `.iso` file).
+/// * `source_name` - The name of the ROM file, used for logging and region mismatch checks.
+/// * `strict` - Whether an unexpected signature should fail the analysis.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`SegaCdAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small to contain a valid Sega CD header,
+///   or [`RomAnalyzerError::InvalidSignature`] if `strict` is set and the signature is unexpected.
+pub fn analyze_segacd_data_with_options(
+    data: &[u8],
+    source_name: &str,
+    strict: bool,
+) -> Result<SegaCdAnalysis, RomAnalyzerError> {
     if data.len() < REQUIRED_SIZE {
         return Err(RomAnalyzerError::DataTooSmall {
             file_size: data.len(),
@@ -134,20 +216,34 @@ pub fn analyze_segacd_data(
         .trim()
         .to_string();
 
-    // Region byte is at offset 0x10B in the boot program.
-    let region_code = data[0x10B];
+    let region_code = data[REGION_CODE_OFFSET];
 
     let (region_name, region) = map_region(region_code);
+    let raw_region_info = (region == Region::UNKNOWN).then_some((REGION_CODE_OFFSET, region_code));
 
     // If the signature is not recognized, we might still proceed if the region byte is present,
     // but a warning could be logged or returned.
-    if signature != "SEGA CD" && signature != "SEGA MEGA" {
+    let is_valid_signature = signature == "SEGA CD" || signature == "SEGA MEGA";
+    if !is_valid_signature && strict {
+        return Err(RomAnalyzerError::InvalidSignature {
+            expected: "SEGA CD or SEGA MEGA".to_string(),
+            found: signature,
+            offset: 0x100,
+        });
+    }
+    if !is_valid_signature {
         error!(
+            console = "segacd",
+            reason = "signature_unexpected",
+            path = source_name;
             "[!] Warning: File does not appear to be a standard Sega CD boot file (no SEGA CD or SEGA MEGA signature at 0x100) for {}. Found: '{}'",
             source_name, signature
         );
     }
 
+    let serial = sanitize_title(&data[SERIAL_START..SERIAL_END]);
+    let device_support = sanitize_title(&data[DEVICE_SUPPORT_START..DEVICE_SUPPORT_END]);
+
     let region_mismatch = check_region_mismatch(source_name, region);
 
     Ok(SegaCdAnalysis {
@@ -155,8 +251,13 @@ pub fn analyze_segacd_data(
         region,
         region_string: region_name.to_string(),
         region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
+        raw_region_info,
         region_code,
         signature,
+        serial,
+        device_support,
     })
 }
 
@@ -204,6 +305,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_segacd_data_serial_and_devices() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_segacd_header("SEGA CD", 0xC0);
+        data[SERIAL_START..SERIAL_END].copy_from_slice(b"GM T-12345 -00");
+        data[DEVICE_SUPPORT_START..DEVICE_SUPPORT_END].copy_from_slice(b"J6M             ");
+        let analysis = analyze_segacd_data(&data, "game.iso")?;
+
+        assert_eq!(analysis.serial, "GM T-12345 -00");
+        assert_eq!(analysis.device_support, "J6M");
+        assert_eq!(
+            analysis.print(),
+            "game.iso\n\
+             System:       Sega CD / Mega CD\n\
+             Signature:    SEGA CD\n\
+             Serial:       GM T-12345 -00\n\
+             Devices:      J6M\n\
+             Region Code:  0xC0\n\
+             Region:       USA"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_segacd_data_europe() -> Result<(), RomAnalyzerError> {
         let data = generate_segacd_header("SEGA CD", 0x80); // Europe region
@@ -272,6 +395,19 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_segacd_data_bad_signature_strict() {
+        let data = generate_segacd_header("NOT SEGA", 0x80);
+        assert!(analyze_segacd_data(&data, "bad_sig.iso").is_ok());
+        match analyze_segacd_data_with_options(&data, "bad_sig.iso", true) {
+            Err(RomAnalyzerError::InvalidSignature { found, offset, .. }) => {
+                assert_eq!(found, "NOT SEGA");
+                assert_eq!(offset, 0x100);
+            }
+            other => panic!("Expected InvalidSignature error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_analyze_segacd_data_too_small() {
         // Test with data smaller than the minimum required size for analysis.
@@ -1,16 +1,30 @@
 //! Provides header analysis functionality for Super Nintendo Entertainment System (SNES) ROMs.
 //!
-//! This module can detect SNES ROM mapping types (LoROM, HiROM),
-//! validate checksums, and extract game title and region information.
+//! This module can detect SNES ROM mapping types (LoROM, HiROM, ExHiROM) and BS-X / Satellaview
+//! ROMs, validate checksums, and extract game title and region information.
 //!
 //! Super Nintendo header documentation referenced here:
 //! <https://snes.nesdev.org/wiki/ROM_header>
 
+use std::borrow::Cow;
+use std::fmt;
+
 use log::error;
 use serde::Serialize;
 
+use crate::console::{
+    RomMetadata, archive_source_field, field, region_unknown_field, size_mismatch_field,
+};
 use crate::error::RomAnalyzerError;
-use crate::region::{Region, check_region_mismatch};
+use crate::publisher::{maker_code_from_byte, publisher_name};
+use crate::region::{
+    Confidence, Region, VideoSystem, check_region_mismatch, infer_region_from_filename,
+};
+use crate::util::sanitize_title;
+
+// Size of the header block that has to be present for a location to be considered.
+// It spans the title, Map Mode, region code and the checksum pair at its end.
+pub(crate) const SNES_HEADER_SIZE: usize = 0x20;
 
 // Map Mode byte offset relative to the header start (0x7FC0 for LoROM, 0xFFC0 for HiROM)
 const MAP_MODE_OFFSET: usize = 0x15;
@@ -18,6 +32,70 @@ const MAP_MODE_OFFSET: usize = 0x15;
 // Expected Map Mode byte values for LoROM and HiROM
 const LOROM_MAP_MODES: &[u8] = &[0x20, 0x30, 0x25, 0x35];
 const HIROM_MAP_MODES: &[u8] = &[0x21, 0x31, 0x22, 0x32];
+const EXHIROM_MAP_MODES: &[u8] = &[0x25, 0x35];
+
+// Header locations relative to the start of the ROM, without any copier header.
+pub(crate) const LOROM_HEADER_START: usize = 0x7FC0;
+const HIROM_HEADER_START: usize = 0xFFC0;
+const EXHIROM_HEADER_START: usize = 0x40FFC0;
+// Only ROMs larger than 4MB use ExHiROM, so smaller ones never have its header probed.
+const EXHIROM_MIN_ROM_SIZE: usize = 0x400000;
+
+// BS-X headers share the header locations, but have a 16-byte title followed by the broadcast
+// date and move the Map Mode byte to 0x18. They have no region code or ROM size byte.
+const BSX_TITLE_LENGTH: usize = 0x10;
+const BSX_MONTH_OFFSET: usize = 0x16;
+const BSX_DAY_OFFSET: usize = 0x17;
+const BSX_MAP_MODE_OFFSET: usize = 0x18;
+const BSX_MAP_MODES: &[u8] = &[0x20, 0x21, 0x30, 0x31];
+
+// Interleaved dumps are shuffled in 32KB blocks, and only whole 64KB banks can be deinterleaved.
+const INTERLEAVE_BLOCK_SIZE: usize = 0x8000;
+const INTERLEAVE_BANK_SIZE: usize = 0x10000;
+
+// Region code used by ROMs released for every region ("Common / International").
+const SNES_REGION_INTERNATIONAL: u8 = 0x0E;
+// Canada shares its region code with ROMs that are labelled for all of North America or used
+// the code loosely, so like the international code it is refined by the game code.
+const SNES_REGION_CANADA: u8 = 0x0F;
+
+// Region code byte offset relative to the header start.
+const REGION_CODE_OFFSET: usize = 0x19;
+// Developer ID byte offset relative to the header start.
+const DEVELOPER_ID_OFFSET: usize = 0x1A;
+// ROM size byte offset relative to the header start, holding the size as a power of two in KB.
+const ROM_SIZE_OFFSET: usize = 0x17;
+// Developer ID indicating the maker code is stored in the extended header instead.
+const USE_EXTENDED_HEADER: u8 = 0x33;
+// The extended header fills the 16 bytes preceding the header, starting with the maker code.
+const EXTENDED_HEADER_SIZE: usize = 0x10;
+// The four-character game code follows the maker code, ending with a region letter.
+const GAME_CODE_OFFSET: usize = 0x02;
+const GAME_CODE_LENGTH: usize = 4;
+
+/// The ROM format of a SNES ROM, as told by the location and layout of its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SnesFormat {
+    /// Header at 0x7FC0.
+    LoRom,
+    /// Header at 0xFFC0.
+    HiRom,
+    /// Extended HiROM for ROMs over 4MB, with the header at 0x40FFC0.
+    ExHiRom,
+    /// A BS-X / Satellaview broadcast ROM, with its own header layout at 0x7FC0 or 0xFFC0.
+    Bsx,
+}
+
+impl fmt::Display for SnesFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnesFormat::LoRom => write!(f, "LoROM"),
+            SnesFormat::HiRom => write!(f, "HiROM"),
+            SnesFormat::ExHiRom => write!(f, "ExHiROM"),
+            SnesFormat::Bsx => write!(f, "BS-X"),
+        }
+    }
+}
 
 /// Struct to hold the analysis results for a SNES ROM.
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -30,26 +108,98 @@ pub struct SnesAnalysis {
     pub region_string: String,
     /// If the region in the ROM header doesn't match the region in the filename.
     pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The offset and value of the header's region byte, if it maps to no known region.
+    pub raw_region_info: Option<(usize, u8)>,
     /// The raw region code byte.
     pub region_code: u8,
+    /// The raw developer ID byte, where 0x33 defers to the maker code in the extended header.
+    pub developer_id: u8,
+    /// The two-character maker code, from the extended header or the developer ID in hex.
+    pub maker_code: String,
+    /// The publisher name for the maker code, if it is a known publisher.
+    pub publisher: Option<String>,
     /// The game title extracted from the ROM header.
     pub game_title: String,
+    /// The game code from the extended header (e.g. "ARWE"), if the header defers to it.
+    pub game_code: Option<String>,
     /// The detected mapping type (e.g., "LoROM", "HiROM").
     pub mapping_type: String,
+    /// The detected ROM format. Unverified headers are reported as [`SnesFormat::LoRom`].
+    pub format: SnesFormat,
+    /// If the header checksum and its complement are valid. When neither header location has a
+    /// valid checksum, the header is read from the LoROM location as a fallback.
+    pub checksum_valid: bool,
+    /// If the ROM was stored interleaved and had to be deinterleaved to find a valid header.
+    pub interleaved: bool,
+    /// The offset of the internal header, in the deinterleaved ROM data if it was interleaved.
+    pub header_offset: usize,
+    /// The video timing implied by the region code.
+    pub video_system: VideoSystem,
+    /// The ROM size declared in the header, if it holds a plausible size.
+    pub declared_size: Option<usize>,
+    /// The size of the ROM data, without any copier header.
+    pub rom_size: usize,
+    /// If the ROM size doesn't fit the declared size, as for overdumped or truncated ROMs.
+    pub size_mismatch: bool,
+    /// If the "Common / International" region code was checked with strict world, see
+    /// [`analyze_snes_data_with_options`].
+    pub strict_world: bool,
 }
 
-impl SnesAnalysis {
-    /// Returns a printable String of the analysis results.
-    pub fn print(&self) -> String {
-        format!(
-            "{}\n\
-             System:       Super Nintendo (SNES)\n\
-             Game Title:   {}\n\
-             Mapping:      {}\n\
-             Region Code:  0x{:02X}\n\
-             Region:       {}",
-            self.source_name, self.game_title, self.mapping_type, self.region_code, self.region
-        )
+impl RomMetadata for SnesAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.raw_region_info
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Super Nintendo (SNES)")),
+            Some(field("Game Title", &self.game_title)),
+            self.game_code
+                .as_ref()
+                .map(|game_code| field("Game Code", game_code)),
+            self.publisher
+                .as_ref()
+                .map(|publisher| field("Publisher", publisher)),
+            Some(field("Mapping", &self.mapping_type)),
+            self.interleaved.then(|| field("Storage", "Interleaved")),
+            Some(field("Region Code", format!("0x{:02X}", self.region_code))),
+            Some(field("Region", self.region)),
+            region_unknown_field(self.region_confidence),
+            size_mismatch_field(self.size_mismatch, self.declared_size, self.rom_size),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -106,7 +256,7 @@ pub fn map_region(code: u8) -> (&'static str, Region) {
         0x0B => ("China (PAL)", Region::CHINA),
         0x0C => ("Indonesia (PAL)", Region::EUROPE | Region::ASIA),
         0x0D => ("South Korea (NTSC)", Region::KOREA),
-        0x0E => (
+        SNES_REGION_INTERNATIONAL => (
             "Common / International",
             Region::USA | Region::EUROPE | Region::JAPAN | Region::ASIA,
         ),
@@ -120,6 +270,119 @@ pub fn map_region(code: u8) -> (&'static str, Region) {
     }
 }
 
+/// Determines the region hinted at by the region letter ending a SNES game code.
+///
+/// # Arguments
+///
+/// * `game_code` - The game code from the extended header (e.g. "ARWE").
+///
+/// # Returns
+///
+/// The [`Region`] for the last letter of a four-character game code, or `None` if the code is
+/// shorter or the letter isn't a known region letter.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::snes::game_code_region;
+/// use rom_analyzer::region::Region;
+///
+/// assert_eq!(game_code_region("ARWJ"), Some(Region::JAPAN));
+/// assert_eq!(game_code_region("ARWE"), Some(Region::USA));
+/// assert_eq!(game_code_region("AR"), None);
+/// ```
+pub fn game_code_region(game_code: &str) -> Option<Region> {
+    if game_code.len() != GAME_CODE_LENGTH {
+        return None;
+    }
+    match game_code.as_bytes()[GAME_CODE_LENGTH - 1] {
+        b'J' => Some(Region::JAPAN),
+        b'E' => Some(Region::USA),
+        b'P' | b'D' | b'F' | b'H' | b'I' | b'S' | b'U' | b'W' | b'X' | b'Y' => Some(Region::EUROPE),
+        b'K' => Some(Region::KOREA),
+        b'C' => Some(Region::CHINA),
+        _ => None,
+    }
+}
+
+/// Determines the ROM size declared by the ROM size byte of a SNES header.
+///
+/// The byte holds the size as a power of two in kilobytes, rounded up for ROMs whose size isn't
+/// a power of two.
+///
+/// # Returns
+///
+/// The size in bytes, or `None` if the byte is outside the 128KB to 8MB range of SNES ROMs.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::snes::map_rom_size;
+///
+/// assert_eq!(map_rom_size(0x0A), Some(1024 * 1024));
+/// assert_eq!(map_rom_size(0x00), None);
+/// ```
+pub fn map_rom_size(rom_size_byte: u8) -> Option<usize> {
+    (0x07..=0x0D)
+        .contains(&rom_size_byte)
+        .then(|| 0x400 << rom_size_byte)
+}
+
+/// Checks whether a ROM of `rom_size` bytes doesn't fit the size declared in its header.
+///
+/// The declared size is rounded up to a power of two, so a ROM is only considered mismatched
+/// when it is larger than that, or no larger than the next smaller power of two.
+pub(crate) fn is_size_mismatch(declared_size: usize, rom_size: usize) -> bool {
+    rom_size > declared_size || rom_size <= declared_size / 2
+}
+
+/// Returns the size of the copier header a SNES file of `file_size` bytes starts with.
+///
+/// Copier headers are 512 bytes, so a file whose size is 512 more than a multiple of 1KB is
+/// assumed to have one. This is a simple heuristic that could be fooled by odd-sized dumps.
+pub(crate) fn copier_header_size(file_size: usize) -> usize {
+    if file_size % 1024 == 512 { 512 } else { 0 }
+}
+
+/// Determines the SNES video timing implied by a region code.
+///
+/// # Arguments
+///
+/// * `code` - The region code byte from the ROM header.
+///
+/// # Returns
+///
+/// The [`VideoSystem`] for the region. Brazilian consoles use PAL-M, which has NTSC timing, and
+/// the "Common / International" code (0x0E) doesn't imply any timing.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::snes::map_video_system;
+/// use rom_analyzer::region::VideoSystem;
+///
+/// assert_eq!(map_video_system(0x01), VideoSystem::Ntsc);
+/// assert_eq!(map_video_system(0x02), VideoSystem::Pal);
+/// assert_eq!(map_video_system(0x0E), VideoSystem::Unknown);
+/// ```
+pub fn map_video_system(code: u8) -> VideoSystem {
+    match code {
+        0x00 | 0x01 | 0x0D | 0x0F | 0x10 => VideoSystem::Ntsc,
+        0x02..=0x0C | 0x11 => VideoSystem::Pal,
+        _ => VideoSystem::Unknown,
+    }
+}
+
+/// Helper function to check whether a full SNES header block fits within the ROM data.
+///
+/// Every candidate header location goes through this check so that the checksum, the Map Mode
+/// byte and the final header reads all agree on whether a location is usable.
+fn snes_header_in_bounds(file_size: usize, header_offset: usize) -> bool {
+    header_offset
+        .checked_add(SNES_HEADER_SIZE)
+        .is_some_and(|end| end <= file_size)
+}
+
 /// Helper function to validate the SNES ROM checksum.
 ///
 /// This function checks if the 16-bit checksum and its complement, located
@@ -137,7 +400,7 @@ pub fn map_region(code: u8) -> (&'static str, Region) {
 /// `false` otherwise, or if the `header_offset` is out of bounds.
 pub fn validate_snes_checksum(rom_data: &[u8], header_offset: usize) -> bool {
     // Ensure we have enough data for checksum and complement bytes.
-    if header_offset + 0x20 > rom_data.len() {
+    if !snes_header_in_bounds(rom_data.len(), header_offset) {
         return false;
     }
 
@@ -163,19 +426,45 @@ pub fn validate_snes_checksum(rom_data: &[u8], header_offset: usize) -> bool {
 
 /// Analyzes SNES ROM data.
 ///
+/// This is a wrapper around [`analyze_snes_data_with_options`] which keeps the broad
+/// multi-region mask for the "Common / International" region code (0x0E).
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `source_name` - The name of the ROM file, used for logging and region mismatch checks.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`SnesAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError`]) if the ROM data is too small or the header is deemed invalid
+///   such that critical information cannot be read.
+pub fn analyze_snes_data(data: &[u8], source_name: &str) -> Result<SnesAnalysis, RomAnalyzerError> {
+    analyze_snes_data_with_options(data, source_name, false)
+}
+
+/// Analyzes SNES ROM data, optionally treating the international region code as unknown.
+///
 /// This function first attempts to detect a copier header. It then tries to determine
 /// the ROM's mapping type (LoROM or HiROM) by validating checksums and examining
 /// the Map Mode byte at expected header locations. If both checksum and Map Mode
 /// are consistent, that mapping is chosen. If only the checksum is valid, it uses
 /// that mapping with an "Map Mode Unverified" tag. If neither is fully consistent,
-/// it falls back to LoROM (Unverified). Once the header location is determined,
+/// it tries deinterleaving the ROM as a last resort, in case it is an interleaved dump, and
+/// otherwise falls back to LoROM (Unverified). Once the header location is determined,
 /// it extracts the game title and region code, maps the region code to a human-readable
 /// name, and performs a region mismatch check against the `source_name`.
 ///
+/// When `strict_world` is set, a ROM with the "Common / International" region code (0x0E) is
+/// flagged as a region mismatch if its filename names a single region, such as "(J)". The region
+/// is still reported as a mask covering every region.
+///
 /// # Arguments
 ///
 /// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
 /// * `source_name` - The name of the ROM file, used for logging and region mismatch checks.
+/// * `strict_world` - Whether region code 0x0E should only match filenames naming every region.
 ///
 /// # Returns
 ///
@@ -183,117 +472,331 @@ pub fn validate_snes_checksum(rom_data: &[u8], header_offset: usize) -> bool {
 /// - `Ok`([`SnesAnalysis`]) containing the detailed analysis results.
 /// - `Err`([`RomAnalyzerError`]) if the ROM data is too small or the header is deemed invalid
 ///   such that critical information cannot be read.
-pub fn analyze_snes_data(data: &[u8], source_name: &str) -> Result<SnesAnalysis, RomAnalyzerError> {
+pub fn analyze_snes_data_with_options(
+    data: &[u8],
+    source_name: &str,
+    strict_world: bool,
+) -> Result<SnesAnalysis, RomAnalyzerError> {
     let file_size = data.len();
-    let mut header_offset = 0;
-
-    // Detect copier header (often 512 bytes, common for some older dumps/tools)
-    if file_size >= 512 && (file_size % 1024 == 512) {
-        // Heuristic: If file size ends in 512 and is divisible by 1024
-        header_offset = 512;
-        // Note: This copier header detection is a simple heuristic and might not be foolproof.
-        // More advanced detection could involve checking for specific patterns.
-    }
+    let header_offset = copier_header_size(file_size);
+    let rom_size = file_size - header_offset;
 
     // Determine ROM mapping type (LoROM vs HiROM) by checking checksums and Map Mode byte.
-    // The relevant header information is usually found at 0x7FC0 for LoROM and 0xFFC0 for HiROM
-    // (relative to the start of the ROM, accounting for the header_offset).
-    let lorom_header_start = 0x7FC0 + header_offset; // Header block starts here
-    let hirom_header_start = 0xFFC0 + header_offset; // Header block starts here
-
-    let mapping_type: String;
-    let valid_header_offset: usize;
-
-    let lorom_checksum_valid = validate_snes_checksum(data, lorom_header_start);
-    let hirom_checksum_valid = validate_snes_checksum(data, hirom_header_start);
+    // If neither checksum validates, the ROM may be an interleaved dump, so as a last resort
+    // deinterleave it and look again. ROMs with any valid checksum are never deinterleaved.
+    let mut rom: Cow<[u8]> = Cow::Borrowed(data);
+    let mut interleaved = false;
+    let mut candidate = detect_header(data, header_offset);
+    if candidate.is_none()
+        && let Some(rom_data) = deinterleave(&data[header_offset..])
+        && let Some(deinterleaved) = detect_header(&rom_data, 0)
+    {
+        candidate = Some(deinterleaved);
+        rom = Cow::Owned(rom_data);
+        interleaved = true;
+    }
 
-    // Get Map Mode bytes if headers are within bounds
-    let lorom_map_mode_byte = if lorom_header_start + MAP_MODE_OFFSET < file_size {
-        Some(data[lorom_header_start + MAP_MODE_OFFSET])
-    } else {
-        None
+    let checksum_valid = candidate.is_some();
+    let is_bsx = candidate.is_some_and(|candidate| candidate.bsx);
+    let format = match candidate {
+        Some(candidate) if candidate.bsx => SnesFormat::Bsx,
+        Some(candidate) => candidate.mapping,
+        None => SnesFormat::LoRom,
     };
-    let hirom_map_mode_byte = if hirom_header_start + MAP_MODE_OFFSET < file_size {
-        Some(data[hirom_header_start + MAP_MODE_OFFSET])
-    } else {
-        None
+    let (mapping_type, valid_header_offset) = match candidate {
+        Some(candidate) if candidate.bsx => (
+            format!("{} ({})", SnesFormat::Bsx, candidate.mapping),
+            candidate.header_start,
+        ),
+        Some(candidate) if candidate.map_mode_valid => {
+            (candidate.mapping.to_string(), candidate.header_start)
+        }
+        Some(candidate) => {
+            error!(
+                console = "snes",
+                reason = "map_mode_invalid",
+                path = source_name;
+                "[!] {} checksum valid for {}, but Map Mode byte (0x{:02X?}) is not a typical {} value. Falling back to {}.",
+                candidate.mapping,
+                source_name,
+                candidate.map_mode_byte,
+                candidate.mapping,
+                candidate.mapping
+            );
+            (
+                format!("{} (Map Mode Unverified)", candidate.mapping),
+                candidate.header_start,
+            )
+        }
+        None => {
+            // If neither checksum is valid, log a warning and try LoROM as a fallback, as it's more common.
+            let lorom_header_start = LOROM_HEADER_START + header_offset;
+            error!(
+                console = "snes",
+                reason = "checksum_invalid",
+                path = source_name;
+                "[!] Checksum validation failed for {}. Attempting to read header from LoROM location ({:X}) as fallback.",
+                source_name, lorom_header_start
+            );
+            ("LoROM (Unverified)".to_string(), lorom_header_start)
+        }
     };
-
-    let is_lorom_map_mode = lorom_map_mode_byte.is_some_and(|b| LOROM_MAP_MODES.contains(&b));
-    let is_hirom_map_mode = hirom_map_mode_byte.is_some_and(|b| HIROM_MAP_MODES.contains(&b));
-
-    // Decision logic: Prioritize HiROM if both checksum and map mode are consistent.
-    // Then check LoROM similarly. If only one checksum is valid, use that.
-    // If neither is fully consistent, fallback to LoROM (unverified) with a warning.
-    if hirom_checksum_valid && is_hirom_map_mode {
-        mapping_type = "HiROM".to_string();
-        valid_header_offset = hirom_header_start;
-    } else if lorom_checksum_valid && is_lorom_map_mode {
-        mapping_type = "LoROM".to_string();
-        valid_header_offset = lorom_header_start;
-    } else if hirom_checksum_valid {
-        mapping_type = "HiROM (Map Mode Unverified)".to_string();
-        valid_header_offset = hirom_header_start;
-        error!(
-            "[!] HiROM checksum valid for {}, but Map Mode byte (0x{:02X?}) is not a typical HiROM value. Falling back to HiROM.",
-            source_name, hirom_map_mode_byte
-        );
-    } else if lorom_checksum_valid {
-        mapping_type = "LoROM (Map Mode Unverified)".to_string();
-        valid_header_offset = lorom_header_start;
-        error!(
-            "[!] LoROM checksum valid for {}, but Map Mode byte (0x{:02X?}) is not a typical LoROM value. Falling back to LoROM.",
-            source_name, lorom_map_mode_byte
-        );
-    } else {
-        // If neither checksum is valid, log a warning and try LoROM as a fallback, as it's more common.
-        error!(
-            "[!] Checksum validation failed for {}. Attempting to read header from LoROM location ({:X}) as fallback.",
-            source_name, lorom_header_start
-        );
-        mapping_type = "LoROM (Unverified)".to_string();
-        valid_header_offset = lorom_header_start; // Fallback to LoROM offset
-    }
+    let data = rom.as_ref();
+    let file_size = data.len();
 
     // Ensure the determined header offset plus the header size needed for analysis is within the file bounds.
     // We need at least up to the region code (offset 0x19 relative to header start) and game title (offset 0x0 to 0x14).
-    // Thus, we check if `valid_header_offset + 0x20` is within bounds, as this covers the checksum bytes.
-    if valid_header_offset + 0x20 > file_size {
+    // Thus, we check that the whole header block is within bounds, as this covers the checksum bytes.
+    if !snes_header_in_bounds(file_size, valid_header_offset) {
         return Err(RomAnalyzerError::DataTooSmall {
             file_size,
-            required_size: valid_header_offset + 0x20,
+            required_size: valid_header_offset + SNES_HEADER_SIZE,
             details: format!("Checked header at offset: {}.", valid_header_offset),
         });
     }
 
     // Extract region code and game title from the identified header.
-    let region_byte_offset = valid_header_offset + 0x19; // Offset for region code within the header
-    let region_code = data[region_byte_offset];
-    let (region_name, region) = map_region(region_code);
+    // BS-X headers have no region code, but the Satellaview was only released in Japan, so they
+    // are reported with the Japanese region code.
+    let region_code = if is_bsx {
+        0x00
+    } else {
+        data[valid_header_offset + REGION_CODE_OFFSET]
+    };
+    let (region_name, mut region) = map_region(region_code);
+    let raw_region_info = (region == Region::UNKNOWN)
+        .then_some((valid_header_offset + REGION_CODE_OFFSET, region_code));
+    let mut region_name = region_name.to_string();
+    if is_bsx {
+        region_name = "Japan (BS-X)".to_string();
+    }
 
-    // Game title is located at the beginning of the header (offset 0x0 relative to valid_header_offset) for 21 bytes.
-    // It is null-terminated, so we trim null bytes and leading/trailing whitespace.
-    let game_title = String::from_utf8_lossy(&data[valid_header_offset..valid_header_offset + 21])
-        .trim_matches(char::from(0)) // Remove null bytes
-        .trim()
-        .to_string();
+    // Game title is located at the beginning of the header (offset 0x0 relative to valid_header_offset) for 21 bytes,
+    // or 16 bytes for BS-X headers.
+    // It is padded with nulls or spaces, which are trimmed along with any control bytes.
+    let title_length = if is_bsx { BSX_TITLE_LENGTH } else { 21 };
+    let game_title = sanitize_title(&data[valid_header_offset..valid_header_offset + title_length]);
+
+    // The developer ID either identifies the maker itself or defers to the extended header's
+    // two-character maker code.
+    let developer_id = data[valid_header_offset + DEVELOPER_ID_OFFSET];
+    let extended_start = valid_header_offset
+        .checked_sub(EXTENDED_HEADER_SIZE)
+        .filter(|_| developer_id == USE_EXTENDED_HEADER);
+    let maker_code = match extended_start {
+        Some(extended_start) => String::from_utf8_lossy(&data[extended_start..extended_start + 2])
+            .trim_matches(char::from(0))
+            .to_string(),
+        None => maker_code_from_byte(developer_id),
+    };
+    let publisher = publisher_name(&maker_code).map(str::to_string);
+
+    // The game code is only kept when it is printable, as older headers leave it blank.
+    let game_code = extended_start
+        .map(|extended_start| {
+            let start = extended_start + GAME_CODE_OFFSET;
+            sanitize_title(&data[start..start + GAME_CODE_LENGTH])
+        })
+        .filter(|game_code| {
+            !game_code.is_empty() && game_code.bytes().all(|b| b.is_ascii_alphanumeric())
+        });
+
+    // The international and Canada region codes are ambiguous, so the region letter of the game
+    // code refines them when there is one.
+    if matches!(region_code, SNES_REGION_INTERNATIONAL | SNES_REGION_CANADA)
+        && let Some(hint) = game_code.as_deref().and_then(game_code_region)
+    {
+        region = hint;
+        region_name = format!("{} (Game Code: {})", region_name, hint);
+    }
+
+    // BS-X headers hold the broadcast day in place of the ROM size byte.
+    let declared_size = (!is_bsx)
+        .then(|| map_rom_size(data[valid_header_offset + ROM_SIZE_OFFSET]))
+        .flatten();
+    let size_mismatch =
+        declared_size.is_some_and(|declared_size| is_size_mismatch(declared_size, rom_size));
 
-    let region_mismatch = check_region_mismatch(source_name, region);
+    let region_mismatch = check_region_mismatch(source_name, region)
+        || (strict_world && is_strict_world_mismatch(source_name, region_code));
 
     Ok(SnesAnalysis {
         source_name: source_name.to_string(),
         region,
-        region_string: region_name.to_string(),
+        region_string: region_name,
         region_mismatch,
+        region_confidence: Confidence::from_header(region),
+        archive_source: None,
+        raw_region_info,
         region_code,
+        developer_id,
+        maker_code,
+        publisher,
         game_title,
+        game_code,
         mapping_type,
+        format,
+        checksum_valid,
+        interleaved,
+        header_offset: valid_header_offset,
+        video_system: map_video_system(region_code),
+        declared_size,
+        rom_size,
+        size_mismatch,
+        strict_world,
     })
 }
 
+/// Returns whether a "Common / International" ROM is named for a single region, which strict
+/// world treats as a region mismatch.
+pub(crate) fn is_strict_world_mismatch(source_name: &str, region_code: u8) -> bool {
+    region_code == SNES_REGION_INTERNATIONAL
+        && infer_region_from_filename(source_name).bits().count_ones() == 1
+}
+
+/// A header location whose checksum validated, as found by [`detect_header`].
+#[derive(Debug, Clone, Copy)]
+struct HeaderCandidate {
+    /// The mapping type implied by the header location.
+    mapping: SnesFormat,
+    /// The offset of the header block within the ROM data.
+    header_start: usize,
+    /// The Map Mode byte read from the header.
+    map_mode_byte: Option<u8>,
+    /// If the Map Mode byte is a typical value for the mapping type.
+    map_mode_valid: bool,
+    /// If the header has the BS-X layout, see [`is_bsx_header`].
+    bsx: bool,
+}
+
+/// Helper function to find the SNES header location with a valid checksum.
+///
+/// The relevant header information is usually found at 0x7FC0 for LoROM, 0xFFC0 for HiROM and
+/// 0x40FFC0 for ExHiROM (relative to the start of the ROM, accounting for `header_offset`). A
+/// location whose checksum and Map Mode byte are both consistent is preferred, ExHiROM first,
+/// then HiROM. Otherwise a location with only a valid checksum is returned, in the same order.
+/// BS-X headers are recognized at the LoROM and HiROM locations, with their own Map Mode byte.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `header_offset` - The size of the copier header preceding the ROM, if any.
+///
+/// # Returns
+///
+/// An `Option` containing the [`HeaderCandidate`], or `None` if neither header location has a
+/// valid checksum.
+fn detect_header(data: &[u8], header_offset: usize) -> Option<HeaderCandidate> {
+    let file_size = data.len();
+
+    let candidate = |mapping, header_start, map_modes: &[u8], bsx_allowed: bool| {
+        // Get the Map Mode byte if the whole header block is within bounds. Using the same bounds
+        // as the checksum validation keeps a partially present header from being considered.
+        let bsx = bsx_allowed && is_bsx_header(data, header_start);
+        let map_mode_offset = if bsx {
+            BSX_MAP_MODE_OFFSET
+        } else {
+            MAP_MODE_OFFSET
+        };
+        let map_mode_byte = snes_header_in_bounds(file_size, header_start)
+            .then(|| data[header_start + map_mode_offset]);
+        validate_snes_checksum(data, header_start).then(|| HeaderCandidate {
+            mapping,
+            header_start,
+            map_mode_byte,
+            map_mode_valid: bsx || map_mode_byte.is_some_and(|b| map_modes.contains(&b)),
+            bsx,
+        })
+    };
+    let exhirom = (file_size.saturating_sub(header_offset) > EXHIROM_MIN_ROM_SIZE)
+        .then(|| {
+            candidate(
+                SnesFormat::ExHiRom,
+                EXHIROM_HEADER_START + header_offset,
+                EXHIROM_MAP_MODES,
+                false,
+            )
+        })
+        .flatten();
+    let hirom = candidate(
+        SnesFormat::HiRom,
+        HIROM_HEADER_START + header_offset,
+        HIROM_MAP_MODES,
+        true,
+    );
+    let lorom = candidate(
+        SnesFormat::LoRom,
+        LOROM_HEADER_START + header_offset,
+        LOROM_MAP_MODES,
+        true,
+    );
+
+    // Decision logic: Prioritize ExHiROM, then HiROM, if both checksum and map mode are
+    // consistent. Then check LoROM similarly. If only one checksum is valid, use that.
+    [exhirom, hirom, lorom]
+        .into_iter()
+        .flatten()
+        .find(|c| c.map_mode_valid)
+        .or(exhirom)
+        .or(hirom)
+        .or(lorom)
+}
+
+/// Helper function to check whether a header block has the BS-X / Satellaview layout.
+///
+/// BS-X headers always defer to the extended header for the maker code, hold their Map Mode byte
+/// at 0x18, and store the broadcast month in the upper nibble of 0x16. ROMs without a broadcast
+/// date have both the month and day set to zero.
+fn is_bsx_header(data: &[u8], header_start: usize) -> bool {
+    if !snes_header_in_bounds(data.len(), header_start) {
+        return false;
+    }
+    let header = &data[header_start..header_start + SNES_HEADER_SIZE];
+    let month = header[BSX_MONTH_OFFSET];
+    let dated = month & 0x0F == 0 && (1..=12).contains(&(month >> 4));
+    let undated = month == 0 && header[BSX_DAY_OFFSET] == 0;
+    header[DEVELOPER_ID_OFFSET] == USE_EXTENDED_HEADER
+        && BSX_MAP_MODES.contains(&header[BSX_MAP_MODE_OFFSET])
+        && (dated || undated)
+}
+
+/// Helper function to deinterleave an interleaved SNES ROM dump.
+///
+/// Interleaved dumps store the odd-numbered 32KB blocks of the ROM first, followed by the
+/// even-numbered ones. Deinterleaving alternates between the two halves of the file so block
+/// `2i` comes from block `i + n/2` and block `2i + 1` from block `i`, where `n` is the number
+/// of 32KB blocks.
+///
+/// # Arguments
+///
+/// * `rom_data` - The ROM data, without any copier header.
+///
+/// # Returns
+///
+/// An `Option` containing the deinterleaved ROM, or `None` if the ROM is not a whole number
+/// of 64KB banks.
+fn deinterleave(rom_data: &[u8]) -> Option<Vec<u8>> {
+    if rom_data.is_empty() || !rom_data.len().is_multiple_of(INTERLEAVE_BANK_SIZE) {
+        return None;
+    }
+
+    let (lower, upper) = rom_data.split_at(rom_data.len() / 2);
+    let mut deinterleaved = Vec::with_capacity(rom_data.len());
+    for (upper_block, lower_block) in upper
+        .chunks(INTERLEAVE_BLOCK_SIZE)
+        .zip(lower.chunks(INTERLEAVE_BLOCK_SIZE))
+    {
+        deinterleaved.extend_from_slice(upper_block);
+        deinterleaved.extend_from_slice(lower_block);
+    }
+    Some(deinterleaved)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::console::test_logger::capture_logs;
+    use log::Level;
 
     /// Helper to create a dummy SNES ROM with a valid checksum.
     /// It allows specifying ROM size, copier header offset, region code, mapping type.
@@ -358,6 +861,7 @@ mod tests {
         assert_eq!(analysis.game_title, "TEST GAME TITLE");
         assert_eq!(analysis.mapping_type, "LoROM (Map Mode Unverified)");
         assert_eq!(analysis.region_code, 0x00);
+        assert!(analysis.checksum_valid);
         assert_eq!(analysis.region, Region::JAPAN);
         assert_eq!(analysis.region_string, "Japan (NTSC)");
         assert_eq!(
@@ -372,6 +876,79 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_snes_data_developer_id() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_snes_header(0x80000, 0, 0x00, false, "TEST GAME TITLE", None);
+        data[0x7FC0 + DEVELOPER_ID_OFFSET] = 0xC3;
+        let analysis = analyze_snes_data(&data, "test_lorom_jp.sfc")?;
+
+        assert_eq!(analysis.developer_id, 0xC3);
+        assert_eq!(analysis.maker_code, "C3");
+        assert_eq!(analysis.publisher.as_deref(), Some("Square"));
+        assert_eq!(
+            analysis.print(),
+            "test_lorom_jp.sfc\n\
+             System:       Super Nintendo (SNES)\n\
+             Game Title:   TEST GAME TITLE\n\
+             Publisher:    Square\n\
+             Mapping:      LoROM (Map Mode Unverified)\n\
+             Region Code:  0x00\n\
+             Region:       Japan"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_extended_header_maker_code() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_snes_header(0x100000, 0, 0x01, true, "TEST GAME TITLE", None);
+        data[0xFFC0 + DEVELOPER_ID_OFFSET] = USE_EXTENDED_HEADER;
+        data[0xFFB0..0xFFB2].copy_from_slice(b"01");
+        let analysis = analyze_snes_data(&data, "test_hirom_us.sfc")?;
+
+        assert_eq!(analysis.developer_id, USE_EXTENDED_HEADER);
+        assert_eq!(analysis.maker_code, "01");
+        assert_eq!(analysis.publisher.as_deref(), Some("Nintendo"));
+
+        // An unknown maker code is kept without a publisher.
+        data[0xFFB0..0xFFB2].copy_from_slice(b"ZZ");
+        let analysis = analyze_snes_data(&data, "test_hirom_us.sfc")?;
+        assert_eq!(analysis.maker_code, "ZZ");
+        assert_eq!(analysis.publisher, None);
+        assert!(!analysis.print().contains("Publisher:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_game_code_refines_international() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_snes_header(0x100000, 0, 0x0E, true, "TEST GAME TITLE", None);
+        data[0xFFC0 + DEVELOPER_ID_OFFSET] = USE_EXTENDED_HEADER;
+        data[0xFFB0..0xFFB6].copy_from_slice(b"01ARWJ");
+        let analysis = analyze_snes_data(&data, "test (USA).sfc")?;
+
+        assert_eq!(analysis.game_code.as_deref(), Some("ARWJ"));
+        assert_eq!(analysis.region, Region::JAPAN);
+        assert_eq!(
+            analysis.region_string,
+            "Common / International (Game Code: Japan)"
+        );
+        assert!(analysis.region_mismatch);
+        assert!(analysis.print().contains("\nGame Code:    ARWJ\n"));
+
+        // Unambiguous region codes keep their own region.
+        data[0xFFC0 + 0x19] = 0x01;
+        let analysis = analyze_snes_data(&data, "test (USA).sfc")?;
+        assert_eq!(analysis.region, Region::USA);
+        assert!(!analysis.region_mismatch);
+
+        // A blank game code is not reported and doesn't refine the region.
+        data[0xFFC0 + 0x19] = 0x0E;
+        data[0xFFB2..0xFFB6].copy_from_slice(b"    ");
+        let analysis = analyze_snes_data(&data, "test (USA).sfc")?;
+        assert_eq!(analysis.game_code, None);
+        assert_eq!(analysis.region_string, "Common / International");
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_snes_data_hirom_usa() -> Result<(), RomAnalyzerError> {
         let data = generate_snes_header(0x100000, 0, 0x01, true, "TEST GAME TITLE", None); // 1MB ROM, HiROM, USA
@@ -398,6 +975,7 @@ mod tests {
         assert_eq!(analysis.region_code, 0x02);
         assert_eq!(analysis.region, Region::EUROPE | Region::ASIA);
         assert_eq!(analysis.region_string, "Europe / Oceania / Asia (PAL)");
+        assert_eq!(analysis.video_system, VideoSystem::Pal);
         Ok(())
     }
 
@@ -423,6 +1001,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_snes_data_size_mismatch() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_snes_header(0x80000, 0, 0x01, false, "TEST GAME TITLE", None);
+        // Declares 512KB, the size of the ROM.
+        data[0x7FC0 + ROM_SIZE_OFFSET] = 0x09;
+        let analysis = analyze_snes_data(&data, "game.sfc")?;
+        assert_eq!(analysis.declared_size, Some(0x80000));
+        assert!(!analysis.size_mismatch);
+
+        // A 384KB ROM still fits the declared size rounded up to 512KB.
+        let analysis = analyze_snes_data(&data[..0x60000], "game.sfc")?;
+        assert!(!analysis.size_mismatch);
+
+        // A ROM of double the declared size is an overdump.
+        data[0x7FC0 + ROM_SIZE_OFFSET] = 0x08;
+        let analysis = analyze_snes_data(&data, "game.sfc")?;
+        assert_eq!(analysis.rom_size, 0x80000);
+        assert!(analysis.size_mismatch);
+        assert_eq!(
+            analysis.to_fields(),
+            vec![
+                field("System", "Super Nintendo (SNES)"),
+                field("Game Title", "TEST GAME TITLE"),
+                field("Mapping", "LoROM (Map Mode Unverified)"),
+                field("Region Code", "0x01"),
+                field("Region", "USA"),
+                field(
+                    "ROM Size",
+                    "524288 bytes, header declares 262144 (overdumped or truncated)"
+                ),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_snes_data_unknown_region() -> Result<(), RomAnalyzerError> {
         let data = generate_snes_header(0x80000, 0, 0xFF, false, "TEST GAME TITLE", None); // LoROM, Unknown region
@@ -434,6 +1047,7 @@ mod tests {
         assert_eq!(analysis.region_code, 0xFF);
         assert_eq!(analysis.region, Region::UNKNOWN);
         assert_eq!(analysis.region_string, "Unknown");
+        assert_eq!(analysis.raw_region_info, Some((0x7FD9, 0xFF)));
         Ok(())
     }
 
@@ -468,6 +1082,56 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_snes_data_common_lenient() -> Result<(), RomAnalyzerError> {
+        let data = generate_snes_header(0x80000, 0, 0x0E, false, "TEST GAME TITLE", None);
+        let analysis = analyze_snes_data_with_options(&data, "test_common (J).sfc", false)?;
+
+        assert_eq!(
+            analysis.region,
+            Region::USA | Region::EUROPE | Region::JAPAN | Region::ASIA
+        );
+        assert_eq!(analysis.region_string, "Common / International");
+        assert!(!analysis.region_mismatch);
+        assert_eq!(analysis, analyze_snes_data(&data, "test_common (J).sfc")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_common_strict_world() -> Result<(), RomAnalyzerError> {
+        let data = generate_snes_header(0x80000, 0, 0x0E, false, "TEST GAME TITLE", None);
+        let analysis = analyze_snes_data_with_options(&data, "test_common (J).sfc", true)?;
+
+        assert_eq!(analysis.region_code, 0x0E);
+        assert_eq!(
+            analysis.region,
+            Region::USA | Region::EUROPE | Region::JAPAN | Region::ASIA
+        );
+        assert_eq!(analysis.region_string, "Common / International");
+        assert!(analysis.region_mismatch);
+        assert!(analysis.raw_region_info.is_none());
+
+        // Filenames naming several regions, or none, still match.
+        for name in [
+            "test_common (U) (E).sfc",
+            "test_common (World).sfc",
+            "test_common.sfc",
+        ] {
+            assert!(!analyze_snes_data_with_options(&data, name, true)?.region_mismatch);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_strict_world_ignores_other_codes() -> Result<(), RomAnalyzerError> {
+        let data = generate_snes_header(0x80000, 0, 0x01, false, "TEST GAME TITLE", None);
+        let analysis = analyze_snes_data_with_options(&data, "test_game (J).sfc", true)?;
+
+        assert_eq!(analysis.region, Region::USA);
+        assert!(analysis.region_mismatch);
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_snes_data_minimal_lorom_size() -> Result<(), RomAnalyzerError> {
         // Minimal size for LoROM: header at 0x7FC0, needs up to 0x7FE0 for checksum.
@@ -511,6 +1175,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_analyze_snes_data_tiny_input_is_data_too_small() {
+        // Inputs far too small for any header location must still yield the structured
+        // DataTooSmall variant rather than a Generic error.
+        for size in [0, 0x20, 0x1000] {
+            let data = vec![0; size];
+            match analyze_snes_data(&data, "tiny.sfc") {
+                Err(RomAnalyzerError::DataTooSmall { file_size, .. }) => {
+                    assert_eq!(file_size, size)
+                }
+                other => panic!("Expected DataTooSmall error, got {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn test_analyze_snes_data_hirom_checksum_map_mode_consistent() -> Result<(), RomAnalyzerError> {
         let data =
@@ -591,6 +1270,7 @@ mod tests {
         let analysis = analyze_snes_data(&data, "test_no_checksum_hirom_map.sfc")?;
 
         assert_eq!(analysis.mapping_type, "LoROM (Unverified)"); // Expect fallback
+        assert!(!analysis.checksum_valid);
         Ok(())
     }
     #[test]
@@ -618,6 +1298,227 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_analyze_snes_data_512kb_lorom_with_hirom_map_mode_noise() -> Result<(), RomAnalyzerError>
+    {
+        // A 512KB LoROM whose data at the HiROM location happens to look like a HiROM Map Mode
+        // byte, without a matching checksum, must still be read from the LoROM header.
+        let mut data = generate_snes_header(0x80000, 0, 0x00, false, "LOROM GAME", Some(0x20));
+        data[0xFFC0 + MAP_MODE_OFFSET] = 0x21;
+        data[0xFFC0 + 0x1C..0xFFC0 + 0x20].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        let analysis = analyze_snes_data(&data, "test_lorom_512kb.sfc")?;
+
+        assert_eq!(analysis.mapping_type, "LoROM");
+        assert_eq!(analysis.game_title, "LOROM GAME");
+        assert_eq!(analysis.region, Region::JAPAN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_partial_hirom_header_ignored() -> Result<(), RomAnalyzerError> {
+        // The data ends just past the HiROM Map Mode byte, so the HiROM header block is cut off
+        // before its checksum. It must be ignored entirely rather than half-considered.
+        let mut data = generate_snes_header(0x10000, 0, 0x01, false, "LOROM GAME", Some(0x20));
+        data.truncate(0xFFC0 + MAP_MODE_OFFSET + 1);
+        data[0xFFC0 + MAP_MODE_OFFSET] = 0x21;
+        let analysis = analyze_snes_data(&data, "test_lorom_partial.sfc")?;
+
+        assert_eq!(analysis.mapping_type, "LoROM");
+        assert_eq!(analysis.game_title, "LOROM GAME");
+        assert_eq!(analysis.region, Region::USA);
+        Ok(())
+    }
+
+    /// Test helper to interleave a ROM the way interleaved dumps store it.
+    fn interleave(rom_data: &[u8]) -> Vec<u8> {
+        let blocks: Vec<&[u8]> = rom_data.chunks(INTERLEAVE_BLOCK_SIZE).collect();
+        let odd = blocks.iter().skip(1).step_by(2);
+        let even = blocks.iter().step_by(2);
+        odd.chain(even)
+            .flat_map(|block| block.iter().copied())
+            .collect()
+    }
+
+    #[test]
+    fn test_analyze_snes_data_interleaved_lorom() -> Result<(), RomAnalyzerError> {
+        let data = generate_snes_header(0x100000, 0, 0x01, false, "INTERLEAVED", Some(0x20));
+        let analysis = analyze_snes_data(&interleave(&data), "test_interleaved.sfc")?;
+
+        assert!(analysis.interleaved);
+        assert_eq!(analysis.mapping_type, "LoROM");
+        assert_eq!(analysis.game_title, "INTERLEAVED");
+        assert_eq!(analysis.region, Region::USA);
+        assert_eq!(
+            analysis.print(),
+            "test_interleaved.sfc\n\
+             System:       Super Nintendo (SNES)\n\
+             Game Title:   INTERLEAVED\n\
+             Mapping:      LoROM\n\
+             Storage:      Interleaved\n\
+             Region Code:  0x01\n\
+             Region:       USA"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_interleaved_with_copier_header() -> Result<(), RomAnalyzerError> {
+        let data = generate_snes_header(0x100000, 0, 0x00, false, "INTERLEAVED", Some(0x20));
+        let mut copier_data = vec![0; 512];
+        copier_data.extend(interleave(&data));
+        let analysis = analyze_snes_data(&copier_data, "test_interleaved_copier.sfc")?;
+
+        assert!(analysis.interleaved);
+        assert_eq!(analysis.mapping_type, "LoROM");
+        assert_eq!(analysis.game_title, "INTERLEAVED");
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_valid_rom_not_deinterleaved() -> Result<(), RomAnalyzerError> {
+        let data = generate_snes_header(0x100000, 0, 0x01, true, "PLAIN", Some(0x21));
+        let analysis = analyze_snes_data(&data, "test_plain.sfc")?;
+
+        assert!(!analysis.interleaved);
+        assert_eq!(analysis.mapping_type, "HiROM");
+        assert_eq!(analysis.game_title, "PLAIN");
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_no_valid_header_stays_unverified() -> Result<(), RomAnalyzerError> {
+        let data = vec![0; 0x100000];
+        let analysis = analyze_snes_data(&data, "test_blank.sfc")?;
+
+        assert!(!analysis.interleaved);
+        assert_eq!(analysis.mapping_type, "LoROM (Unverified)");
+        assert_eq!(analysis.format, SnesFormat::LoRom);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_exhirom() -> Result<(), RomAnalyzerError> {
+        let mut data = vec![0; EXHIROM_HEADER_START + 0x40];
+        let header = &mut data[EXHIROM_HEADER_START..EXHIROM_HEADER_START + SNES_HEADER_SIZE];
+        header[..8].copy_from_slice(b"EXHIROM ");
+        header[MAP_MODE_OFFSET] = 0x35;
+        header[0x19] = 0x00;
+        header[0x1C..0x20].copy_from_slice(&[0x55, 0x55, 0xAA, 0xAA]);
+        let analysis = analyze_snes_data(&data, "test_exhirom (Japan).sfc")?;
+
+        assert!(analysis.checksum_valid);
+        assert_eq!(analysis.format, SnesFormat::ExHiRom);
+        assert_eq!(analysis.mapping_type, "ExHiROM");
+        assert_eq!(analysis.header_offset, EXHIROM_HEADER_START);
+        assert_eq!(analysis.game_title, "EXHIROM");
+        assert_eq!(analysis.region, Region::JAPAN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_exhirom_6mb_with_hirom_copy() -> Result<(), RomAnalyzerError> {
+        // ExHiROM games often keep a header at the HiROM location too, which must not win.
+        let mut data = generate_snes_header(0x600000, 0, 0x01, true, "HIROM COPY", Some(0x21));
+        data[EXHIROM_HEADER_START..EXHIROM_HEADER_START + SNES_HEADER_SIZE].copy_from_slice(
+            &generate_snes_header(0xFFE0, 0, 0x01, true, "TALES OF PHANTASIA", Some(0x35))
+                [HIROM_HEADER_START..],
+        );
+        let analysis = analyze_snes_data(&data, "test_exhirom_6mb.sfc")?;
+
+        assert_eq!(analysis.mapping_type, "ExHiROM");
+        assert_eq!(analysis.format, SnesFormat::ExHiRom);
+        assert_eq!(analysis.game_title, "TALES OF PHANTASIA");
+        assert_eq!(analysis.rom_size, 0x600000);
+
+        // The same header in a 4MB ROM is out of the ExHiROM range, leaving the HiROM header.
+        data.truncate(EXHIROM_MIN_ROM_SIZE);
+        let analysis = analyze_snes_data(&data, "test_hirom_4mb.sfc")?;
+        assert_eq!(analysis.mapping_type, "HiROM");
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_snes_data_bsx() -> Result<(), RomAnalyzerError> {
+        let mut data = generate_snes_header(0x100000, 0, 0xFF, false, "", None);
+        let header = &mut data[LOROM_HEADER_START..LOROM_HEADER_START + SNES_HEADER_SIZE];
+        header[..BSX_TITLE_LENGTH].copy_from_slice(b"BS ZELDA MAP 1  ");
+        header[BSX_MONTH_OFFSET] = 0x80; // August
+        header[BSX_DAY_OFFSET] = 0x06;
+        header[BSX_MAP_MODE_OFFSET] = 0x20;
+        header[DEVELOPER_ID_OFFSET] = USE_EXTENDED_HEADER;
+        data[LOROM_HEADER_START - EXTENDED_HEADER_SIZE..][..2].copy_from_slice(b"01");
+        let analysis = analyze_snes_data(&data, "test_bsx.bs")?;
+
+        assert!(analysis.checksum_valid);
+        assert_eq!(analysis.format, SnesFormat::Bsx);
+        assert_eq!(analysis.mapping_type, "BS-X (LoROM)");
+        assert_eq!(analysis.game_title, "BS ZELDA MAP 1");
+        assert_eq!(analysis.region, Region::JAPAN);
+        assert_eq!(analysis.region_string, "Japan (BS-X)");
+        assert_eq!(analysis.video_system, VideoSystem::Ntsc);
+        assert_eq!(analysis.maker_code, "01");
+        assert_eq!(analysis.declared_size, None);
+        assert!(!analysis.size_mismatch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_bsx_header() {
+        let mut data = vec![0; SNES_HEADER_SIZE];
+        data[DEVELOPER_ID_OFFSET] = USE_EXTENDED_HEADER;
+        data[BSX_MAP_MODE_OFFSET] = 0x21;
+        assert!(is_bsx_header(&data, 0));
+
+        // A broadcast month outside 1-12 isn't a BS-X date.
+        data[BSX_MONTH_OFFSET] = 0xD0;
+        assert!(!is_bsx_header(&data, 0));
+
+        // Standard headers hold the RAM size at 0x18.
+        data[BSX_MONTH_OFFSET] = 0x00;
+        data[BSX_MAP_MODE_OFFSET] = 0x03;
+        assert!(!is_bsx_header(&data, 0));
+        assert!(!is_bsx_header(&data, 1));
+    }
+
+    #[test]
+    fn test_deinterleave_round_trip() {
+        let data: Vec<u8> = (0..0x40000)
+            .map(|i| (i / INTERLEAVE_BLOCK_SIZE) as u8)
+            .collect();
+        assert_eq!(deinterleave(&interleave(&data)), Some(data));
+        assert_eq!(deinterleave(&[0; 0x18000]), None);
+        assert_eq!(deinterleave(&[]), None);
+    }
+
+    #[test]
+    fn test_snes_header_in_bounds() {
+        assert!(snes_header_in_bounds(
+            0x8000,
+            0x7FC0 + 0x20 - SNES_HEADER_SIZE
+        ));
+        assert!(snes_header_in_bounds(0x7FE0, 0x7FC0));
+        assert!(!snes_header_in_bounds(0x7FDF, 0x7FC0));
+        assert!(!snes_header_in_bounds(0x8000, usize::MAX));
+    }
+
+    #[test]
+    fn test_map_video_system_all_codes() {
+        for code in 0x00..=0xFF {
+            let expected = match map_region(code).0 {
+                "Common / International" => VideoSystem::Unknown,
+                name if name.ends_with("(PAL)") => VideoSystem::Pal,
+                name if name.ends_with("(NTSC)") => VideoSystem::Ntsc,
+                _ => VideoSystem::Unknown,
+            };
+            assert_eq!(
+                map_video_system(code),
+                expected,
+                "Failed for 0x{:02X}",
+                code
+            );
+        }
+    }
+
     #[test]
     fn test_map_region_all_codes() {
         // Test all known region codes to catch "delete match arm" mutations
@@ -659,4 +1560,28 @@ mod tests {
             assert_eq!(region, expected_region, "Failed for code 0x{:02X}", code);
         }
     }
+
+    #[test]
+    fn test_analyze_snes_data_checksum_fallback_logs_fields() {
+        // Neither checksum validates in an empty ROM, so the LoROM fallback is logged.
+        let data = vec![0; 0x8000];
+        let records = capture_logs(|| {
+            let _ = analyze_snes_data(&data, "broken.sfc");
+        });
+
+        let record = records
+            .iter()
+            .find(|record| {
+                record.fields.get("reason").map(String::as_str) == Some("checksum_invalid")
+            })
+            .expect("Expected a checksum fallback log record");
+        assert_eq!(record.level, Level::Error);
+        assert_eq!(record.fields["console"], "snes");
+        assert_eq!(record.fields["path"], "broken.sfc");
+        assert!(
+            record
+                .message
+                .starts_with("[!] Checksum validation failed for broken.sfc.")
+        );
+    }
 }
@@ -0,0 +1,219 @@
+//! Provides header analysis functionality for 3DO Interactive Multiplayer disc images.
+//!
+//! This module recognizes the Opera filesystem volume header at the start of a 3DO disc and
+//! extracts its volume label. 3DO discs are not region locked and their header has no region
+//! code, so the region is inferred from the filename.
+
+use serde::Serialize;
+
+use crate::console::{RomMetadata, archive_source_field, field};
+use crate::error::RomAnalyzerError;
+use crate::region::{
+    Confidence, Region, VideoSystem, check_region_mismatch, infer_region_from_filename,
+};
+use crate::util::sanitize_title;
+
+// The volume header starts with a record type of 1, five 'Z' sync bytes and a version of 1.
+const THREEDO_VOLUME_SIGNATURE: &[u8] = b"\x01ZZZZZ\x01";
+// Raw (2352-byte sector) images carry a 16-byte sync and sector header before the data.
+const RAW_SECTOR_DATA_OFFSET: usize = 0x10;
+const VOLUME_LABEL_START: usize = 0x28;
+const VOLUME_LABEL_END: usize = 0x48;
+
+/// Struct to hold the analysis results for a 3DO disc image.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct ThreeDoAnalysis {
+    /// The name of the source file.
+    pub source_name: String,
+    /// The region(s) inferred from the filename as a region::Region bitmask.
+    pub region: Region,
+    /// The inferred region name (e.g., "Europe").
+    pub region_string: String,
+    /// If the region in the ROM header doesn't match the region in the filename.
+    pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The volume label extracted from the volume header.
+    pub volume_label: String,
+    /// The video system, PAL for European discs and NTSC for other known regions.
+    pub video_system: VideoSystem,
+}
+
+impl RomMetadata for ThreeDoAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "3DO Interactive Multiplayer")),
+            Some(field("Volume Label", &self.volume_label)),
+            Some(field("Video", self.video_system)),
+            Some(field("Region", self.region)),
+            Some(field(
+                "Note",
+                "Region information not in disc header, inferred from filename.",
+            )),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Helper function to locate the 3DO volume header in a disc image.
+///
+/// The header is at the start of the image for ISO (2048-byte sector) images, and right after
+/// the sector header for raw (2352-byte sector) images.
+///
+/// # Returns
+///
+/// The offset of the volume header, or `None` if the signature isn't found.
+pub(crate) fn find_volume_header(data: &[u8]) -> Option<usize> {
+    [0, RAW_SECTOR_DATA_OFFSET].into_iter().find(|&offset| {
+        data.get(offset..offset + THREEDO_VOLUME_SIGNATURE.len()) == Some(THREEDO_VOLUME_SIGNATURE)
+    })
+}
+
+/// Determines the video system of a 3DO disc from its region.
+///
+/// The disc header doesn't record the video system, but discs were released as PAL in Europe
+/// and NTSC elsewhere.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::console::threedo::map_video_system;
+/// use rom_analyzer::region::{Region, VideoSystem};
+///
+/// assert_eq!(map_video_system(Region::EUROPE), VideoSystem::Pal);
+/// assert_eq!(map_video_system(Region::USA), VideoSystem::Ntsc);
+/// assert_eq!(map_video_system(Region::UNKNOWN), VideoSystem::Unknown);
+/// ```
+pub fn map_video_system(region: Region) -> VideoSystem {
+    if region.contains(Region::EUROPE) {
+        VideoSystem::Pal
+    } else if region == Region::UNKNOWN {
+        VideoSystem::Unknown
+    } else {
+        VideoSystem::Ntsc
+    }
+}
+
+/// Analyzes 3DO disc image data.
+///
+/// This function locates the Opera filesystem volume header and extracts the volume label.
+/// The region is inferred from the `source_name`, and the video system is derived from it.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw disc data (e.g., from a `.iso` file).
+/// * `source_name` - The name of the disc image, used for region inference.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`ThreeDoAnalysis`]) containing the detailed analysis results.
+/// - `Err`([`RomAnalyzerError::InvalidHeader`]) if no complete 3DO volume header is found.
+pub fn analyze_threedo_data(
+    data: &[u8],
+    source_name: &str,
+) -> Result<ThreeDoAnalysis, RomAnalyzerError> {
+    let label = find_volume_header(data)
+        .and_then(|offset| data.get(offset + VOLUME_LABEL_START..offset + VOLUME_LABEL_END))
+        .ok_or_else(|| RomAnalyzerError::InvalidHeader("No 3DO volume header found".to_string()))?;
+    let volume_label = sanitize_title(label);
+
+    let region = infer_region_from_filename(source_name);
+    let region_mismatch = check_region_mismatch(source_name, region);
+
+    Ok(ThreeDoAnalysis {
+        source_name: source_name.to_string(),
+        region,
+        region_string: region.to_string(),
+        region_mismatch,
+        region_confidence: Confidence::from_filename(region),
+        archive_source: None,
+        volume_label,
+        video_system: map_video_system(region),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_volume_header(offset: usize, label: &str) -> Vec<u8> {
+        let mut data = vec![0; 0x2000];
+        data[offset..offset + THREEDO_VOLUME_SIGNATURE.len()]
+            .copy_from_slice(THREEDO_VOLUME_SIGNATURE);
+        let label_start = offset + VOLUME_LABEL_START;
+        data[label_start..label_start + label.len()].copy_from_slice(label.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_analyze_threedo_data() -> Result<(), RomAnalyzerError> {
+        let data = generate_volume_header(0, "CD-ROM");
+        let analysis = analyze_threedo_data(&data, "Road Rash (Europe).iso")?;
+
+        assert_eq!(analysis.volume_label, "CD-ROM");
+        assert_eq!(analysis.region, Region::EUROPE);
+        assert_eq!(analysis.region_string, "Europe");
+        assert_eq!(analysis.video_system, VideoSystem::Pal);
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "Road Rash (Europe).iso\n\
+             System:       3DO Interactive Multiplayer\n\
+             Volume Label: CD-ROM\n\
+             Video:        PAL\n\
+             Region:       Europe\n\
+             Note:         Region information not in disc header, inferred from filename."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_threedo_data_raw_sectors() -> Result<(), RomAnalyzerError> {
+        let data = generate_volume_header(RAW_SECTOR_DATA_OFFSET, "CD-ROM");
+        let analysis = analyze_threedo_data(&data, "game (USA).bin")?;
+
+        assert_eq!(analysis.volume_label, "CD-ROM");
+        assert_eq!(analysis.video_system, VideoSystem::Ntsc);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_threedo_data_no_header() {
+        let result = analyze_threedo_data(&vec![0; 0x2000], "game.iso");
+        assert!(matches!(result, Err(RomAnalyzerError::InvalidHeader(_))));
+
+        // A signature without room for the label isn't a complete header.
+        let result = analyze_threedo_data(THREEDO_VOLUME_SIGNATURE, "game.iso");
+        assert!(matches!(result, Err(RomAnalyzerError::InvalidHeader(_))));
+    }
+}
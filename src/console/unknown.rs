@@ -0,0 +1,117 @@
+//! Provides the fallback result for files no console analyzer recognizes.
+//!
+//! These results are only produced on request (see [`crate::analyze_rom_data_or_unknown`]), so
+//! that batch runs over mixed directories still report every file with at least the region
+//! inferred from its filename.
+
+use serde::Serialize;
+
+use crate::console::{RomMetadata, archive_source_field, field};
+use crate::region::{Confidence, Region, infer_region_from_filename};
+
+/// Struct to hold the analysis results for an unrecognized file.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct UnknownAnalysis {
+    /// The name of the source file.
+    pub source_name: String,
+    /// The region(s) inferred from the filename as a region::Region bitmask.
+    pub region: Region,
+    /// The inferred region name (e.g., "USA").
+    pub region_string: String,
+    /// Always `false`, as there is no header to compare the filename against.
+    pub region_mismatch: bool,
+    /// How the region was determined, see [`Confidence`].
+    pub region_confidence: Confidence,
+    /// The path of the archive (ZIP or CHD) the ROM was read from, if any.
+    pub archive_source: Option<String>,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+impl RomMetadata for UnknownAnalysis {
+    fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn region_string(&self) -> &str {
+        &self.region_string
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.region_mismatch
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.region_confidence
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.archive_source.as_deref()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        [
+            archive_source_field(self.archive_source.as_deref()),
+            Some(field("System", "Unknown")),
+            Some(field("Size", format!("{} bytes", self.size))),
+            Some(field("Region", self.region)),
+            Some(field(
+                "Note",
+                "File format not recognized, region inferred from filename.",
+            )),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Builds the result for a file no console analyzer recognizes.
+///
+/// # Arguments
+///
+/// * `source_name` - The name of the file, used for region inference.
+/// * `size` - The size of the file in bytes.
+///
+/// # Returns
+///
+/// An [`UnknownAnalysis`] with the region inferred from the `source_name`.
+pub fn analyze_unknown_file(source_name: &str, size: u64) -> UnknownAnalysis {
+    let region = infer_region_from_filename(source_name);
+
+    UnknownAnalysis {
+        source_name: source_name.to_string(),
+        region,
+        region_string: region.to_string(),
+        region_mismatch: false,
+        region_confidence: Confidence::from_filename(region),
+        archive_source: None,
+        size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_unknown_file() {
+        let analysis = analyze_unknown_file("Manual (Europe).txt", 1234);
+
+        assert_eq!(analysis.region, Region::EUROPE);
+        assert_eq!(analysis.region_string, "Europe");
+        assert!(!analysis.region_mismatch);
+        assert_eq!(
+            analysis.print(),
+            "Manual (Europe).txt\n\
+             System:       Unknown\n\
+             Size:         1234 bytes\n\
+             Region:       Europe\n\
+             Note:         File format not recognized, region inferred from filename."
+        );
+    }
+}
@@ -18,6 +18,12 @@ pub enum RomAnalyzerError {
     },
     /// Header data is invalid or corrupted
     InvalidHeader(String),
+    /// Expected console signature is missing from the header
+    InvalidSignature {
+        expected: String,
+        found: String,
+        offset: usize,
+    },
     /// Reserved for future parsing error handling
     ParsingError(String),
     /// Checksum validation failed
@@ -38,6 +44,46 @@ pub enum RomAnalyzerError {
     WithPath(String, Box<RomAnalyzerError>),
 }
 
+/// The kind of a [`RomAnalyzerError`], without any of its payload.
+///
+/// This allows matching on the category of an error without destructuring the full enum or
+/// parsing its message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RomAnalyzerErrorKind {
+    UnsupportedFormat,
+    DataTooSmall,
+    InvalidHeader,
+    InvalidSignature,
+    ParsingError,
+    ChecksumMismatch,
+    ArchiveError,
+    Io,
+    Zip,
+    Chd,
+    FileNotFound,
+    Generic,
+}
+
+impl RomAnalyzerErrorKind {
+    /// Returns a stable, machine-readable snake_case name for the kind.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RomAnalyzerErrorKind::UnsupportedFormat => "unsupported_format",
+            RomAnalyzerErrorKind::DataTooSmall => "data_too_small",
+            RomAnalyzerErrorKind::InvalidHeader => "invalid_header",
+            RomAnalyzerErrorKind::InvalidSignature => "invalid_signature",
+            RomAnalyzerErrorKind::ParsingError => "parsing_error",
+            RomAnalyzerErrorKind::ChecksumMismatch => "checksum_mismatch",
+            RomAnalyzerErrorKind::ArchiveError => "archive_error",
+            RomAnalyzerErrorKind::Io => "io_error",
+            RomAnalyzerErrorKind::Zip => "zip_error",
+            RomAnalyzerErrorKind::Chd => "chd_error",
+            RomAnalyzerErrorKind::FileNotFound => "file_not_found",
+            RomAnalyzerErrorKind::Generic => "generic",
+        }
+    }
+}
+
 impl RomAnalyzerError {
     /// Creates a new generic [`RomAnalyzerError`] with the given message.
     ///
@@ -51,6 +97,69 @@ impl RomAnalyzerError {
     pub fn new(msg: &str) -> RomAnalyzerError {
         RomAnalyzerError::Generic(msg.to_string())
     }
+
+    /// Attaches the path of the file being analyzed to this error.
+    ///
+    /// I/O errors for a missing file become [`RomAnalyzerError::FileNotFound`], which already
+    /// names the path. Any other error is wrapped in [`RomAnalyzerError::WithPath`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rom_analyzer::error::RomAnalyzerError;
+    ///
+    /// let err = RomAnalyzerError::InvalidHeader("bad".to_string()).with_path("game.nes");
+    /// assert_eq!(err.to_string(), "Error processing file game.nes: Invalid header: bad");
+    /// ```
+    pub fn with_path(self, path: &str) -> RomAnalyzerError {
+        match self {
+            RomAnalyzerError::IoError(io_err) if io_err.kind() == std::io::ErrorKind::NotFound => {
+                RomAnalyzerError::FileNotFound(path.to_string())
+            }
+            other => RomAnalyzerError::WithPath(path.to_string(), Box::new(other)),
+        }
+    }
+
+    /// Returns the [`RomAnalyzerErrorKind`] of this error.
+    ///
+    /// Errors wrapped in [`RomAnalyzerError::WithPath`] report the kind of the inner error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rom_analyzer::error::{RomAnalyzerError, RomAnalyzerErrorKind};
+    ///
+    /// let err = RomAnalyzerError::FileNotFound("game.nes".to_string());
+    /// assert_eq!(err.kind(), RomAnalyzerErrorKind::FileNotFound);
+    /// ```
+    pub fn kind(&self) -> RomAnalyzerErrorKind {
+        match self {
+            RomAnalyzerError::UnsupportedFormat(_) => RomAnalyzerErrorKind::UnsupportedFormat,
+            RomAnalyzerError::DataTooSmall { .. } => RomAnalyzerErrorKind::DataTooSmall,
+            RomAnalyzerError::InvalidHeader(_) => RomAnalyzerErrorKind::InvalidHeader,
+            RomAnalyzerError::InvalidSignature { .. } => RomAnalyzerErrorKind::InvalidSignature,
+            RomAnalyzerError::ParsingError(_) => RomAnalyzerErrorKind::ParsingError,
+            RomAnalyzerError::ChecksumMismatch(_) => RomAnalyzerErrorKind::ChecksumMismatch,
+            RomAnalyzerError::ArchiveError(_) => RomAnalyzerErrorKind::ArchiveError,
+            RomAnalyzerError::IoError(_) => RomAnalyzerErrorKind::Io,
+            RomAnalyzerError::ZipError(_) => RomAnalyzerErrorKind::Zip,
+            RomAnalyzerError::ChdError(_) => RomAnalyzerErrorKind::Chd,
+            RomAnalyzerError::FileNotFound(_) => RomAnalyzerErrorKind::FileNotFound,
+            RomAnalyzerError::Generic(_) => RomAnalyzerErrorKind::Generic,
+            RomAnalyzerError::WithPath(_, err) => err.kind(),
+        }
+    }
+
+    /// Returns a stable, machine-readable name for the kind of error.
+    ///
+    /// This is the name of [`RomAnalyzerError::kind`], see [`RomAnalyzerErrorKind::as_str`].
+    ///
+    /// # Returns
+    ///
+    /// A snake_case string such as `"file_not_found"` or `"data_too_small"`.
+    pub fn kind_str(&self) -> &'static str {
+        self.kind().as_str()
+    }
 }
 
 impl fmt::Display for RomAnalyzerError {
@@ -67,6 +176,15 @@ impl fmt::Display for RomAnalyzerError {
                 file_size, required_size, details
             ),
             RomAnalyzerError::InvalidHeader(msg) => write!(f, "Invalid header: {}", msg),
+            RomAnalyzerError::InvalidSignature {
+                expected,
+                found,
+                offset,
+            } => write!(
+                f,
+                "Invalid signature at 0x{:X}: expected '{}', found '{}'",
+                offset, expected, found
+            ),
             RomAnalyzerError::ParsingError(msg) => write!(f, "Parsing error: {}", msg),
             RomAnalyzerError::ChecksumMismatch(msg) => write!(f, "Checksum mismatch: {}", msg),
             RomAnalyzerError::ArchiveError(msg) => write!(f, "Archive error: {}", msg),
@@ -130,6 +248,100 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_kind_maps_each_variant() {
+        let io_err = || std::io::Error::other("io");
+        let test_cases = vec![
+            (
+                RomAnalyzerError::UnsupportedFormat("x".to_string()),
+                RomAnalyzerErrorKind::UnsupportedFormat,
+            ),
+            (
+                RomAnalyzerError::DataTooSmall {
+                    file_size: 1,
+                    required_size: 2,
+                    details: String::new(),
+                },
+                RomAnalyzerErrorKind::DataTooSmall,
+            ),
+            (
+                RomAnalyzerError::InvalidHeader("x".to_string()),
+                RomAnalyzerErrorKind::InvalidHeader,
+            ),
+            (
+                RomAnalyzerError::InvalidSignature {
+                    expected: "NES".to_string(),
+                    found: "SEN".to_string(),
+                    offset: 0,
+                },
+                RomAnalyzerErrorKind::InvalidSignature,
+            ),
+            (
+                RomAnalyzerError::ParsingError("x".to_string()),
+                RomAnalyzerErrorKind::ParsingError,
+            ),
+            (
+                RomAnalyzerError::ChecksumMismatch("x".to_string()),
+                RomAnalyzerErrorKind::ChecksumMismatch,
+            ),
+            (
+                RomAnalyzerError::ArchiveError("x".to_string()),
+                RomAnalyzerErrorKind::ArchiveError,
+            ),
+            (
+                RomAnalyzerError::IoError(io_err()),
+                RomAnalyzerErrorKind::Io,
+            ),
+            (
+                RomAnalyzerError::ZipError(ZipError::FileNotFound),
+                RomAnalyzerErrorKind::Zip,
+            ),
+            (
+                RomAnalyzerError::ChdError(chd::Error::InvalidData),
+                RomAnalyzerErrorKind::Chd,
+            ),
+            (
+                RomAnalyzerError::FileNotFound("x".to_string()),
+                RomAnalyzerErrorKind::FileNotFound,
+            ),
+            (RomAnalyzerError::new("x"), RomAnalyzerErrorKind::Generic),
+            (
+                RomAnalyzerError::WithPath(
+                    "x".to_string(),
+                    Box::new(RomAnalyzerError::IoError(io_err())),
+                ),
+                RomAnalyzerErrorKind::Io,
+            ),
+        ];
+        for (err, expected_kind) in test_cases {
+            assert_eq!(err.kind(), expected_kind, "Failed for {:?}", err);
+            assert_eq!(err.kind_str(), expected_kind.as_str());
+        }
+    }
+
+    #[test]
+    fn test_kind_str() {
+        assert_eq!(
+            RomAnalyzerError::FileNotFound("game.nes".to_string()).kind_str(),
+            "file_not_found"
+        );
+        assert_eq!(
+            RomAnalyzerError::DataTooSmall {
+                file_size: 1,
+                required_size: 2,
+                details: String::new(),
+            }
+            .kind_str(),
+            "data_too_small"
+        );
+        assert_eq!(RomAnalyzerError::new("oops").kind_str(), "generic");
+        let wrapped = RomAnalyzerError::WithPath(
+            "game.zip".to_string(),
+            Box::new(RomAnalyzerError::ArchiveError("empty".to_string())),
+        );
+        assert_eq!(wrapped.kind_str(), "archive_error");
+    }
+
     #[test]
     fn test_display_trait() {
         let error_msg = "Display test";
@@ -156,12 +368,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_display_invalid_signature() {
+        let err = RomAnalyzerError::InvalidSignature {
+            expected: "NES\\x1a".to_string(),
+            found: "XXXX".to_string(),
+            offset: 0,
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Invalid signature at 0x0: expected 'NES\\x1a', found 'XXXX'"
+        );
+    }
+
     #[test]
     fn test_display_file_not_found() {
         let err = RomAnalyzerError::FileNotFound("test.nes".to_string());
         assert_eq!(format!("{}", err), "File not found: test.nes");
     }
 
+    #[test]
+    fn test_display_with_path() {
+        let err = RomAnalyzerError::WithPath(
+            "roms/game.nes".to_string(),
+            Box::new(RomAnalyzerError::InvalidHeader("bad".to_string())),
+        );
+        assert_eq!(
+            format!("{}", err),
+            "Error processing file roms/game.nes: Invalid header: bad"
+        );
+    }
+
     #[test]
     fn test_from_zip_error() {
         let zip_err = ZipError::FileNotFound;
@@ -230,7 +467,10 @@ mod tests {
 
         let dir = tempdir().unwrap();
         let chd_path = dir.path().join("test.chd");
-        std::fs::write(&chd_path, b"invalid chd data").unwrap();
+        // Long enough to reach the CHD parser, which rejects the missing magic.
+        let mut data = b"invalid chd data".to_vec();
+        data.resize(0x100, 0);
+        std::fs::write(&chd_path, data).unwrap();
 
         // Try to analyze the invalid CHD file
         let result = crate::archive::chd::analyze_chd_file(&chd_path);
@@ -245,6 +485,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_with_path() {
+        // Missing files become FileNotFound, which already holds the path.
+        let err = RomAnalyzerError::IoError(IoError::new(ErrorKind::NotFound, "missing"));
+        assert!(matches!(
+            err.with_path("game.nes"),
+            RomAnalyzerError::FileNotFound(path) if path == "game.nes"
+        ));
+
+        // Everything else is wrapped, keeping its kind.
+        let err = RomAnalyzerError::IoError(IoError::new(ErrorKind::PermissionDenied, "denied"))
+            .with_path("game.nes");
+        assert!(matches!(&err, RomAnalyzerError::WithPath(path, _) if path == "game.nes"));
+        assert_eq!(err.kind(), RomAnalyzerErrorKind::Io);
+    }
+
     #[test]
     fn test_error_source_with_path() {
         // Test that WithPath delegates source() to the inner error
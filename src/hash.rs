@@ -0,0 +1,174 @@
+//! Provides functionality for hashing ROM files.
+//!
+//! The CRC32 and SHA1 checksums computed here are the ones ROM databases (such as No-Intro and
+//! Redump) use to identify dumps, so they can be compared directly against DAT files.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+
+use crate::error::RomAnalyzerError;
+
+// Files are hashed in chunks so large CD images are never fully loaded into memory.
+const HASH_CHUNK_SIZE: usize = 0x10000; // 64KB
+
+/// Struct to hold the checksums of a ROM file.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Hashes {
+    /// The CRC32 checksum of the file.
+    pub crc32: u32,
+    /// The SHA1 digest of the file as a lowercase hex string.
+    pub sha1: String,
+}
+
+impl fmt::Display for Hashes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08x} {}", self.crc32, self.sha1)
+    }
+}
+
+/// A reader that computes the CRC32 and SHA1 checksums of every byte read through it.
+///
+/// This lets a file be analyzed and hashed in a single pass: the header is read through the
+/// `HashingReader`, and [`HashingReader::finish`] then hashes the rest of the file, rather than
+/// reading the file a second time to hash it.
+pub struct HashingReader<R> {
+    inner: R,
+    crc32: crc32fast::Hasher,
+    sha1: Sha1,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wraps `inner`, hashing the bytes read from it.
+    pub fn new(inner: R) -> Self {
+        HashingReader {
+            inner,
+            crc32: crc32fast::Hasher::new(),
+            sha1: Sha1::new(),
+        }
+    }
+
+    /// Reads the rest of the underlying reader and returns the checksums of all its bytes.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is:
+    /// - `Ok`([`Hashes`]) containing the checksums of the data.
+    /// - `Err`([`RomAnalyzerError`]) if reading fails.
+    pub fn finish(mut self) -> Result<Hashes, RomAnalyzerError> {
+        let mut buf = vec![0; HASH_CHUNK_SIZE];
+        while self.read(&mut buf)? != 0 {}
+
+        Ok(Hashes {
+            crc32: self.crc32.finalize(),
+            sha1: self
+                .sha1
+                .finalize()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        })
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.crc32.update(&buf[..read]);
+        self.sha1.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Computes the CRC32 and SHA1 checksums of everything read from `reader`.
+///
+/// # Arguments
+///
+/// * `reader` - The source of the bytes to hash.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`Hashes`]) containing the checksums of the data.
+/// - `Err`([`RomAnalyzerError`]) if reading fails.
+pub fn hash_reader<R: Read>(reader: R) -> Result<Hashes, RomAnalyzerError> {
+    HashingReader::new(reader).finish()
+}
+
+/// Computes the CRC32 and SHA1 checksums of a file, streaming it from disk.
+///
+/// The file is hashed as-is, without any header parsing or archive extraction.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file to hash.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// - `Ok`([`Hashes`]) containing the checksums of the file.
+/// - `Err`([`RomAnalyzerError`]) if the file cannot be opened or read.
+pub fn hash_file(path: &Path) -> Result<Hashes, RomAnalyzerError> {
+    hash_reader(File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    const TEST_INPUT: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn test_hash_file_known_input() -> Result<(), RomAnalyzerError> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("fox.bin");
+        std::fs::write(&path, TEST_INPUT)?;
+
+        let hashes = hash_file(&path)?;
+        assert_eq!(hashes.crc32, 0x414FA339);
+        assert_eq!(hashes.sha1, "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+        assert_eq!(
+            hashes.to_string(),
+            "414fa339 2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_reader_spans_chunks() -> Result<(), RomAnalyzerError> {
+        // Hashing across chunk boundaries must match hashing the data in one go.
+        let data: Vec<u8> = (0..HASH_CHUNK_SIZE * 2 + 7).map(|i| i as u8).collect();
+        let hashes = hash_reader(data.as_slice())?;
+        assert_eq!(hashes.crc32, crc32fast::hash(&data));
+        Ok(())
+    }
+
+    #[test]
+    fn test_hashing_reader_partial_reads() -> Result<(), RomAnalyzerError> {
+        // Bytes read before finish() count towards the hashes, as when a header is read first.
+        let data: Vec<u8> = (0..HASH_CHUNK_SIZE + 0x123)
+            .map(|i| (i * 7) as u8)
+            .collect();
+        let mut reader = HashingReader::new(data.as_slice());
+        let mut header = vec![0; 0x200];
+        reader.read_exact(&mut header)?;
+        assert_eq!(header, data[..0x200]);
+        assert_eq!(reader.finish()?, hash_reader(data.as_slice())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_file_non_existent() {
+        match hash_file(Path::new("non_existent_file.nes")) {
+            Err(RomAnalyzerError::IoError(io_err)) => {
+                assert_eq!(io_err.kind(), ErrorKind::NotFound)
+            }
+            other => panic!("Expected IoError variant, got {:?}", other),
+        }
+    }
+}
@@ -4,7 +4,8 @@
 //!
 //! This library supports a range of console ROMs, including but not limited to NES, SNES, N64,
 //! Sega Master System, Game Gear, Game Boy, Game Boy Advance, Sega Genesis, and Sega CD.  It can
-//! also handle ROMs packaged as ZIP or CHD (Compressed Hunks of Data) archives.
+//! also handle ROMs packaged as ZIP, CHD (Compressed Hunks of Data) or CSO (compressed ISO)
+//! archives.
 //!
 //! The primary entry point for analysis is the [`analyze_rom_data`] function, which takes a file
 //! path and returns a [`RomAnalysisResult`] enum containing console-specific analysis data.
@@ -12,64 +13,210 @@
 pub mod archive;
 pub mod console;
 pub mod error;
+pub mod hash;
+pub mod multicart;
+pub mod publisher;
 pub mod region;
+pub mod registry;
+pub mod util;
+pub mod xml;
 
-use std::fs::{self, File};
+use std::fs::File;
+use std::io::Read;
+use std::ops::Range;
 use std::path::Path;
 
+use log::{debug, warn};
+use rayon::prelude::*;
 use serde::Serialize;
 
 use crate::archive::chd::analyze_chd_file;
-use crate::archive::zip::process_zip_file;
-use crate::console::gamegear::{self, GameGearAnalysis};
+use crate::archive::cso::analyze_cso_file;
+use crate::archive::cue::cue_data_track;
+use crate::archive::rvz::analyze_rvz_file;
+use crate::archive::zip::{extract_zip_rom, extract_zip_roms_all};
+use crate::console::RomMetadata;
+use crate::console::atari7800::{self, Atari7800Analysis};
+use crate::console::cdimage::CdImageAnalysis;
+use crate::console::gamecube::{self, GameCubeAnalysis};
+use crate::console::gamegear::GameGearAnalysis;
 use crate::console::gb::{self, GbAnalysis};
 use crate::console::gba::{self, GbaAnalysis};
 use crate::console::genesis::{self, GenesisAnalysis};
+use crate::console::intellivision::{self, IntellivisionAnalysis};
 use crate::console::mastersystem::{self, MasterSystemAnalysis};
 use crate::console::n64::{self, N64Analysis};
+use crate::console::neogeo::{self, NeoGeoAnalysis};
 use crate::console::nes::{self, NesAnalysis};
+use crate::console::psp::{self, PspAnalysis};
 use crate::console::psx::{self, PsxAnalysis};
 use crate::console::segacd::{self, SegaCdAnalysis};
 use crate::console::snes::{self, SnesAnalysis};
-use crate::error::RomAnalyzerError;
+use crate::console::threedo::ThreeDoAnalysis;
+use crate::console::unknown::{self, UnknownAnalysis};
+use crate::error::{RomAnalyzerError, RomAnalyzerErrorKind};
+use crate::hash::{Hashes, HashingReader, hash_file};
+use crate::region::{
+    Confidence, MismatchMode, Region, VideoSystem, check_region_mismatch_with_mode,
+    infer_region_from_filename,
+};
 
 /// A list of file extensions that the ROM analyzer supports.
 /// These extensions are used to determine the type of ROM file being processed.
-pub const SUPPORTED_ROM_EXTENSIONS: &[&str] = &[
-    ".nes", // NES
-    ".smc", ".sfc", // SNES
-    ".n64", ".v64", ".z64", // N64
-    ".sms", // Sega Master System
-    ".gg",  // Sega Game Gear
-    ".md", ".gen", ".32x", // Sega Genesis / 32X
-    ".gb", ".gbc", // Game Boy / Game Boy Color
-    ".gba", // Game Boy Advance
-    ".scd", // Sega CD
-    ".iso", ".bin", ".img", ".psx", // CD Systems
-];
+///
+/// The list is built from the extensions of [`supported_consoles`], leaving out the container
+/// formats in [`CONTAINER_EXTENSIONS`].
+pub const SUPPORTED_ROM_EXTENSIONS: &[&str] = &ROM_EXTENSIONS;
+
+/// The extensions of compressed disc images (CHD, CSO, RVZ and WIA). They are mapped to a
+/// console, but can only be decompressed from a file on disk, so they aren't picked from ZIP
+/// archives and are left out of [`SUPPORTED_ROM_EXTENSIONS`].
+pub const CONTAINER_EXTENSIONS: &[&str] = &[".chd", ".cso", ".rvz", ".wia"];
+
+const ROM_EXTENSIONS: [&str; rom_extension_count()] = rom_extensions();
+
+/// Returns whether `extension` is one of [`CONTAINER_EXTENSIONS`].
+const fn is_container_extension(extension: &str) -> bool {
+    let mut i = 0;
+    while i < CONTAINER_EXTENSIONS.len() {
+        if bytes_eq(extension.as_bytes(), CONTAINER_EXTENSIONS[i].as_bytes()) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Compares two byte slices, as `==` isn't available in const functions.
+const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Counts the console extensions that aren't container formats.
+const fn rom_extension_count() -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < CONSOLES.len() {
+        let extensions = CONSOLES[i].1.extensions;
+        let mut j = 0;
+        while j < extensions.len() {
+            if !is_container_extension(extensions[j]) {
+                count += 1;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Collects the console extensions that aren't container formats, in [`CONSOLES`] order.
+const fn rom_extensions<const N: usize>() -> [&'static str; N] {
+    let mut result = [""; N];
+    let mut count = 0;
+    let mut i = 0;
+    while i < CONSOLES.len() {
+        let extensions = CONSOLES[i].1.extensions;
+        let mut j = 0;
+        while j < extensions.len() {
+            if !is_container_extension(extensions[j]) {
+                result[count] = extensions[j];
+                count += 1;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    result
+}
 
 pub const SEGA_MEGA_DRIVE_SIG: &[u8] = b"SEGA MEGA DRIVE";
 pub const SEGA_GENESIS_SIG: &[u8] = b"SEGA GENESIS";
 
+/// The number of leading bytes [`detect_rom_file_type`] needs to recognize every console it
+/// knows about.
+pub const ROM_DETECTION_PREFIX_SIZE: usize = 0x200;
+
+// Big-endian (.z64) N64 ROMs start with the PI BSD domain configuration word.
+const N64_Z64_MAGIC: &[u8] = &[0x80, 0x37, 0x12, 0x40];
+
+// How far into a PSP image a plain file read is extended to reach its PARAM.SFO.
+const MAX_PSP_PARAM_SFO_END: usize = 0x400_0000; // 64MB
+
 /// Represents the analysis result for a ROM file.
 #[derive(Debug, PartialEq, Clone, Serialize)]
 #[serde(tag = "console")]
 pub enum RomAnalysisResult {
+    Atari7800(Atari7800Analysis),
+    /// A CD image with an ISO9660 filesystem that no console analyzer recognized.
+    CdImage(CdImageAnalysis),
+    /// A Nintendo GameCube or Wii disc.
+    GameCube(GameCubeAnalysis),
     GameGear(GameGearAnalysis),
     GB(GbAnalysis),
     GBA(GbaAnalysis),
     Genesis(GenesisAnalysis),
+    Intellivision(IntellivisionAnalysis),
     MasterSystem(MasterSystemAnalysis),
     N64(N64Analysis),
+    NeoGeo(NeoGeoAnalysis),
     NES(NesAnalysis),
+    PSP(PspAnalysis),
     PSX(PsxAnalysis),
     SegaCD(SegaCdAnalysis),
     SNES(SnesAnalysis),
+    ThreeDO(ThreeDoAnalysis),
+    /// A file no analyzer recognized, see [`analyze_rom_data_or_unknown`].
+    Unknown(UnknownAnalysis),
+}
+
+/// Holds the best-effort outcome of [`analyze_rom_data_lenient`].
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct LenientAnalysis {
+    /// The path of the analyzed file.
+    pub source_name: String,
+    /// The full header analysis, or `None` if the header couldn't be read.
+    pub analysis: Option<RomAnalysisResult>,
+    /// The region from the header, or the region inferred from the filename as a fallback.
+    pub region: Region,
+    /// The problems that forced a fallback to filename inference, if any.
+    pub warnings: Vec<String>,
+}
+
+/// Options for [`analyze_rom_data_with_options`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnalysisOptions {
+    /// Fail on suspicious headers the analyzers otherwise only warn about: an unexpected Genesis
+    /// or Sega CD signature ([`RomAnalyzerError::InvalidSignature`]), a NES ROM without an iNES
+    /// header, and a SNES ROM without a valid checksum ([`RomAnalyzerError::ChecksumMismatch`]).
+    pub strict: bool,
+    /// The maximum number of bytes to read from a plain (non-archive) file, if any.
+    pub max_file_size: Option<usize>,
+    /// The console to analyze every file as, bypassing detection by extension and content. Files
+    /// inside archives are forced too. [`RomFileType::Unknown`] is rejected with
+    /// [`RomAnalyzerError::UnsupportedFormat`].
+    pub force_type: Option<RomFileType>,
+    /// Report files no analyzer recognizes as [`RomAnalysisResult::Unknown`] results instead of
+    /// failing, see [`analyze_rom_data_or_unknown`]. Ignored when a console is forced.
+    pub include_unknown: bool,
+    /// Flag SNES ROMs with the "Common / International" region code whose filename names a
+    /// single region as a region mismatch, see [`snes::analyze_snes_data_with_options`].
+    pub strict_world: bool,
 }
 
 /// Represents the type of ROM file based on its extension.
 /// This enum is used internally to dispatch to the correct analysis logic.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum RomFileType {
     Nes,
     Snes,
@@ -80,10 +227,186 @@ pub enum RomFileType {
     GameBoyAdvance,
     Genesis,
     SegaCD,
+    NeoGeo,
+    Intellivision,
+    Atari7800,
     CDSystem,
+    GameCube,
     Unknown,
 }
 
+impl RomFileType {
+    /// Returns the minimum number of bytes the analyzer of this type needs to read its header.
+    ///
+    /// Analyzers given less data fail with [`RomAnalyzerError::DataTooSmall`]. Headers without a
+    /// fixed location are sized for their first candidate location: the SNES LoROM header and the
+    /// Master System and Game Gear header at 0x1FF0 (Game Gear ROMs without one still fall back to
+    /// their filename). [`RomFileType::CDSystem`] is sized for PlayStation images, and
+    /// [`RomFileType::Unknown`] returns 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rom_analyzer::RomFileType;
+    ///
+    /// assert_eq!(RomFileType::Nes.min_header_size(), 16);
+    /// assert_eq!(RomFileType::GameBoy.min_header_size(), 0x150);
+    /// ```
+    pub fn min_header_size(&self) -> usize {
+        match self {
+            RomFileType::Nes => nes::INES_HEADER_SIZE,
+            RomFileType::Snes => snes::LOROM_HEADER_START + snes::SNES_HEADER_SIZE,
+            RomFileType::N64 => n64::HEADER_SIZE,
+            RomFileType::MasterSystem | RomFileType::GameGear => mastersystem::MIN_HEADER_ROM_SIZE,
+            RomFileType::GameBoy => gb::HEADER_SIZE,
+            RomFileType::GameBoyAdvance => gba::HEADER_SIZE,
+            RomFileType::Genesis => genesis::HEADER_SIZE,
+            RomFileType::SegaCD => segacd::REQUIRED_SIZE,
+            RomFileType::NeoGeo => neogeo::NEO_HEADER_SIZE,
+            RomFileType::Intellivision => intellivision::EXEC_HEADER_WORDS * 2,
+            RomFileType::Atari7800 => atari7800::A78_HEADER_SIZE,
+            RomFileType::CDSystem => psx::MIN_DATA_SIZE,
+            RomFileType::GameCube => gamecube::HEADER_SIZE,
+            RomFileType::Unknown => 0,
+        }
+    }
+}
+
+/// Describes a console (or family of CD-based systems) and the file extensions mapped to it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub struct ConsoleInfo {
+    /// The display name of the console.
+    pub name: &'static str,
+    /// The file extensions mapped to the console, lowercase and with a leading dot.
+    pub extensions: &'static [&'static str],
+}
+
+/// The console table backing both [`get_rom_file_type`] and [`supported_consoles`].
+const CONSOLES: &[(RomFileType, ConsoleInfo)] = &[
+    (
+        RomFileType::Nes,
+        ConsoleInfo {
+            name: "NES",
+            extensions: &[".nes"],
+        },
+    ),
+    (
+        RomFileType::Snes,
+        ConsoleInfo {
+            name: "SNES",
+            extensions: &[".smc", ".sfc"],
+        },
+    ),
+    (
+        RomFileType::N64,
+        ConsoleInfo {
+            name: "N64",
+            extensions: &[".n64", ".v64", ".z64"],
+        },
+    ),
+    (
+        RomFileType::MasterSystem,
+        ConsoleInfo {
+            name: "Sega Master System",
+            extensions: &[".sms"],
+        },
+    ),
+    (
+        RomFileType::GameGear,
+        ConsoleInfo {
+            name: "Sega Game Gear",
+            extensions: &[".gg"],
+        },
+    ),
+    (
+        RomFileType::GameBoy,
+        ConsoleInfo {
+            name: "Game Boy",
+            extensions: &[".gb", ".gbc"],
+        },
+    ),
+    (
+        RomFileType::GameBoyAdvance,
+        ConsoleInfo {
+            name: "Game Boy Advance",
+            extensions: &[".gba"],
+        },
+    ),
+    (
+        RomFileType::Genesis,
+        ConsoleInfo {
+            name: "Sega Genesis",
+            extensions: &[".md", ".gen", ".32x", ".smd"],
+        },
+    ),
+    (
+        RomFileType::SegaCD,
+        ConsoleInfo {
+            name: "Sega CD",
+            extensions: &[".scd"],
+        },
+    ),
+    (
+        RomFileType::NeoGeo,
+        ConsoleInfo {
+            name: "Neo Geo",
+            extensions: &[".neo"],
+        },
+    ),
+    (
+        RomFileType::Intellivision,
+        ConsoleInfo {
+            name: "Intellivision",
+            extensions: &[".int"],
+        },
+    ),
+    (
+        RomFileType::Atari7800,
+        ConsoleInfo {
+            name: "Atari 7800",
+            extensions: &[".a78"],
+        },
+    ),
+    (
+        RomFileType::CDSystem,
+        ConsoleInfo {
+            name: "CD Systems (PlayStation / PSP / Sega CD / 3DO)",
+            extensions: &[".iso", ".bin", ".img", ".psx", ".chd", ".cso"],
+        },
+    ),
+    (
+        RomFileType::GameCube,
+        ConsoleInfo {
+            name: "GameCube / Wii",
+            extensions: &[".rvz", ".wia"],
+        },
+    ),
+];
+
+/// Lists the consoles supported by the analyzer along with their file extensions.
+///
+/// This is intended for building help text or file-picker filters. The returned data is the
+/// same table [`get_rom_file_type`] dispatches on, so the two never disagree.
+///
+/// # Returns
+///
+/// A `Vec` of [`ConsoleInfo`], one per supported console.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::supported_consoles;
+///
+/// let snes = supported_consoles()
+///     .into_iter()
+///     .find(|console| console.name == "SNES")
+///     .unwrap();
+/// assert_eq!(snes.extensions, &[".smc", ".sfc"]);
+/// ```
+pub fn supported_consoles() -> Vec<ConsoleInfo> {
+    CONSOLES.iter().map(|(_, info)| *info).collect()
+}
+
 /// Extracts the file extension from a given file path and converts it to lowercase.
 ///
 /// # Arguments
@@ -122,9 +445,13 @@ fn get_file_extension_lowercase(file_path: &str) -> String {
 /// * [`RomFileType::GameGear`] for `gg`
 /// * [`RomFileType::GameBoy`] for `gb` or `gbc`
 /// * [`RomFileType::GameBoyAdvance`] for `gba`
-/// * [`RomFileType::Genesis`] for `md`, `gen`, or `32x`
+/// * [`RomFileType::Genesis`] for `md`, `gen`, `32x`, or `smd`
 /// * [`RomFileType::SegaCD`] for `scd`
-/// * [`RomFileType::CDSystem`] for `iso`, `bin`, `img`, `psx`, or `chd`
+/// * [`RomFileType::NeoGeo`] for `neo`
+/// * [`RomFileType::Intellivision`] for `int`
+/// * [`RomFileType::Atari7800`] for `a78`
+/// * [`RomFileType::CDSystem`] for `iso`, `bin`, `img`, `psx`, `chd`, or `cso`
+/// * [`RomFileType::GameCube`] for `rvz` or `wia`
 /// * [`RomFileType::Unknown`] for any other extension.
 ///
 /// # Examples
@@ -142,93 +469,147 @@ fn get_file_extension_lowercase(file_path: &str) -> String {
 /// assert_eq!(unknown, RomFileType::Unknown);
 /// ```
 pub fn get_rom_file_type(name: &str) -> RomFileType {
-    let ext = get_file_extension_lowercase(name);
+    let ext = format!(".{}", get_file_extension_lowercase(name));
+
+    CONSOLES
+        .iter()
+        .find(|(_, info)| info.extensions.contains(&ext.as_str()))
+        .map_or(RomFileType::Unknown, |(file_type, _)| *file_type)
+}
+
+/// Checks whether a path's **extension** is one the crate can analyze.
+///
+/// This covers every ROM extension from [`get_rom_file_type`], as well as ZIP archives, which are
+/// searched for a supported ROM, and CUE sheets, which are analyzed through their data track.
+/// It's a quick check for callers walking directories before they call [`analyze_rom_data`].
+/// Files without an extension return `false`, even though [`analyze_rom_data`] may still
+/// identify them by content.
+///
+/// # Arguments
+///
+/// * `path` - The file name or path to check.
+///
+/// # Returns
+///
+/// `true` if the extension is handled by the crate, `false` otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::is_rom_file;
+///
+/// assert!(is_rom_file("roms/zelda.NES"));
+/// assert!(is_rom_file("romset.zip"));
+/// assert!(is_rom_file("game.cue"));
+/// assert!(!is_rom_file("readme.txt"));
+/// ```
+pub fn is_rom_file(path: &str) -> bool {
+    get_rom_file_type(path) != RomFileType::Unknown
+        || matches!(get_file_extension_lowercase(path).as_str(), "zip" | "cue")
+}
+
+/// Identifies the console of raw ROM data from its content, regardless of file name.
+///
+/// Only headers carrying an unambiguous signature are recognized: the iNES signature, the
+/// big-endian N64 magic word, the Game Boy Advance Nintendo logo and the Sega Genesis and
+/// Sega CD signatures at 0x100. The first [`ROM_DETECTION_PREFIX_SIZE`] bytes are enough.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the start of the ROM data.
+///
+/// # Returns
+///
+/// The detected [`RomFileType`], or [`RomFileType::Unknown`] if no signature matches.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::{detect_rom_file_type, RomFileType};
+///
+/// assert_eq!(detect_rom_file_type(b"NES\x1a\x01\x01"), RomFileType::Nes);
+/// assert_eq!(detect_rom_file_type(b"plain text"), RomFileType::Unknown);
+/// ```
+pub fn detect_rom_file_type(data: &[u8]) -> RomFileType {
+    let sega_header = data.get(0x100..0x110).unwrap_or_default();
 
-    match ext.as_str() {
-        "nes" => RomFileType::Nes,
-        "smc" | "sfc" => RomFileType::Snes,
-        "n64" | "v64" | "z64" => RomFileType::N64,
-        "sms" => RomFileType::MasterSystem,
-        "gg" => RomFileType::GameGear,
-        "gb" | "gbc" => RomFileType::GameBoy,
-        "gba" => RomFileType::GameBoyAdvance,
-        "md" | "gen" | "32x" => RomFileType::Genesis,
-        "scd" => RomFileType::SegaCD,
-        "iso" | "bin" | "img" | "psx" | "chd" => RomFileType::CDSystem,
-        _ => RomFileType::Unknown,
+    if data.starts_with(nes::INES_SIGNATURE) {
+        RomFileType::Nes
+    } else if data.starts_with(N64_Z64_MAGIC) {
+        RomFileType::N64
+    } else if data.get(gba::GBA_LOGO_START..gba::GBA_LOGO_END) == Some(&gba::GBA_NINTENDO_LOGO) {
+        RomFileType::GameBoyAdvance
+    } else if genesis::has_genesis_signature(data) {
+        RomFileType::Genesis
+    } else if sega_header.starts_with(b"SEGA CD") {
+        RomFileType::SegaCD
+    } else if data.get(1..1 + atari7800::A78_SIGNATURE.len()) == Some(atari7800::A78_SIGNATURE) {
+        RomFileType::Atari7800
+    } else {
+        RomFileType::Unknown
     }
 }
 
 /// Processes raw ROM data based on its determined file type.
 ///
 /// This function takes the raw byte data of a ROM file and its path, determines
-/// the console type using [`get_rom_file_type`] and then dispatches the data to
-/// the appropriate console-specific analysis function.
+/// the console type using [`get_rom_file_type`] and then looks up the console-specific
+/// analysis function in the built-in [`registry`]. Files without an extension are
+/// identified by their content with [`detect_rom_file_type`] instead.
 ///
 /// # Arguments
 ///
-/// * `data` - A `Vec<u8>` containing the raw bytes of the ROM file.
+/// * `data` - A byte slice (`&[u8]`) containing the raw bytes of the ROM file.
 /// * `rom_path` - The path to the ROM file, used to infer the file type.
 ///
 /// # Returns
 ///
 /// A `Result` containing either a [`RomAnalysisResult`] with the analysis data
 /// or a [`RomAnalyzerError`].
-fn process_rom_data(data: Vec<u8>, rom_path: &str) -> Result<RomAnalysisResult, RomAnalyzerError> {
-    match get_rom_file_type(rom_path) {
-        RomFileType::Nes => nes::analyze_nes_data(&data, rom_path).map(RomAnalysisResult::NES),
-        RomFileType::Snes => snes::analyze_snes_data(&data, rom_path).map(RomAnalysisResult::SNES),
-        RomFileType::N64 => n64::analyze_n64_data(&data, rom_path).map(RomAnalysisResult::N64),
-        RomFileType::MasterSystem => mastersystem::analyze_mastersystem_data(&data, rom_path)
-            .map(RomAnalysisResult::MasterSystem),
-        RomFileType::GameGear => {
-            gamegear::analyze_gamegear_data(&data, rom_path).map(RomAnalysisResult::GameGear)
-        }
-        RomFileType::GameBoy => gb::analyze_gb_data(&data, rom_path).map(RomAnalysisResult::GB),
-        RomFileType::GameBoyAdvance => {
-            gba::analyze_gba_data(&data, rom_path).map(RomAnalysisResult::GBA)
-        }
-        RomFileType::Genesis => {
-            genesis::analyze_genesis_data(&data, rom_path).map(RomAnalysisResult::Genesis)
-        }
-        RomFileType::SegaCD => {
-            segacd::analyze_segacd_data(&data, rom_path).map(RomAnalysisResult::SegaCD)
-        }
-        RomFileType::CDSystem => {
-            // Some cartridge formats (like Sega Genesis) use the .bin extension, which
-            // conflicts with CD image formats. This checks for cartridge headers inside
-            // files that might otherwise be treated as CD images.
-            const SEGA_HEADER_START: usize = 0x100;
-            const SEGA_GENESIS_HEADER_END: usize = 0x110;
-            const SEGA_CD_SIGNATURE_END: usize = 0x107;
-            const SEGA_CD_MIN_LEN: usize = 0x10C; // To read region code at 0x10B
-
-            if data.len() >= SEGA_GENESIS_HEADER_END
-                && (data[SEGA_HEADER_START..SEGA_GENESIS_HEADER_END]
-                    .starts_with(SEGA_MEGA_DRIVE_SIG)
-                    || data[SEGA_HEADER_START..SEGA_GENESIS_HEADER_END]
-                        .starts_with(SEGA_GENESIS_SIG))
-            {
-                genesis::analyze_genesis_data(&data, rom_path).map(RomAnalysisResult::Genesis)
-            } else if data.len() >= SEGA_CD_MIN_LEN
-                && data[SEGA_HEADER_START..SEGA_CD_SIGNATURE_END].eq_ignore_ascii_case(b"SEGA CD")
-            {
-                segacd::analyze_segacd_data(&data, rom_path).map(RomAnalysisResult::SegaCD)
-            } else {
-                psx::analyze_psx_data(&data, rom_path).map(RomAnalysisResult::PSX)
-            }
+fn process_rom_data(data: &[u8], rom_path: &str) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    process_rom_data_with_options(data, rom_path, &AnalysisOptions::default())
+}
+
+/// Processes raw ROM data like [`process_rom_data`], with the strictness of `options`.
+fn process_rom_data_with_options(
+    data: &[u8],
+    rom_path: &str,
+    options: &AnalysisOptions,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    let file_type = match options.force_type {
+        Some(RomFileType::Unknown) => {
+            return Err(RomAnalyzerError::UnsupportedFormat(
+                "Cannot force analysis as an unknown console.".to_string(),
+            ));
         }
-        RomFileType::Unknown => Err(RomAnalyzerError::UnsupportedFormat(format!(
+        Some(file_type) => file_type,
+        None => dispatch_file_type(data, rom_path),
+    };
+
+    let analyzer = registry::builtin_analyzer(file_type).ok_or_else(|| {
+        RomAnalyzerError::UnsupportedFormat(format!(
             "Unrecognized ROM file extension for dispatch: {}",
             rom_path
-        ))),
+        ))
+    })?;
+    analyzer(data, rom_path, options)
+}
+
+/// Returns the [`RomFileType`] to dispatch `data` to, from the extension of `rom_path`.
+///
+/// Files without an extension can't be dispatched by name, so they fall back to their content.
+pub(crate) fn dispatch_file_type(data: &[u8], rom_path: &str) -> RomFileType {
+    if get_file_extension_lowercase(rom_path).is_empty() {
+        detect_rom_file_type(data)
+    } else {
+        get_rom_file_type(rom_path)
     }
 }
 
 /// Analyze the header data of a ROM file.
 ///
 /// This is the primary public function for analyzing ROM files. It handles different
-/// file types (including archives like ZIP and CHD) by first processing them to
+/// file types (including archives like ZIP, CHD and CSO) by first processing them to
 /// extract the ROM data, and then dispatches the data to `process_rom_data` for
 /// console-specific analysis.
 ///
@@ -253,135 +634,1136 @@ fn process_rom_data(data: Vec<u8>, rom_path: &str) -> Result<RomAnalysisResult,
 /// }
 /// ```
 pub fn analyze_rom_data(file_path: &str) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    analyze_rom_data_with_options(file_path, &AnalysisOptions::default())
+}
+
+/// Analyze the header data of a ROM file, reading at most `max_file_size` bytes of a plain file.
+///
+/// This behaves like [`analyze_rom_data`], which already only reads the part of a plain
+/// (non-archive) file its console's analyzer needs, such as the first 128KB of a CD image. The
+/// `max_file_size` lowers that limit further, and a warning is logged when it truncates a file.
+/// Archives are unaffected, as they already cap what they extract.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the ROM file or archive.
+/// * `max_file_size` - The maximum number of bytes to read from a plain file, if any.
+///
+/// # Returns
+///
+/// A `Result` containing either a [`RomAnalysisResult`] with the analysis data
+/// or a [`RomAnalyzerError`].
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::analyze_rom_data_with_max_size;
+///
+/// // Read no more than 16MB of the file.
+/// let result = analyze_rom_data_with_max_size("path/to/your/rom.n64", Some(16 * 1024 * 1024));
+/// assert!(result.is_err());
+/// ```
+pub fn analyze_rom_data_with_max_size(
+    file_path: &str,
+    max_file_size: Option<usize>,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    analyze_rom_data_with_options(
+        file_path,
+        &AnalysisOptions {
+            max_file_size,
+            ..AnalysisOptions::default()
+        },
+    )
+}
+
+/// Analyze the header data of a ROM file with the given [`AnalysisOptions`].
+///
+/// This behaves like [`analyze_rom_data_with_max_size`], reading at most
+/// [`AnalysisOptions::max_file_size`] bytes of a plain file. With [`AnalysisOptions::strict`]
+/// set, headers the analyzers would otherwise accept with a warning fail the analysis instead.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the ROM file or archive.
+/// * `options` - The [`AnalysisOptions`] to analyze the ROM with.
+///
+/// # Returns
+///
+/// A `Result` containing either a [`RomAnalysisResult`] with the analysis data
+/// or a [`RomAnalyzerError`].
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::{AnalysisOptions, analyze_rom_data_with_options};
+///
+/// let options = AnalysisOptions {
+///     strict: true,
+///     ..AnalysisOptions::default()
+/// };
+/// match analyze_rom_data_with_options("path/to/your/rom.md", &options) {
+///     Ok(analysis) => println!("{}", analysis.print()),
+///     Err(e) => eprintln!("Rejected: {}", e),
+/// }
+/// ```
+pub fn analyze_rom_data_with_options(
+    file_path: &str,
+    options: &AnalysisOptions,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    match analyze_rom_file(file_path, options) {
+        Err(e)
+            if options.include_unknown
+                && options.force_type.is_none()
+                && e.kind() == RomAnalyzerErrorKind::UnsupportedFormat =>
+        {
+            let size = std::fs::metadata(file_path)?.len();
+            Ok(RomAnalysisResult::Unknown(unknown::analyze_unknown_file(
+                file_path, size,
+            )))
+        }
+        result => result,
+    }
+}
+
+/// Analyzes a ROM file or archive for [`analyze_rom_data_with_options`], without the
+/// [`AnalysisOptions::include_unknown`] fallback.
+fn analyze_rom_file(
+    file_path: &str,
+    options: &AnalysisOptions,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
     match get_file_extension_lowercase(file_path).as_str() {
         "zip" => {
             let file = File::open(file_path)?;
-            let (data, rom_file_name) = process_zip_file(file, file_path)?;
-            process_rom_data(data, &rom_file_name)
+            let rom = extract_zip_rom(file, file_path)?;
+            process_rom_data_with_options(&rom.data, &rom.name, options).map(|analysis| {
+                analysis
+                    .with_rom_size(rom.size as usize)
+                    .with_archive_source(file_path)
+            })
         }
         "chd" => {
             let decompressed_chd = analyze_chd_file(Path::new(file_path))?;
-            process_rom_data(decompressed_chd, file_path)
+            process_rom_data_with_options(&decompressed_chd, file_path, options)
+                .map(|analysis| analysis.with_archive_source(file_path))
         }
-        _ => {
-            let data = fs::read(file_path)?;
-            process_rom_data(data, file_path)
-        }
-    }
-}
-
-macro_rules! impl_rom_analysis_method {
-    ($fn_name:ident, $return_type:ty) => {
-        /// Calls the `$fn_name` method on the inner console-specific analysis struct.
-        /// This allows a common interface for accessing console-specific data.
-        pub fn $fn_name(&self) -> $return_type {
-            match self {
-                RomAnalysisResult::GameGear(a) => a.$fn_name(),
-                RomAnalysisResult::GB(a) => a.$fn_name(),
-                RomAnalysisResult::GBA(a) => a.$fn_name(),
-                RomAnalysisResult::Genesis(a) => a.$fn_name(),
-                RomAnalysisResult::MasterSystem(a) => a.$fn_name(),
-                RomAnalysisResult::N64(a) => a.$fn_name(),
-                RomAnalysisResult::NES(a) => a.$fn_name(),
-                RomAnalysisResult::PSX(a) => a.$fn_name(),
-                RomAnalysisResult::SegaCD(a) => a.$fn_name(),
-                RomAnalysisResult::SNES(a) => a.$fn_name(),
-            }
+        "cso" => {
+            let decompressed_cso = analyze_cso_file(Path::new(file_path))?;
+            process_rom_data_with_options(&decompressed_cso, file_path, options)
+                .map(|analysis| analysis.with_archive_source(file_path))
         }
-    };
+        "rvz" | "wia" => {
+            let disc_header = analyze_rvz_file(Path::new(file_path))?;
+            process_rom_data_with_options(&disc_header, file_path, options)
+                .map(|analysis| analysis.with_archive_source(file_path))
+        }
+        "cue" => {
+            let data_track = cue_data_track(Path::new(file_path))?;
+            let data_track = data_track.to_str().ok_or_else(|| {
+                RomAnalyzerError::ArchiveError("Track file path is not valid UTF-8.".to_string())
+            })?;
+            analyze_plain_file(data_track, options)
+                .map(|analysis| analysis.with_archive_source(file_path))
+        }
+        _ => analyze_plain_file(file_path, options),
+    }
 }
 
-macro_rules! impl_rom_analysis_accessor {
-    ($fn_name:ident, $field:ident, &$return_type:ty) => {
-        /// Provides read-only access to the `$field` field of the inner console-specific analysis struct.
-        pub fn $fn_name(&self) -> &$return_type {
-            match self {
-                RomAnalysisResult::GameGear(a) => &a.$field,
-                RomAnalysisResult::GB(a) => &a.$field,
-                RomAnalysisResult::GBA(a) => &a.$field,
-                RomAnalysisResult::Genesis(a) => &a.$field,
-                RomAnalysisResult::MasterSystem(a) => &a.$field,
-                RomAnalysisResult::N64(a) => &a.$field,
-                RomAnalysisResult::NES(a) => &a.$field,
-                RomAnalysisResult::PSX(a) => &a.$field,
-                RomAnalysisResult::SegaCD(a) => &a.$field,
-                RomAnalysisResult::SNES(a) => &a.$field,
-            }
+/// Analyze raw ROM data with the analyzer of a given console.
+///
+/// Detection by extension and content is bypassed, which helps with ambiguous extensions like
+/// `.bin` (Genesis cartridges, CD tracks and raw dumps alike) or misnamed files. The data is
+/// analyzed as-is, so archives have to be extracted by the caller.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+/// * `rom_path` - The name of the ROM file, used for region mismatch checks.
+/// * `file_type` - The console to analyze the data as.
+///
+/// # Returns
+///
+/// A `Result` containing either a [`RomAnalysisResult`] with the analysis data
+/// or a [`RomAnalyzerError`]. Forcing [`RomFileType::Unknown`] fails with
+/// [`RomAnalyzerError::UnsupportedFormat`].
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::{RomFileType, analyze_rom_bytes_as};
+///
+/// let mut data = b"NES\x1a\x01\x01\x00\x00".to_vec();
+/// data.resize(0x6010, 0);
+/// let analysis = analyze_rom_bytes_as(&data, "game (USA).bin", RomFileType::Nes).unwrap();
+/// assert_eq!(analysis.console_name(), "NES");
+/// assert!(analyze_rom_bytes_as(&data, "game.bin", RomFileType::Unknown).is_err());
+/// ```
+pub fn analyze_rom_bytes_as(
+    data: &[u8],
+    rom_path: &str,
+    file_type: RomFileType,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    process_rom_data_with_options(
+        data,
+        rom_path,
+        &AnalysisOptions {
+            force_type: Some(file_type),
+            ..AnalysisOptions::default()
+        },
+    )
+}
+
+/// Analyze the header data of a ROM file and compute the CRC32 and SHA1 of the whole file.
+///
+/// Plain files are analyzed and hashed in a single pass, so large CD images are only read once.
+/// Archives are extracted in memory for analysis rather than streamed, so they are hashed
+/// separately. As with [`hash::hash_file`], the file is hashed as-is, including any archive
+/// container or copier header.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the ROM file or archive.
+/// * `options` - The [`AnalysisOptions`] to analyze the ROM with.
+///
+/// # Returns
+///
+/// A `Result` containing either the [`RomAnalysisResult`] and the [`Hashes`] of the file, or a
+/// [`RomAnalyzerError`].
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::{AnalysisOptions, analyze_rom_data_with_hashes};
+///
+/// match analyze_rom_data_with_hashes("path/to/your/game.iso", &AnalysisOptions::default()) {
+///     Ok((analysis, hashes)) => println!("{} {}", analysis.source_name(), hashes),
+///     Err(e) => eprintln!("Error analyzing ROM: {}", e),
+/// }
+/// ```
+pub fn analyze_rom_data_with_hashes(
+    file_path: &str,
+    options: &AnalysisOptions,
+) -> Result<(RomAnalysisResult, Hashes), RomAnalyzerError> {
+    match get_file_extension_lowercase(file_path).as_str() {
+        "zip" | "chd" | "cso" | "rvz" | "wia" | "cue" => {
+            let analysis = analyze_rom_data_with_options(file_path, options)?;
+            Ok((analysis, hash_file(Path::new(file_path))?))
         }
-    };
-    ($fn_name:ident, $field:ident, $return_type:ty) => {
-        /// Provides access to the `$field` field of the inner console-specific analysis struct.
-        pub fn $fn_name(&self) -> $return_type {
-            match self {
-                RomAnalysisResult::GameGear(a) => a.$field,
-                RomAnalysisResult::GB(a) => a.$field,
-                RomAnalysisResult::GBA(a) => a.$field,
-                RomAnalysisResult::Genesis(a) => a.$field,
-                RomAnalysisResult::MasterSystem(a) => a.$field,
-                RomAnalysisResult::N64(a) => a.$field,
-                RomAnalysisResult::NES(a) => a.$field,
-                RomAnalysisResult::PSX(a) => a.$field,
-                RomAnalysisResult::SegaCD(a) => a.$field,
-                RomAnalysisResult::SNES(a) => a.$field,
-            }
+        _ => {
+            let (analysis, hashes) = analyze_plain_file_with_hashes(file_path, options, true)?;
+            let hashes = hashes.ok_or_else(|| RomAnalyzerError::new("File was not hashed."))?;
+            Ok((analysis, hashes))
         }
-    };
+    }
 }
 
-impl RomAnalysisResult {
-    impl_rom_analysis_method!(print, String);
-    impl_rom_analysis_accessor!(source_name, source_name, &str);
-    impl_rom_analysis_accessor!(region, region_string, &str);
-    impl_rom_analysis_accessor!(region_mismatch, region_mismatch, bool);
+/// Analyze the header data of a ROM file, reporting unrecognized files instead of failing.
+///
+/// This behaves like [`analyze_rom_data_with_max_size`], except that a file no analyzer
+/// recognizes (an [`RomAnalyzerError::UnsupportedFormat`] error) produces a
+/// [`RomAnalysisResult::Unknown`] result holding its size and the region inferred from its
+/// filename. This suits batch runs over mixed directories, where every file should still appear
+/// in the output. It is a shorthand for [`AnalysisOptions::include_unknown`].
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the ROM file or archive.
+/// * `max_file_size` - The maximum number of bytes to read from a plain file, if any.
+///
+/// # Returns
+///
+/// A `Result` containing either a [`RomAnalysisResult`] with the analysis data
+/// or a [`RomAnalyzerError`] for problems other than an unrecognized format.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::analyze_rom_data_or_unknown;
+///
+/// match analyze_rom_data_or_unknown("path/to/your/readme.txt", None) {
+///     Ok(analysis) => println!("{} ({})", analysis.source_name(), analysis.console_name()),
+///     Err(e) => eprintln!("Error analyzing file: {}", e),
+/// }
+/// ```
+pub fn analyze_rom_data_or_unknown(
+    file_path: &str,
+    max_file_size: Option<usize>,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    analyze_rom_data_with_options(
+        file_path,
+        &AnalysisOptions {
+            max_file_size,
+            include_unknown: true,
+            ..AnalysisOptions::default()
+        },
+    )
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Write;
-    use tempfile::tempdir;
-    use zip::write::{FileOptions, ZipWriter};
+/// Analyze the header data of a ROM file with the given [`AnalysisOptions`], attaching the path
+/// to any error.
+///
+/// This is the per-file step of [`analyze_paths`]: a missing file is reported as
+/// [`RomAnalyzerError::FileNotFound`], and any other error is wrapped in
+/// [`RomAnalyzerError::WithPath`] (see [`RomAnalyzerError::with_path`]).
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the ROM file or archive.
+/// * `options` - The [`AnalysisOptions`] to analyze the ROM with.
+///
+/// # Returns
+///
+/// A `Result` containing either a [`RomAnalysisResult`] with the analysis data
+/// or a [`RomAnalyzerError`] carrying `file_path`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::error::RomAnalyzerError;
+/// use rom_analyzer::{AnalysisOptions, analyze_path};
+///
+/// let result = analyze_path("path/to/missing.nes", &AnalysisOptions::default());
+/// assert!(matches!(result, Err(RomAnalyzerError::FileNotFound(_))));
+/// ```
+pub fn analyze_path(
+    file_path: &str,
+    options: &AnalysisOptions,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    analyze_rom_data_with_options(file_path, options).map_err(|e| e.with_path(file_path))
+}
 
-    const TEST_SEGA_MEGA_DRIVE_HEADER: &[u8] = b"SEGA MEGA DRIVE "; // Padded to 16 bytes
-    const TEST_SEGA_GENESIS_HEADER: &[u8] = b"SEGA GENESIS    ";
+/// Analyze the header data of several ROM files in parallel.
+///
+/// Each path is analyzed with [`analyze_path`] and the default [`AnalysisOptions`] on the rayon
+/// thread pool, and the results are returned in the same order as `paths`. Errors carry the path
+/// they were produced for: a missing file is reported as [`RomAnalyzerError::FileNotFound`], and
+/// any other error is wrapped in [`RomAnalyzerError::WithPath`]. One file failing doesn't stop
+/// the others from being analyzed.
+///
+/// # Arguments
+///
+/// * `paths` - The paths of the ROM files or archives to analyze.
+///
+/// # Returns
+///
+/// A `Vec` holding the [`RomAnalysisResult`] or [`RomAnalyzerError`] of each path.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::analyze_paths;
+///
+/// let paths = vec!["game.nes".to_string(), "game.sfc".to_string()];
+/// for result in analyze_paths(&paths) {
+///     match result {
+///         Ok(analysis) => println!("{}", analysis.print()),
+///         Err(e) => eprintln!("{}", e),
+///     }
+/// }
+/// ```
+pub fn analyze_paths(paths: &[String]) -> Vec<Result<RomAnalysisResult, RomAnalyzerError>> {
+    paths
+        .par_iter()
+        .map(|path| analyze_path(path, &AnalysisOptions::default()))
+        .collect()
+}
 
-    #[test]
-    fn test_get_rom_file_type() {
-        assert_eq!(get_rom_file_type("game.nes"), RomFileType::Nes);
-        assert_eq!(get_rom_file_type("game.smc"), RomFileType::Snes);
-        assert_eq!(get_rom_file_type("game.sfc"), RomFileType::Snes);
-        assert_eq!(get_rom_file_type("game.n64"), RomFileType::N64);
-        assert_eq!(get_rom_file_type("game.v64"), RomFileType::N64);
-        assert_eq!(get_rom_file_type("game.z64"), RomFileType::N64);
-        assert_eq!(get_rom_file_type("game.sms"), RomFileType::MasterSystem);
-        assert_eq!(get_rom_file_type("game.gg"), RomFileType::GameGear);
-        assert_eq!(get_rom_file_type("game.gb"), RomFileType::GameBoy);
-        assert_eq!(get_rom_file_type("game.gbc"), RomFileType::GameBoy);
-        assert_eq!(get_rom_file_type("game.gba"), RomFileType::GameBoyAdvance);
-        assert_eq!(get_rom_file_type("game.md"), RomFileType::Genesis);
-        assert_eq!(get_rom_file_type("game.gen"), RomFileType::Genesis);
-        assert_eq!(get_rom_file_type("game.32x"), RomFileType::Genesis);
-        assert_eq!(get_rom_file_type("game.scd"), RomFileType::SegaCD);
-        assert_eq!(get_rom_file_type("game.iso"), RomFileType::CDSystem);
-        assert_eq!(get_rom_file_type("game.bin"), RomFileType::CDSystem);
-        assert_eq!(get_rom_file_type("game.img"), RomFileType::CDSystem);
-        assert_eq!(get_rom_file_type("game.psx"), RomFileType::CDSystem);
-        assert_eq!(get_rom_file_type("game.chd"), RomFileType::CDSystem);
-        assert_eq!(get_rom_file_type("game.zip"), RomFileType::Unknown);
-        assert_eq!(get_rom_file_type("game.txt"), RomFileType::Unknown);
+/// Analyze the header data of every ROM in a file.
+///
+/// ZIP archives are expanded with [`archive::zip::process_zip_file_all`] and each supported ROM
+/// inside is analyzed separately, in archive order. Any other file is analyzed as a single ROM
+/// exactly as [`analyze_rom_data`] would, producing a one-element vector.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the ROM file or archive.
+///
+/// # Returns
+///
+/// A `Result` containing either a `Vec` of [`RomAnalysisResult`] with one entry per ROM, or the
+/// first [`RomAnalyzerError`] encountered.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::analyze_rom_data_multi;
+///
+/// match analyze_rom_data_multi("path/to/your/romset.zip") {
+///     Ok(analyses) => println!("Analyzed {} ROMs", analyses.len()),
+///     Err(e) => eprintln!("Error analyzing ROMs: {}", e),
+/// }
+/// ```
+pub fn analyze_rom_data_multi(file_path: &str) -> Result<Vec<RomAnalysisResult>, RomAnalyzerError> {
+    analyze_rom_data_multi_with_options(file_path, &AnalysisOptions::default())
+}
+
+/// Analyze the header data of every ROM in a file with the given [`AnalysisOptions`].
+///
+/// This behaves like [`analyze_rom_data_multi`], with every ROM in a ZIP archive analyzed as
+/// [`analyze_rom_data_with_options`] would analyze a single one. Any other file is analyzed by
+/// [`analyze_rom_data_with_options`], producing a one-element vector.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the ROM file or archive.
+/// * `options` - The [`AnalysisOptions`] to analyze each ROM with.
+///
+/// # Returns
+///
+/// A `Result` containing either a `Vec` of [`RomAnalysisResult`] with one entry per ROM, or the
+/// first [`RomAnalyzerError`] encountered.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::{AnalysisOptions, analyze_rom_data_multi_with_options};
+///
+/// let options = AnalysisOptions {
+///     strict: true,
+///     ..AnalysisOptions::default()
+/// };
+/// match analyze_rom_data_multi_with_options("path/to/your/romset.zip", &options) {
+///     Ok(analyses) => println!("Analyzed {} ROMs", analyses.len()),
+///     Err(e) => eprintln!("Rejected: {}", e),
+/// }
+/// ```
+pub fn analyze_rom_data_multi_with_options(
+    file_path: &str,
+    options: &AnalysisOptions,
+) -> Result<Vec<RomAnalysisResult>, RomAnalyzerError> {
+    match get_file_extension_lowercase(file_path).as_str() {
+        "zip" => {
+            let file = File::open(file_path)?;
+            extract_zip_roms_all(file, file_path)?
+                .into_iter()
+                .map(|rom| {
+                    let analysis =
+                        match process_rom_data_with_options(&rom.data, &rom.name, options) {
+                            Err(e)
+                                if options.include_unknown
+                                    && options.force_type.is_none()
+                                    && e.kind() == RomAnalyzerErrorKind::UnsupportedFormat =>
+                            {
+                                RomAnalysisResult::Unknown(unknown::analyze_unknown_file(
+                                    &rom.name, rom.size,
+                                ))
+                            }
+                            result => result?,
+                        };
+                    Ok(analysis
+                        .with_rom_size(rom.size as usize)
+                        .with_archive_source(file_path))
+                })
+                .collect()
+        }
+        _ => analyze_rom_data_with_options(file_path, options).map(|analysis| vec![analysis]),
+    }
+}
+
+/// Analyze the header data of a ROM file, falling back to its filename when the header is bad.
+///
+/// This behaves like [`analyze_rom_data`], except that header problems (data too small for the
+/// header, or an invalid header or signature) don't fail the analysis. Instead the region is
+/// inferred from the filename with [`infer_region_from_filename`] and the error is recorded in
+/// [`LenientAnalysis::warnings`]. This suits batch processing, where best-effort metadata is
+/// preferable to none.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the ROM file.
+///
+/// # Returns
+///
+/// A `Result` containing either a [`LenientAnalysis`], or a [`RomAnalyzerError`] for problems
+/// other than an unreadable header (such as a missing file or an unsupported extension).
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::analyze_rom_data_lenient;
+///
+/// match analyze_rom_data_lenient("path/to/your/rom (USA).sfc") {
+///     Ok(lenient) => println!("Region: {} ({} warnings)", lenient.region, lenient.warnings.len()),
+///     Err(e) => eprintln!("Error analyzing ROM: {}", e),
+/// }
+/// ```
+pub fn analyze_rom_data_lenient(file_path: &str) -> Result<LenientAnalysis, RomAnalyzerError> {
+    match analyze_rom_data(file_path) {
+        Ok(analysis) => Ok(LenientAnalysis {
+            source_name: file_path.to_string(),
+            region: analysis.region_bitmask(),
+            analysis: Some(analysis),
+            warnings: Vec::new(),
+        }),
+        Err(e)
+            if matches!(
+                e.kind(),
+                RomAnalyzerErrorKind::DataTooSmall
+                    | RomAnalyzerErrorKind::InvalidHeader
+                    | RomAnalyzerErrorKind::InvalidSignature
+            ) =>
+        {
+            Ok(LenientAnalysis {
+                source_name: file_path.to_string(),
+                analysis: None,
+                region: infer_region_from_filename(file_path),
+                warnings: vec![e.to_string()],
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Returns the number of leading bytes the analyzer for a plain file of the given type needs.
+///
+/// Most cartridge analyzers only look at their header, and CD analyzers at the start of the
+/// image. NES, SNES and Master System analysis use the whole ROM (to recognize headerless dumps
+/// by their size, to deinterleave it and to validate its checksum), Intellivision analysis
+/// follows a title pointer that can lead anywhere in the ROM, and content detection for files
+/// without a known extension needs the whole file, so those return `None`.
+fn analysis_read_limit(file_type: RomFileType) -> Option<usize> {
+    match file_type {
+        // The header CRC covers the first 1MB after the 4KB header and boot code.
+        RomFileType::N64 => Some(0x101000),
+        RomFileType::GameBoy => Some(0x150),
+        RomFileType::GameBoyAdvance => Some(0xC0),
+        // SMD dumps need the first 16KB block after their copier header to be deinterleaved.
+        RomFileType::Genesis => Some(genesis::SMD_HEADER_SIZE + genesis::SMD_BLOCK_SIZE),
+        RomFileType::NeoGeo => Some(0x1000),
+        RomFileType::Atari7800 => Some(atari7800::A78_HEADER_SIZE),
+        // The header can sit as late as 0x7ff0.
+        RomFileType::GameGear => Some(0x8000),
+        RomFileType::SegaCD | RomFileType::CDSystem => Some(psx::DEFAULT_SCAN_WINDOW),
+        RomFileType::GameCube => Some(gamecube::HEADER_SIZE),
+        RomFileType::Nes
+        | RomFileType::Snes
+        | RomFileType::MasterSystem
+        | RomFileType::Intellivision
+        | RomFileType::Unknown => None,
+    }
+}
+
+/// Reads a plain (non-archive) ROM file from disk and analyzes it.
+///
+/// Only the first [`analysis_read_limit`] bytes are read, further capped by
+/// [`AnalysisOptions::max_file_size`].
+/// With the `mmap` feature enabled the file is memory-mapped and the mapping is handed straight
+/// to the analyzers, which avoids copying large N64 and CD images into memory. If the feature is
+/// disabled or the file cannot be mapped (e.g. it is empty), the file is read instead.
+fn analyze_plain_file(
+    file_path: &str,
+    options: &AnalysisOptions,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    analyze_plain_file_with_hashes(file_path, options, false).map(|(analysis, _)| analysis)
+}
+
+/// Analyzes a plain ROM file like [`analyze_plain_file`], also hashing the whole file when
+/// `hash` is set.
+///
+/// The file is hashed in the same pass that reads it for analysis: the analyzed prefix is read
+/// through a [`HashingReader`], which then hashes the rest of the file.
+fn analyze_plain_file_with_hashes(
+    file_path: &str,
+    options: &AnalysisOptions,
+    hash: bool,
+) -> Result<(RomAnalysisResult, Option<Hashes>), RomAnalyzerError> {
+    let max_file_size = options.max_file_size;
+    let file = File::open(file_path)?;
+    let file_size = file.metadata()?.len();
+    let type_limit = analysis_read_limit(
+        options
+            .force_type
+            .unwrap_or_else(|| get_rom_file_type(file_path)),
+    );
+    let limit = match (type_limit, max_file_size) {
+        (Some(type_limit), Some(max_file_size)) => Some(type_limit.min(max_file_size)),
+        (type_limit, max_file_size) => type_limit.or(max_file_size),
+    };
+
+    if let Some(max_file_size) = max_file_size
+        && file_size > max_file_size as u64
+        && type_limit.is_none_or(|type_limit| type_limit > max_file_size)
+    {
+        warn!(
+            "{} is larger than the maximum file size, only its first {} bytes are analyzed.",
+            file_path, max_file_size
+        );
+    } else if let Some(limit) = limit
+        && file_size > limit as u64
+    {
+        debug!(
+            "[+] Reading the first {} bytes of {} for header analysis.",
+            limit, file_path
+        );
+    }
+
+    #[cfg(feature = "mmap")]
+    if let Some(mapped) = map_file(&file) {
+        let mut end = limit.map_or(mapped.len(), |limit| limit.min(mapped.len()));
+        if let Some(sfo_end) = psp_param_sfo_read_end(&mapped[..end], max_file_size) {
+            end = sfo_end.min(mapped.len());
+        }
+        let analysis = process_rom_data_with_options(&mapped[..end], file_path, options)?
+            .with_rom_size(mapped.len());
+        let hashes = hash.then(|| hash::hash_reader(&mapped[..])).transpose()?;
+        return Ok((analysis, hashes));
+    }
+
+    let (data, hashes) = if hash {
+        let mut reader = HashingReader::new(&file);
+        let data = read_analysis_data(&mut reader, limit, max_file_size)?;
+        (data, Some(reader.finish()?))
+    } else {
+        (read_analysis_data(&file, limit, max_file_size)?, None)
+    };
+    let analysis =
+        process_rom_data_with_options(&data, file_path, options)?.with_rom_size(file_size as usize);
+    Ok((analysis, hashes))
+}
+
+/// Reads the leading bytes of a plain ROM file that its analyzer needs.
+///
+/// At most `limit` bytes are read, unless a PSP image's PARAM.SFO lies further in (see
+/// [`psp_param_sfo_read_end`]).
+fn read_analysis_data<R: Read>(
+    mut reader: R,
+    limit: Option<usize>,
+    max_file_size: Option<usize>,
+) -> std::io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    match limit {
+        Some(limit) => (&mut reader).take(limit as u64).read_to_end(&mut data)?,
+        None => reader.read_to_end(&mut data)?,
+    };
+    if let Some(sfo_end) = psp_param_sfo_read_end(&data, max_file_size) {
+        reader
+            .take((sfo_end - data.len()) as u64)
+            .read_to_end(&mut data)?;
+    }
+    Ok(data)
+}
+
+/// Returns how many leading bytes of a PSP image must be read to cover its PARAM.SFO.
+///
+/// The start of a disc image holds the PSP filesystem's directories, but the PARAM.SFO itself
+/// is usually further in than the CD scan window. Returns `None` if `data` isn't a PSP image or
+/// already covers the PARAM.SFO, or if reaching it would read more than [`MAX_PSP_PARAM_SFO_END`]
+/// or `max_file_size` bytes.
+fn psp_param_sfo_read_end(data: &[u8], max_file_size: Option<usize>) -> Option<usize> {
+    psp::param_sfo_end(data).filter(|&end| {
+        end > data.len()
+            && end <= MAX_PSP_PARAM_SFO_END
+            && max_file_size.is_none_or(|max_file_size| end <= max_file_size)
+    })
+}
+
+/// Memory-maps a file read-only, returning `None` if it cannot be mapped.
+#[cfg(feature = "mmap")]
+fn map_file(file: &File) -> Option<memmap2::Mmap> {
+    // SAFETY: The mapping is read-only and only lives for the duration of the analysis. As with
+    // any memory-mapped file, another process truncating it while it is mapped is not supported.
+    unsafe { memmap2::Mmap::map(file) }.ok()
+}
+
+impl RomAnalysisResult {
+    /// Returns the inner console-specific analysis as a [`RomMetadata`] trait object.
+    ///
+    /// This gives access to the fields every console shares without matching on the enum.
+    pub fn metadata(&self) -> &dyn RomMetadata {
+        match self {
+            RomAnalysisResult::Atari7800(a) => a,
+            RomAnalysisResult::CdImage(a) => a,
+            RomAnalysisResult::GameCube(a) => a,
+            RomAnalysisResult::GameGear(a) => a,
+            RomAnalysisResult::GB(a) => a,
+            RomAnalysisResult::GBA(a) => a,
+            RomAnalysisResult::Genesis(a) => a,
+            RomAnalysisResult::Intellivision(a) => a,
+            RomAnalysisResult::MasterSystem(a) => a,
+            RomAnalysisResult::N64(a) => a,
+            RomAnalysisResult::NeoGeo(a) => a,
+            RomAnalysisResult::NES(a) => a,
+            RomAnalysisResult::PSP(a) => a,
+            RomAnalysisResult::PSX(a) => a,
+            RomAnalysisResult::SegaCD(a) => a,
+            RomAnalysisResult::SNES(a) => a,
+            RomAnalysisResult::ThreeDO(a) => a,
+            RomAnalysisResult::Unknown(a) => a,
+        }
+    }
+
+    /// Returns a printable String of the analysis results.
+    pub fn print(&self) -> String {
+        self.metadata().print()
+    }
+
+    /// Returns the analysis results as ordered label/value pairs, see
+    /// [`RomMetadata::to_fields`].
+    pub fn to_fields(&self) -> Vec<(String, String)> {
+        self.metadata().to_fields()
+    }
+
+    /// Returns the name of the source file.
+    pub fn source_name(&self) -> &str {
+        self.metadata().source_name()
+    }
+
+    /// Returns the region name as written in the ROM header (e.g. "Japan (NTSC)").
+    pub fn region(&self) -> &str {
+        self.metadata().region_string()
+    }
+
+    /// Returns the region(s) from the ROM header as a [`Region`] bitmask.
+    ///
+    /// Unlike [`RomAnalysisResult::region`], this is suitable for filtering and set operations.
+    pub fn region_bitmask(&self) -> Region {
+        self.metadata().region()
+    }
+
+    /// Returns the video timing (NTSC or PAL) the ROM targets, for sorting libraries by it.
+    ///
+    /// NES, SNES, Genesis, 3DO and Atari 7800 analyses record it from their header or region, and
+    /// PSX discs derive it from their executable prefix. Other consoles don't encode it and
+    /// return [`VideoSystem::Unknown`].
+    pub fn video_standard(&self) -> VideoSystem {
+        match self {
+            RomAnalysisResult::Atari7800(a) => a.video_system,
+            RomAnalysisResult::Genesis(a) => a.video_system,
+            RomAnalysisResult::NES(a) => a.video_system,
+            RomAnalysisResult::PSX(a) => psx::map_video_system(&a.code),
+            RomAnalysisResult::SNES(a) => a.video_system,
+            RomAnalysisResult::ThreeDO(a) => a.video_system,
+            RomAnalysisResult::CdImage(_)
+            | RomAnalysisResult::GameCube(_)
+            | RomAnalysisResult::GameGear(_)
+            | RomAnalysisResult::GB(_)
+            | RomAnalysisResult::GBA(_)
+            | RomAnalysisResult::Intellivision(_)
+            | RomAnalysisResult::MasterSystem(_)
+            | RomAnalysisResult::N64(_)
+            | RomAnalysisResult::NeoGeo(_)
+            | RomAnalysisResult::PSP(_)
+            | RomAnalysisResult::SegaCD(_)
+            | RomAnalysisResult::Unknown(_) => VideoSystem::Unknown,
+        }
+    }
+
+    /// Returns whether the region in the ROM header doesn't match the region in the filename.
+    pub fn region_mismatch(&self) -> bool {
+        self.metadata().region_mismatch()
+    }
+
+    /// Returns how the region was determined: from the ROM header, the filename, or not at all.
+    pub fn region_confidence(&self) -> Confidence {
+        self.metadata().region_confidence()
+    }
+
+    /// Returns the path of the archive (ZIP, CHD or CSO) the ROM was read from, if any.
+    pub fn archive_source(&self) -> Option<&str> {
+        self.metadata().archive_source()
+    }
+
+    /// Returns the offset and value of the header's region byte when it maps to no known region,
+    /// see [`RomMetadata::raw_region_info`].
+    pub fn raw_region_info(&self) -> Option<(usize, u8)> {
+        self.metadata().raw_region_info()
+    }
+
+    /// Returns a short human-readable label for the console (e.g. "SNES", "Sega Genesis").
+    pub fn console_name(&self) -> &'static str {
+        match self {
+            RomAnalysisResult::Atari7800(_) => "Atari 7800",
+            RomAnalysisResult::CdImage(_) => "CD Image",
+            RomAnalysisResult::GameCube(a) if a.is_wii => "Wii",
+            RomAnalysisResult::GameCube(_) => "GameCube",
+            RomAnalysisResult::GameGear(_) => "Sega Game Gear",
+            RomAnalysisResult::GB(_) => "Game Boy",
+            RomAnalysisResult::GBA(_) => "Game Boy Advance",
+            RomAnalysisResult::Genesis(_) => "Sega Genesis",
+            RomAnalysisResult::Intellivision(_) => "Intellivision",
+            RomAnalysisResult::MasterSystem(_) => "Sega Master System",
+            RomAnalysisResult::N64(_) => "N64",
+            RomAnalysisResult::NeoGeo(_) => "Neo Geo",
+            RomAnalysisResult::NES(_) => "NES",
+            RomAnalysisResult::PSP(_) => "PSP",
+            RomAnalysisResult::PSX(_) => "PlayStation",
+            RomAnalysisResult::SegaCD(_) => "Sega CD",
+            RomAnalysisResult::SNES(_) => "SNES",
+            RomAnalysisResult::ThreeDO(_) => "3DO",
+            RomAnalysisResult::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Returns the game title from the ROM header, for consoles whose header stores one.
+    ///
+    /// Genesis ROMs return the international title, Neo Geo ROMs the game name, N64 ROMs the
+    /// internal name, and Intellivision ROMs and PSP images their EXEC header or PARAM.SFO title
+    /// (if it was found). Generic CD images return their ISO9660 volume label, if set. Consoles
+    /// without a title field in their header (NES, Master System, Game Gear, PSX, Sega CD and
+    /// 3DO) return `None`.
+    pub fn game_title(&self) -> Option<String> {
+        match self {
+            RomAnalysisResult::Atari7800(a) => Some(a.game_title.clone()),
+            RomAnalysisResult::CdImage(a) => a.volume_label.clone(),
+            RomAnalysisResult::GameCube(a) => Some(a.game_title.clone()),
+            RomAnalysisResult::GB(a) => Some(a.game_title.clone()),
+            RomAnalysisResult::GBA(a) => Some(a.game_title.clone()),
+            RomAnalysisResult::Genesis(a) => Some(a.game_title_international.clone()),
+            RomAnalysisResult::Intellivision(a) => a.game_title.clone(),
+            RomAnalysisResult::N64(a) => Some(a.internal_name.clone()),
+            RomAnalysisResult::NeoGeo(a) => Some(a.game_name.clone()),
+            RomAnalysisResult::PSP(a) => a.title.clone(),
+            RomAnalysisResult::SNES(a) => Some(a.game_title.clone()),
+            RomAnalysisResult::GameGear(_)
+            | RomAnalysisResult::MasterSystem(_)
+            | RomAnalysisResult::NES(_)
+            | RomAnalysisResult::PSX(_)
+            | RomAnalysisResult::SegaCD(_)
+            | RomAnalysisResult::ThreeDO(_)
+            | RomAnalysisResult::Unknown(_) => None,
+        }
+    }
+
+    /// Returns the byte range of the ROM header the analysis was read from, for debugging.
+    ///
+    /// The range is relative to the start of the analyzed ROM data. Headerless NES ROMs and
+    /// interleaved SNES and Genesis ROMs (whose header isn't contiguous in the file) return
+    /// `None`, as do consoles whose header has no fixed location (Master System, Game Gear,
+    /// Intellivision and disc images).
+    pub fn header_range(&self) -> Option<Range<usize>> {
+        match self {
+            RomAnalysisResult::Atari7800(_) => Some(0x00..atari7800::A78_HEADER_SIZE),
+            RomAnalysisResult::GameCube(_) => Some(0x00..gamecube::HEADER_SIZE),
+            RomAnalysisResult::GB(_) => Some(0x100..0x150),
+            RomAnalysisResult::GBA(_) => Some(0x00..0xC0),
+            RomAnalysisResult::Genesis(a) if a.interleaved => None,
+            RomAnalysisResult::Genesis(a) if a.copier_header_present => Some(0x300..0x400),
+            RomAnalysisResult::Genesis(_) | RomAnalysisResult::SegaCD(_) => Some(0x100..0x200),
+            RomAnalysisResult::N64(_) => Some(0x00..0x40),
+            RomAnalysisResult::NeoGeo(_) => Some(0x00..0x80),
+            RomAnalysisResult::NES(a) => a.has_header.then_some(0x00..0x10),
+            RomAnalysisResult::SNES(a) => (!a.interleaved)
+                .then_some(a.header_offset..a.header_offset + snes::SNES_HEADER_SIZE),
+            RomAnalysisResult::CdImage(_)
+            | RomAnalysisResult::GameGear(_)
+            | RomAnalysisResult::Intellivision(_)
+            | RomAnalysisResult::MasterSystem(_)
+            | RomAnalysisResult::PSP(_)
+            | RomAnalysisResult::PSX(_)
+            | RomAnalysisResult::ThreeDO(_)
+            | RomAnalysisResult::Unknown(_) => None,
+        }
+    }
+
+    /// Records the archive the ROM was read from on the inner analysis struct.
+    fn with_archive_source(mut self, archive_path: &str) -> Self {
+        let archive_source = Some(archive_path.to_string());
+        match &mut self {
+            RomAnalysisResult::Atari7800(a) => a.archive_source = archive_source,
+            RomAnalysisResult::CdImage(a) => a.archive_source = archive_source,
+            RomAnalysisResult::GameCube(a) => a.archive_source = archive_source,
+            RomAnalysisResult::GameGear(a) => a.archive_source = archive_source,
+            RomAnalysisResult::GB(a) => a.archive_source = archive_source,
+            RomAnalysisResult::GBA(a) => a.archive_source = archive_source,
+            RomAnalysisResult::Genesis(a) => a.archive_source = archive_source,
+            RomAnalysisResult::Intellivision(a) => a.archive_source = archive_source,
+            RomAnalysisResult::MasterSystem(a) => a.archive_source = archive_source,
+            RomAnalysisResult::N64(a) => a.archive_source = archive_source,
+            RomAnalysisResult::NeoGeo(a) => a.archive_source = archive_source,
+            RomAnalysisResult::NES(a) => a.archive_source = archive_source,
+            RomAnalysisResult::PSP(a) => a.archive_source = archive_source,
+            RomAnalysisResult::PSX(a) => a.archive_source = archive_source,
+            RomAnalysisResult::SegaCD(a) => a.archive_source = archive_source,
+            RomAnalysisResult::SNES(a) => a.archive_source = archive_source,
+            RomAnalysisResult::ThreeDO(a) => a.archive_source = archive_source,
+            RomAnalysisResult::Unknown(a) => a.archive_source = archive_source,
+        }
+        self
+    }
+
+    /// Records the full size of a ROM whose analysis only saw part of it.
+    ///
+    /// Game Boy and Genesis analysis is given just the header of a plain file, and ZIP extraction
+    /// and `max_file_size` can cut any ROM short, so the SNES, Game Boy and Genesis size mismatch
+    /// checks are redone against the full size of the file or archive entry.
+    fn with_rom_size(mut self, rom_size: usize) -> Self {
+        match &mut self {
+            RomAnalysisResult::SNES(a) => {
+                let rom_size = rom_size - snes::copier_header_size(rom_size);
+                a.rom_size = rom_size;
+                a.size_mismatch = a
+                    .declared_size
+                    .is_some_and(|size| snes::is_size_mismatch(size, rom_size));
+            }
+            RomAnalysisResult::GB(a) => {
+                a.rom_size = rom_size;
+                a.size_mismatch = a.declared_size.is_some_and(|size| size != rom_size);
+            }
+            RomAnalysisResult::Genesis(a) => {
+                let rom_size = if a.copier_header_present {
+                    rom_size.saturating_sub(genesis::SMD_HEADER_SIZE)
+                } else {
+                    rom_size
+                };
+                a.rom_size = rom_size;
+                a.size_mismatch = a.declared_size.is_some_and(|size| size != rom_size);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Recomputes [`RomAnalysisResult::region_mismatch`] using the given [`MismatchMode`].
+    ///
+    /// Analyzers flag mismatches in [`MismatchMode::Strict`] mode, this re-checks the header
+    /// region against the filename with another mode.
+    pub fn with_mismatch_mode(mut self, mode: MismatchMode) -> Self {
+        // Atari 7800 regions come from the filename, so they are checked against the TV type.
+        let header_region = match &self {
+            RomAnalysisResult::Atari7800(a) => atari7800::tv_region(a.video_system),
+            _ => self.region_bitmask(),
+        };
+        let region_mismatch =
+            check_region_mismatch_with_mode(self.source_name(), header_region, mode);
+        match &mut self {
+            RomAnalysisResult::Atari7800(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::CdImage(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::GameCube(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::GameGear(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::GB(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::GBA(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::Genesis(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::Intellivision(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::MasterSystem(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::N64(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::NeoGeo(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::NES(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::PSP(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::PSX(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::SegaCD(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::SNES(a) => {
+                a.region_mismatch = region_mismatch
+                    || (mode != MismatchMode::Off
+                        && a.strict_world
+                        && snes::is_strict_world_mismatch(&a.source_name, a.region_code));
+            }
+            RomAnalysisResult::ThreeDO(a) => a.region_mismatch = region_mismatch,
+            RomAnalysisResult::Unknown(a) => a.region_mismatch = region_mismatch,
+        }
+        self
+    }
+}
+
+/// Delegates to the inner console-specific analysis, so results can be boxed as a
+/// [`RomMetadata`] trait object, as done by [`registry::AnalyzerRegistry`].
+impl RomMetadata for RomAnalysisResult {
+    fn source_name(&self) -> &str {
+        self.metadata().source_name()
+    }
+
+    fn region(&self) -> Region {
+        self.metadata().region()
+    }
+
+    fn region_string(&self) -> &str {
+        self.metadata().region_string()
+    }
+
+    fn region_mismatch(&self) -> bool {
+        self.metadata().region_mismatch()
+    }
+
+    fn region_confidence(&self) -> Confidence {
+        self.metadata().region_confidence()
+    }
+
+    fn archive_source(&self) -> Option<&str> {
+        self.metadata().archive_source()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        self.metadata().to_fields()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::cdimage;
+    use crate::console::test_logger::capture_logs;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use zip::write::{FileOptions, ZipWriter};
+
+    const TEST_SEGA_MEGA_DRIVE_HEADER: &[u8] = b"SEGA MEGA DRIVE "; // Padded to 16 bytes
+    const TEST_SEGA_GENESIS_HEADER: &[u8] = b"SEGA GENESIS    ";
+
+    #[test]
+    fn test_get_rom_file_type() {
+        assert_eq!(get_rom_file_type("game.nes"), RomFileType::Nes);
+        assert_eq!(get_rom_file_type("game.smc"), RomFileType::Snes);
+        assert_eq!(get_rom_file_type("game.sfc"), RomFileType::Snes);
+        assert_eq!(get_rom_file_type("game.n64"), RomFileType::N64);
+        assert_eq!(get_rom_file_type("game.v64"), RomFileType::N64);
+        assert_eq!(get_rom_file_type("game.z64"), RomFileType::N64);
+        assert_eq!(get_rom_file_type("game.sms"), RomFileType::MasterSystem);
+        assert_eq!(get_rom_file_type("game.gg"), RomFileType::GameGear);
+        assert_eq!(get_rom_file_type("game.gb"), RomFileType::GameBoy);
+        assert_eq!(get_rom_file_type("game.gbc"), RomFileType::GameBoy);
+        assert_eq!(get_rom_file_type("game.gba"), RomFileType::GameBoyAdvance);
+        assert_eq!(get_rom_file_type("game.md"), RomFileType::Genesis);
+        assert_eq!(get_rom_file_type("game.gen"), RomFileType::Genesis);
+        assert_eq!(get_rom_file_type("game.32x"), RomFileType::Genesis);
+        assert_eq!(get_rom_file_type("game.smd"), RomFileType::Genesis);
+        assert_eq!(get_rom_file_type("game.scd"), RomFileType::SegaCD);
+        assert_eq!(get_rom_file_type("game.neo"), RomFileType::NeoGeo);
+        assert_eq!(get_rom_file_type("game.int"), RomFileType::Intellivision);
+        assert_eq!(get_rom_file_type("game.a78"), RomFileType::Atari7800);
+        assert_eq!(get_rom_file_type("game.iso"), RomFileType::CDSystem);
+        assert_eq!(get_rom_file_type("game.bin"), RomFileType::CDSystem);
+        assert_eq!(get_rom_file_type("game.img"), RomFileType::CDSystem);
+        assert_eq!(get_rom_file_type("game.psx"), RomFileType::CDSystem);
+        assert_eq!(get_rom_file_type("game.chd"), RomFileType::CDSystem);
+        assert_eq!(get_rom_file_type("game.cso"), RomFileType::CDSystem);
+        assert_eq!(get_rom_file_type("game.rvz"), RomFileType::GameCube);
+        assert_eq!(get_rom_file_type("game.wia"), RomFileType::GameCube);
+        assert_eq!(get_rom_file_type("game.zip"), RomFileType::Unknown);
+        assert_eq!(get_rom_file_type("game.txt"), RomFileType::Unknown);
+    }
+
+    #[test]
+    fn test_is_rom_file() {
+        assert!(is_rom_file("game.nes"));
+        assert!(is_rom_file("path/to/Game.ZIP"));
+        assert!(is_rom_file("game.chd"));
+        assert!(is_rom_file("game.cso"));
+        assert!(!is_rom_file("notes.txt"));
+        assert!(!is_rom_file("game"));
+    }
+
+    #[test]
+    fn test_get_rom_file_type_no_extension() {
+        assert_eq!(get_rom_file_type("game"), RomFileType::Unknown);
+        assert_eq!(get_rom_file_type("game."), RomFileType::Unknown);
+    }
+
+    #[test]
+    fn test_supported_consoles_cover_each_extension_once() {
+        let consoles = supported_consoles();
+        for ext in SUPPORTED_ROM_EXTENSIONS.iter().chain(CONTAINER_EXTENSIONS) {
+            let owners: Vec<&str> = consoles
+                .iter()
+                .filter(|console| console.extensions.contains(ext))
+                .map(|console| console.name)
+                .collect();
+            assert_eq!(owners.len(), 1, "{} is listed by {:?}", ext, owners);
+        }
+    }
+
+    #[test]
+    fn test_supported_rom_extensions_match_consoles() {
+        // Every console extension is either a ROM extension or a container format, never both.
+        for console in supported_consoles() {
+            for ext in console.extensions {
+                assert_ne!(
+                    SUPPORTED_ROM_EXTENSIONS.contains(ext),
+                    CONTAINER_EXTENSIONS.contains(ext),
+                    "{}",
+                    ext
+                );
+            }
+        }
+        assert!(SUPPORTED_ROM_EXTENSIONS.contains(&".nes"));
+        assert!(!SUPPORTED_ROM_EXTENSIONS.contains(&".chd"));
+    }
+
+    #[test]
+    fn test_supported_consoles_match_dispatch() {
+        for console in supported_consoles() {
+            assert!(!console.extensions.is_empty());
+            for ext in console.extensions {
+                assert_ne!(
+                    get_rom_file_type(&format!("game{}", ext)),
+                    RomFileType::Unknown,
+                    "{} from {} is not dispatched",
+                    ext,
+                    console.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_rom_file_type() {
+        let mut genesis_data = vec![0; 0x200];
+        genesis_data[0x100..0x110].copy_from_slice(TEST_SEGA_GENESIS_HEADER);
+        let mut segacd_data = vec![0; 0x200];
+        segacd_data[0x100..0x107].copy_from_slice(b"SEGA CD");
+        let mut gba_data = vec![0; 0xC0];
+        gba_data[gba::GBA_LOGO_START..gba::GBA_LOGO_END].copy_from_slice(&gba::GBA_NINTENDO_LOGO);
+
+        assert_eq!(detect_rom_file_type(b"NES\x1a\x02\x01"), RomFileType::Nes);
+        assert_eq!(
+            detect_rom_file_type(&[0x80, 0x37, 0x12, 0x40, 0x00]),
+            RomFileType::N64
+        );
+        assert_eq!(detect_rom_file_type(&gba_data), RomFileType::GameBoyAdvance);
+        assert_eq!(detect_rom_file_type(&genesis_data), RomFileType::Genesis);
+        assert_eq!(detect_rom_file_type(&segacd_data), RomFileType::SegaCD);
+        assert_eq!(
+            detect_rom_file_type(&atari7800::tests::generate_a78_rom("GAME", 0x00, 0)),
+            RomFileType::Atari7800
+        );
+        assert_eq!(detect_rom_file_type(&[0; 0x200]), RomFileType::Unknown);
+        assert_eq!(detect_rom_file_type(&[]), RomFileType::Unknown);
+    }
+
+    #[test]
+    fn test_analyze_rom_data_zipped_extensionless_genesis() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let zip_path = dir.path().join("game.zip");
+        let mut genesis_data = vec![0; 0x200];
+        genesis_data[0x100..0x110].copy_from_slice(TEST_SEGA_GENESIS_HEADER);
+
+        let mut zip = ZipWriter::new(File::create(&zip_path)?);
+        zip.start_file("GAME", FileOptions::default())?;
+        zip.write_all(&genesis_data)?;
+        zip.finish()?;
+
+        let analysis = analyze_rom_data(zip_path.to_str().unwrap())?;
+        assert_eq!(analysis.console_name(), "Sega Genesis");
+        assert_eq!(analysis.source_name(), "GAME");
+        Ok(())
     }
 
     #[test]
     fn test_process_rom_data_unrecognized_extension() {
         let data = vec![];
         let name = "game.xyz";
-        let result = process_rom_data(data, name);
+        let result = process_rom_data(&data, name);
         let err = result.expect_err(
             "process_rom_data should have returned an error for unrecognized extension",
         );
         assert!(err.to_string().contains("Unrecognized ROM file extension"));
     }
 
+    #[test]
+    fn test_process_rom_data_too_small_is_typed() {
+        // Every analyzer with a minimum header size reports DataTooSmall, not Generic.
+        for name in [
+            "game.nes", "game.sfc", "game.n64", "game.sms", "game.gb", "game.gba", "game.md",
+            "game.scd", "game.iso",
+        ] {
+            let result = process_rom_data(&[0; 8], name);
+            assert!(
+                matches!(result, Err(RomAnalyzerError::DataTooSmall { .. })),
+                "Expected DataTooSmall for {}, got {:?}",
+                name,
+                result
+            );
+        }
+    }
+
     #[test]
     fn test_process_rom_data_cd_system_sega_genesis_header() {
         let mut data = vec![0; 0x120];
@@ -390,7 +1772,7 @@ mod tests {
         // This will attempt to call genesis::analyze_genesis_data
         // Since we don't have a full mock, we'll assert it doesn't return an unknown error
         // A successful return indicates it dispatched to a recognized console analyzer.
-        let result = process_rom_data(data, name);
+        let result = process_rom_data(&data, name);
         // Expect an error from the analyzer itself if the data isn't valid for a Sega Cartridge, not an 'Unknown' dispatch error.
         assert!(result.is_err());
         let err = result.expect_err("process_rom_data should have returned an error for mock data");
@@ -403,19 +1785,57 @@ mod tests {
         let mut data = vec![0; 0x120];
         data[0x100..0x110].copy_from_slice(TEST_SEGA_GENESIS_HEADER);
         let name = "game.bin";
-        let result = process_rom_data(data, name);
+        let result = process_rom_data(&data, name);
         assert!(result.is_err());
         let err = result.expect_err("process_rom_data should have returned an error for mock data");
         assert!(!err.to_string().contains("Unrecognized ROM file extension"));
         assert!(!err.to_string().contains("PSX"));
     }
 
+    #[test]
+    fn test_process_rom_data_sega_cartridge_returns_structured_data() -> Result<(), RomAnalyzerError>
+    {
+        // Sega cartridges are handled by the Genesis analyzer alone, which returns its findings
+        // as a GenesisAnalysis instead of printing them.
+        let mut data = vec![0; 0x200];
+        data[0x100..0x110].copy_from_slice(TEST_SEGA_MEGA_DRIVE_HEADER);
+        data[0x1F0] = b'U';
+
+        for name in ["game.md", "game.gen", "game.32x", "game.bin"] {
+            match process_rom_data(&data, name)? {
+                RomAnalysisResult::Genesis(analysis) => {
+                    assert_eq!(analysis.source_name, name);
+                    assert_eq!(analysis.console_name, "SEGA MEGA DRIVE");
+                    assert_eq!(analysis.region, Region::USA);
+                }
+                other => panic!("Expected a Genesis analysis for {}, got {:?}", name, other),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_rom_data_cd_system_genesis_copier_header() -> Result<(), RomAnalyzerError> {
+        // A Genesis .bin with a 512-byte copier header isn't mistaken for a CD image.
+        let mut data = vec![0; 0x400];
+        data[0x300..0x30C].copy_from_slice(SEGA_GENESIS_SIG);
+        data[0x3F0] = b'J';
+        let analysis = process_rom_data(&data, "game.bin")?;
+        let RomAnalysisResult::Genesis(genesis) = &analysis else {
+            panic!("Expected a Genesis analysis, got {:?}", analysis);
+        };
+        assert!(genesis.copier_header_present);
+        assert_eq!(analysis.region_bitmask(), Region::JAPAN);
+        assert_eq!(detect_rom_file_type(&data), RomFileType::Genesis);
+        Ok(())
+    }
+
     #[test]
     fn test_process_rom_data_cd_system_sega_cd_header() {
         let mut data = vec![0; 0x120];
         data[0x100..0x107].copy_from_slice(b"SEGA CD");
         let name = "game.iso";
-        let result = process_rom_data(data, name);
+        let result = process_rom_data(&data, name);
         let err = result.expect_err("process_rom_data should have returned an error for mock data");
         assert!(!err.to_string().contains("Unrecognized ROM file extension"));
     }
@@ -424,11 +1844,63 @@ mod tests {
     fn test_process_rom_data_cd_system_psx() {
         let data = vec![0; 0x100]; // Not enough for Sega headers, should fall through to PSX
         let name = "game.bin";
-        let result = process_rom_data(data, name);
+        let result = process_rom_data(&data, name);
         let err = result.expect_err("process_rom_data should have returned an error for mock data");
         assert!(!err.to_string().contains("Unrecognized ROM file extension"));
     }
 
+    #[test]
+    fn test_process_rom_data_cd_system_generic_cd_image() -> Result<(), RomAnalyzerError> {
+        let data = cdimage::tests::generate_cd_image(0x8000, "WIN32", "MY_DISC", "");
+        match process_rom_data(&data, "disc.iso")? {
+            RomAnalysisResult::CdImage(analysis) => {
+                assert_eq!(analysis.volume_label.as_deref(), Some("MY_DISC"));
+            }
+            other => panic!("Expected a CD image analysis, got {:?}", other),
+        }
+
+        // PlayStation discs with a PVD are still left to the PSX analyzer.
+        let data = cdimage::tests::generate_cd_image(0x8000, "PLAYSTATION", "MY_DISC", "");
+        assert!(matches!(
+            process_rom_data(&data, "disc.iso"),
+            Ok(RomAnalysisResult::PSX(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_rom_data_cd_system_gamecube() -> Result<(), RomAnalyzerError> {
+        let mut data = gamecube::tests::generate_gamecube_header("GALP01", "Melee");
+        data.resize(0x8000, 0);
+        let analysis = process_rom_data(&data, "melee.iso")?;
+        let RomAnalysisResult::GameCube(gamecube) = &analysis else {
+            panic!("Expected a GameCube analysis, got {:?}", analysis);
+        };
+        assert_eq!(gamecube.game_id, "GALP01");
+        assert_eq!(analysis.console_name(), "GameCube");
+        assert_eq!(analysis.game_title().as_deref(), Some("Melee"));
+        assert_eq!(analysis.region_bitmask(), Region::EUROPE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_rvz() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let rvz_path = dir.path().join("melee.rvz");
+        let header = gamecube::tests::generate_gamecube_header("GALE01", "Melee");
+        std::fs::write(&rvz_path, archive::rvz::tests::build_rvz(&header))?;
+        let rvz_path = rvz_path.to_str().unwrap();
+
+        let analysis = analyze_rom_data(rvz_path)?;
+        let RomAnalysisResult::GameCube(gamecube) = &analysis else {
+            panic!("Expected a GameCube analysis, got {:?}", analysis);
+        };
+        assert_eq!(gamecube.game_id, "GALE01");
+        assert_eq!(analysis.region_bitmask(), Region::USA);
+        assert_eq!(analysis.archive_source(), Some(rvz_path));
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_rom_data_zip() {
         let dir = tempdir().unwrap();
@@ -445,6 +1917,958 @@ mod tests {
         assert!(!err.to_string().contains("Unrecognized ROM file extension"));
     }
 
+    #[test]
+    fn test_analyze_rom_data_zip_master_system_checksum_not_covered() -> Result<(), RomAnalyzerError>
+    {
+        // A good 256KB ROM is cut to 128KB by ZIP extraction, so its checksum can't be checked.
+        let mut rom = vec![0; 0x40000];
+        rom[0x7FF0..0x7FF8].copy_from_slice(b"TMR SEGA");
+        rom[0x7FFF] = 0x40;
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.sms");
+        std::fs::write(&rom_path, &rom)?;
+        let zip_path = dir.path().join("game.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path)?);
+        zip.start_file("game.sms", FileOptions::default())?;
+        zip.write_all(&rom)?;
+        zip.finish()?;
+
+        for path in [&rom_path, &zip_path] {
+            let analysis = analyze_rom_data(path.to_str().unwrap())?;
+            let RomAnalysisResult::MasterSystem(sms) = &analysis else {
+                panic!("Expected a Master System analysis, got {:?}", analysis);
+            };
+            let expected = if path == &rom_path { Some(true) } else { None };
+            assert_eq!(sms.checksum_valid, expected);
+            assert!(!analysis.print().contains("Checksum:"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_zip_checks_full_rom_size() -> Result<(), RomAnalyzerError> {
+        // ZIP extraction keeps only the first 128KB, so sizes are checked against the entry size.
+        let mut snes = vec![0; 0x80000];
+        snes[0x7FD7] = 0x09; // Declares 512KB
+        let mut gb = vec![0; 0x40000];
+        gb[0x148] = 0x03; // Declares 256KB
+        let mut genesis = vec![0; 0x80000];
+        genesis[0x100..0x10C].copy_from_slice(SEGA_GENESIS_SIG);
+        genesis[0x1A4..0x1A8].copy_from_slice(&0x7FFFFu32.to_be_bytes());
+
+        let dir = tempdir()?;
+        let zip_path = dir.path().join("romset.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path)?);
+        for (name, rom) in [("game.sfc", &snes), ("game.gb", &gb), ("game.md", &genesis)] {
+            zip.start_file(name, FileOptions::default())?;
+            zip.write_all(rom)?;
+        }
+        zip.finish()?;
+
+        let analyses = analyze_rom_data_multi(zip_path.to_str().unwrap())?;
+        assert_eq!(analyses.len(), 3);
+        for analysis in &analyses {
+            match analysis {
+                RomAnalysisResult::SNES(a) => {
+                    assert_eq!((a.rom_size, a.size_mismatch), (0x80000, false))
+                }
+                RomAnalysisResult::GB(a) => {
+                    assert_eq!((a.rom_size, a.size_mismatch), (0x40000, false))
+                }
+                RomAnalysisResult::Genesis(a) => {
+                    assert_eq!((a.rom_size, a.size_mismatch), (0x80000, false))
+                }
+                other => panic!("Unexpected analysis {:?}", other),
+            }
+        }
+
+        let single_path = dir.path().join("game.zip");
+        let mut zip = ZipWriter::new(File::create(&single_path)?);
+        zip.start_file("game.sfc", FileOptions::default())?;
+        zip.write_all(&snes)?;
+        zip.finish()?;
+        match analyze_rom_data(single_path.to_str().unwrap())? {
+            RomAnalysisResult::SNES(a) => {
+                assert_eq!((a.rom_size, a.size_mismatch), (0x80000, false))
+            }
+            other => panic!("Expected an SNES analysis, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_zip_reports_archive_source() {
+        let dir = tempdir().unwrap();
+        let zip_path = dir.path().join("test.zip");
+        let zip_file = File::create(&zip_path).unwrap();
+        let mut zip = ZipWriter::new(zip_file);
+        zip.start_file("game.nes", FileOptions::default()).unwrap();
+        zip.write_all(b"NES\x1a\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00")
+            .unwrap();
+        zip.finish().unwrap();
+        let zip_path_str = zip_path.to_str().unwrap();
+
+        let analysis = analyze_rom_data(zip_path_str).expect("Expected zipped NES to analyze");
+        assert_eq!(analysis.source_name(), "game.nes");
+        assert_eq!(analysis.archive_source(), Some(zip_path_str));
+        assert!(
+            analysis
+                .print()
+                .starts_with(&format!("game.nes\nArchive:      {}\n", zip_path_str))
+        );
+    }
+
+    #[test]
+    fn test_analyze_rom_data_multi_zip() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let zip_path = dir.path().join("romset.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path)?);
+        zip.start_file("game (U).nes", FileOptions::default())?;
+        zip.write_all(b"NES\x1a\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00")?;
+        zip.start_file("game (J).gb", FileOptions::default())?;
+        zip.write_all(&[0; 0x150])?;
+        zip.finish()?;
+        let zip_path_str = zip_path.to_str().unwrap();
+
+        let analyses = analyze_rom_data_multi(zip_path_str)?;
+        assert_eq!(analyses.len(), 2);
+        assert!(matches!(analyses[0], RomAnalysisResult::NES(_)));
+        assert_eq!(analyses[0].source_name(), "game (U).nes");
+        assert!(matches!(analyses[1], RomAnalysisResult::GB(_)));
+        assert_eq!(analyses[1].source_name(), "game (J).gb");
+        for analysis in &analyses {
+            assert_eq!(analysis.archive_source(), Some(zip_path_str));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_multi_with_options_strict_zip() -> Result<(), RomAnalyzerError> {
+        // A Genesis ROM with an unexpected signature inside a ZIP is only rejected in strict mode.
+        let mut genesis = vec![0; 0x200];
+        genesis[0x100..0x110].copy_from_slice(b"NOT A SEGA ROM  ");
+        let dir = tempdir()?;
+        let zip_path = dir.path().join("romset.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path)?);
+        zip.start_file("game.nes", FileOptions::default())?;
+        zip.write_all(b"NES\x1a\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00")?;
+        zip.start_file("game.md", FileOptions::default())?;
+        zip.write_all(&genesis)?;
+        zip.finish()?;
+        let zip_path = zip_path.to_str().unwrap();
+
+        let analyses = analyze_rom_data_multi(zip_path)?;
+        assert!(matches!(analyses[1], RomAnalysisResult::Genesis(_)));
+
+        let strict = AnalysisOptions {
+            strict: true,
+            ..AnalysisOptions::default()
+        };
+        assert_eq!(
+            analyze_rom_data_multi_with_options(zip_path, &strict).map_err(|e| e.kind()),
+            Err(RomAnalyzerErrorKind::InvalidSignature)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_multi_plain_file() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.nes");
+        std::fs::write(
+            &rom_path,
+            b"NES\x1a\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+        )?;
+        let rom_path_str = rom_path.to_str().unwrap();
+
+        let analyses = analyze_rom_data_multi(rom_path_str)?;
+        assert_eq!(analyses, vec![analyze_rom_data(rom_path_str)?]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_plain_file_has_no_archive_source() {
+        let dir = tempdir().unwrap();
+        let rom_path = dir.path().join("game.nes");
+        std::fs::write(
+            &rom_path,
+            b"NES\x1a\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+        )
+        .unwrap();
+
+        let analysis = analyze_rom_data(rom_path.to_str().unwrap()).unwrap();
+        assert_eq!(analysis.archive_source(), None);
+        assert!(!analysis.print().contains("Archive:"));
+    }
+
+    #[test]
+    fn test_analyze_rom_data_zip_and_plain_share_dispatch() -> Result<(), RomAnalyzerError> {
+        // Archived and plain ROMs both go through process_rom_data, so the same header is
+        // analyzed the same way.
+        let rom_data = b"NES\x1a\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.nes");
+        std::fs::write(&rom_path, rom_data)?;
+        let zip_path = dir.path().join("game.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path)?);
+        zip.start_file("game.nes", FileOptions::default())?;
+        zip.write_all(rom_data)?;
+        zip.finish()?;
+        let zip_path = zip_path.to_str().unwrap();
+
+        let plain = analyze_rom_data(rom_path.to_str().unwrap())?;
+        let zipped = analyze_rom_data(zip_path)?;
+        let (RomAnalysisResult::NES(plain), RomAnalysisResult::NES(zipped)) = (&plain, &zipped)
+        else {
+            panic!("Expected NES analyses, got {:?} and {:?}", plain, zipped);
+        };
+        assert_eq!(zipped.source_name, "game.nes");
+        assert_eq!(zipped.archive_source.as_deref(), Some(zip_path));
+        assert_eq!(plain.archive_source, None);
+        assert_eq!(zipped.region, plain.region);
+        assert_eq!(zipped.region_string, plain.region_string);
+        assert_eq!(zipped.has_header, plain.has_header);
+        assert_eq!(zipped.has_trainer, plain.has_trainer);
+        assert_eq!(zipped.payload_offset, plain.payload_offset);
+        assert_eq!(zipped.region_byte_value, plain.region_byte_value);
+        assert_eq!(zipped.is_nes2_format, plain.is_nes2_format);
+        assert_eq!(zipped.video_system, plain.video_system);
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_matches_inner_analysis() -> Result<(), RomAnalyzerError> {
+        let mut nes_data = vec![0; 16];
+        nes_data[0..4].copy_from_slice(b"NES\x1a");
+        let inner = nes::analyze_nes_data(&nes_data, "game (E).nes")?;
+        let analysis = process_rom_data(&nes_data, "game (E).nes")?;
+
+        assert_eq!(analysis.print(), inner.print());
+        assert_eq!(analysis.source_name(), "game (E).nes");
+        assert_eq!(analysis.region(), "NTSC (USA/Japan)");
+        assert!(analysis.region_mismatch());
+        assert_eq!(analysis.metadata().region(), Region::USA | Region::JAPAN);
+        assert_eq!(analysis.region_bitmask(), Region::USA | Region::JAPAN);
+        assert_eq!(analysis.archive_source(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_lenient_truncated_snes() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("Truncated Game (E).sfc");
+        fs::write(&rom_path, vec![0; 0x100])?;
+        let rom_path = rom_path.to_str().unwrap();
+
+        assert!(matches!(
+            analyze_rom_data(rom_path),
+            Err(RomAnalyzerError::DataTooSmall { .. })
+        ));
+
+        let lenient = analyze_rom_data_lenient(rom_path)?;
+        assert_eq!(lenient.source_name, rom_path);
+        assert_eq!(lenient.analysis, None);
+        assert_eq!(lenient.region, Region::EUROPE);
+        assert_eq!(lenient.warnings.len(), 1);
+        assert!(lenient.warnings[0].starts_with("ROM data too small"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_with_max_size_truncates() -> Result<(), RomAnalyzerError> {
+        // A 32KB Master System ROM whose header sits at 0x7ff0, beyond the 16KB cap.
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game (Japan).sms");
+        let mut data = vec![0; 0x8000];
+        data[0x7FF0..0x7FF8].copy_from_slice(b"TMR SEGA");
+        data[0x7FFF] = 0x4C;
+        fs::write(&rom_path, &data)?;
+        let rom_path = rom_path.to_str().unwrap();
+
+        let RomAnalysisResult::MasterSystem(full) = analyze_rom_data(rom_path)? else {
+            panic!("Expected a Master System analysis");
+        };
+        assert!(full.header_found);
+
+        let RomAnalysisResult::MasterSystem(truncated) =
+            analyze_rom_data_with_max_size(rom_path, Some(0x4000))?
+        else {
+            panic!("Expected a Master System analysis");
+        };
+        assert!(!truncated.header_found);
+        assert_eq!(truncated.region, Region::JAPAN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_plain_file_reads_only_cd_prefix() -> Result<(), RomAnalyzerError> {
+        // CD images are only read up to the PSX scan window, so a late prefix isn't seen.
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.iso");
+        let mut data = vec![0; 0x40000];
+        data[0x30000..0x30004].copy_from_slice(b"SLES");
+        fs::write(&rom_path, &data)?;
+        let rom_path = rom_path.to_str().unwrap();
+
+        let analysis = analyze_rom_data(rom_path)?;
+        assert_eq!(analysis.region_bitmask(), Region::UNKNOWN);
+        assert_eq!(process_rom_data(&data[..0x20000], rom_path)?, analysis);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_plain_file_smd_genesis() -> Result<(), RomAnalyzerError> {
+        // A 64KB ROM stored in the SMD format is sized without its copier header.
+        let mut rom_data = vec![0; 0x10000];
+        rom_data[0x100..0x10C].copy_from_slice(SEGA_GENESIS_SIG);
+        rom_data[0x1A4..0x1A8].copy_from_slice(&0xFFFFu32.to_be_bytes());
+        let mut data = vec![0; genesis::SMD_HEADER_SIZE];
+        data[8..10].copy_from_slice(&[0xAA, 0xBB]);
+        for block in rom_data.chunks(genesis::SMD_BLOCK_SIZE) {
+            data.extend(block.iter().skip(1).step_by(2));
+            data.extend(block.iter().step_by(2));
+        }
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.smd");
+        fs::write(&rom_path, &data)?;
+
+        let analysis = analyze_rom_data(rom_path.to_str().unwrap())?;
+        let RomAnalysisResult::Genesis(genesis) = &analysis else {
+            panic!("Expected a Genesis analysis, got {:?}", analysis);
+        };
+        assert!(genesis.interleaved);
+        assert_eq!(genesis.console_name, "SEGA GENESIS");
+        assert_eq!(genesis.rom_size, 0x10000);
+        assert!(!genesis.size_mismatch);
+        assert_eq!(analysis.header_range(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_bytes_as_forces_console() -> Result<(), RomAnalyzerError> {
+        // A .bin holding both a Genesis signature and a SNES header is dispatched to the Genesis
+        // analyzer by content, unless another console is forced.
+        let mut data = vec![0; 0x8000];
+        data[0x100..0x10C].copy_from_slice(SEGA_GENESIS_SIG);
+        data[0x7FC0 + 0x19] = 0x00; // Japan
+        assert_eq!(
+            process_rom_data(&data, "game.bin")?.console_name(),
+            "Sega Genesis"
+        );
+
+        let analysis = analyze_rom_bytes_as(&data, "game.bin", RomFileType::Snes)?;
+        assert_eq!(analysis.console_name(), "SNES");
+        assert_eq!(analysis.region_bitmask(), Region::JAPAN);
+        let analysis = analyze_rom_bytes_as(&data, "game.bin", RomFileType::Genesis)?;
+        assert_eq!(analysis.console_name(), "Sega Genesis");
+
+        assert!(matches!(
+            analyze_rom_bytes_as(&data, "game.bin", RomFileType::Unknown),
+            Err(RomAnalyzerError::UnsupportedFormat(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_with_options_force_type() -> Result<(), RomAnalyzerError> {
+        // The forced console also decides how much of the file is read.
+        let mut data = vec![0; 0x8000];
+        data[0x7FC0 + 0x19] = 0x00; // Japan
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.md");
+        fs::write(&rom_path, &data)?;
+
+        let options = AnalysisOptions {
+            force_type: Some(RomFileType::Snes),
+            ..AnalysisOptions::default()
+        };
+        let analysis = analyze_rom_data_with_options(rom_path.to_str().unwrap(), &options)?;
+        assert_eq!(analysis.console_name(), "SNES");
+        assert_eq!(analysis.region_bitmask(), Region::JAPAN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_header_size_matches_analyzers() {
+        let file_types = [
+            RomFileType::Nes,
+            RomFileType::Snes,
+            RomFileType::N64,
+            RomFileType::MasterSystem,
+            RomFileType::GameBoy,
+            RomFileType::GameBoyAdvance,
+            RomFileType::Genesis,
+            RomFileType::SegaCD,
+            RomFileType::NeoGeo,
+            RomFileType::Intellivision,
+            RomFileType::Atari7800,
+            RomFileType::CDSystem,
+            RomFileType::GameCube,
+        ];
+        for file_type in file_types {
+            let size = file_type.min_header_size();
+            assert!(size >= 16, "{:?} needs only {} bytes", file_type, size);
+            let too_small = analyze_rom_bytes_as(&vec![0; size - 1], "game", file_type);
+            assert!(
+                matches!(too_small, Err(RomAnalyzerError::DataTooSmall { .. })),
+                "{:?} accepted {} bytes: {:?}",
+                file_type,
+                size - 1,
+                too_small
+            );
+            let enough = analyze_rom_bytes_as(&vec![0; size], "game", file_type);
+            assert!(
+                !matches!(enough, Err(RomAnalyzerError::DataTooSmall { .. })),
+                "{:?} rejected {} bytes: {:?}",
+                file_type,
+                size,
+                enough
+            );
+        }
+
+        // Game Gear ROMs without a header fall back to their filename instead of failing.
+        assert_eq!(RomFileType::GameGear.min_header_size(), 0x2000);
+        assert!(analyze_rom_bytes_as(&[0; 0x10], "game (Japan)", RomFileType::GameGear).is_ok());
+        assert_eq!(RomFileType::Unknown.min_header_size(), 0);
+    }
+
+    #[test]
+    fn test_analyze_plain_file_reads_only_n64_crc_range() -> Result<(), RomAnalyzerError> {
+        // A 4MB N64 ROM is analyzed from its header and the first 1MB its CRC covers.
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.z64");
+        let mut data = vec![0xFF; 4 * 1024 * 1024];
+        data[..0x40].fill(0);
+        data[..4].copy_from_slice(N64_Z64_MAGIC);
+        data[0x3E] = b'E';
+        fs::write(&rom_path, &data)?;
+        let rom_path = rom_path.to_str().unwrap();
+
+        let mut result = None;
+        let records = capture_logs(|| result = Some(analyze_rom_data(rom_path)));
+        let analysis = result.unwrap()?;
+        assert_eq!(analysis.region_bitmask(), Region::USA);
+        assert_eq!(process_rom_data(&data[..0x101000], rom_path)?, analysis);
+        assert!(records.iter().any(|record| {
+            record.message
+                == format!(
+                    "[+] Reading the first 1052672 bytes of {} for header analysis.",
+                    rom_path
+                )
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_plain_file_checks_full_rom_size() -> Result<(), RomAnalyzerError> {
+        // Only the Game Boy header is read, but the declared size is checked against the file.
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.gb");
+        let mut data = vec![0; 0x10000];
+        data[0x148] = 0x00; // Declares 32KB
+        fs::write(&rom_path, &data)?;
+
+        match analyze_rom_data(rom_path.to_str().unwrap())? {
+            RomAnalysisResult::GB(analysis) => {
+                assert_eq!(analysis.rom_size, 0x10000);
+                assert!(analysis.size_mismatch);
+            }
+            other => panic!("Expected a Game Boy analysis, got {:?}", other),
+        }
+
+        data.truncate(0x8000);
+        fs::write(&rom_path, &data)?;
+        match analyze_rom_data(rom_path.to_str().unwrap())? {
+            RomAnalysisResult::GB(analysis) => assert!(!analysis.size_mismatch),
+            other => panic!("Expected a Game Boy analysis, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_with_options_strict() -> Result<(), RomAnalyzerError> {
+        let strict = AnalysisOptions {
+            strict: true,
+            ..AnalysisOptions::default()
+        };
+        let dir = tempdir()?;
+
+        // A Genesis ROM with an unexpected signature is only rejected in strict mode.
+        let rom_path = dir.path().join("game.md");
+        let mut data = vec![0; 0x200];
+        data[0x100..0x110].copy_from_slice(b"NOT A SEGA ROM  ");
+        fs::write(&rom_path, &data)?;
+        let rom_path = rom_path.to_str().unwrap();
+        assert!(matches!(
+            analyze_rom_data(rom_path)?,
+            RomAnalysisResult::Genesis(_)
+        ));
+        assert_eq!(
+            analyze_rom_data_with_options(rom_path, &strict).map_err(|e| e.kind()),
+            Err(RomAnalyzerErrorKind::InvalidSignature)
+        );
+
+        // So is a SNES ROM without a valid checksum.
+        let rom_path = dir.path().join("game.sfc");
+        fs::write(&rom_path, vec![0; 0x8000])?;
+        let rom_path = rom_path.to_str().unwrap();
+        assert!(matches!(
+            analyze_rom_data(rom_path)?,
+            RomAnalysisResult::SNES(_)
+        ));
+        assert_eq!(
+            analyze_rom_data_with_options(rom_path, &strict).map_err(|e| e.kind()),
+            Err(RomAnalyzerErrorKind::ChecksumMismatch)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_with_hashes() -> Result<(), RomAnalyzerError> {
+        // Only the start of a CD image is analyzed, but the whole file is hashed.
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.iso");
+        let data: Vec<u8> = (0..0x50000).map(|i| (i % 251) as u8).collect();
+        fs::write(&rom_path, &data)?;
+        let rom_path = rom_path.to_str().unwrap();
+
+        let (analysis, hashes) =
+            analyze_rom_data_with_hashes(rom_path, &AnalysisOptions::default())?;
+        assert_eq!(analysis, analyze_rom_data(rom_path)?);
+        assert_eq!(hashes, hash::hash_reader(data.as_slice())?);
+        assert_eq!(hashes.crc32, crc32fast::hash(&data));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_plain_file_reads_psp_param_sfo() -> Result<(), RomAnalyzerError> {
+        // The PARAM.SFO is past the CD scan window, the read is extended to reach it.
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.iso");
+        let sfo = psp::tests::generate_param_sfo(&[("DISC_ID", "UCES00001"), ("TITLE", "Game")]);
+        let data = psp::tests::generate_psp_image_with_sfo_at(&sfo, b"", 0x100);
+        fs::write(&rom_path, &data)?;
+        let rom_path = rom_path.to_str().unwrap();
+
+        let analysis = analyze_rom_data(rom_path)?;
+        assert_eq!(analysis.console_name(), "PSP");
+        assert_eq!(analysis.game_title().as_deref(), Some("Game"));
+        assert_eq!(analysis.region_bitmask(), Region::EUROPE);
+
+        // Unless that would read past the maximum file size.
+        let capped = analyze_rom_data_with_max_size(rom_path, Some(0x20000))?;
+        assert_eq!(capped.game_title(), None);
+        assert_eq!(capped.region_bitmask(), Region::UNKNOWN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_cue_sheet() -> Result<(), RomAnalyzerError> {
+        // A CUE sheet is analyzed through its first track and reported as the archive source.
+        let dir = tempdir()?;
+        let cue_path = dir.path().join("game.cue");
+        fs::write(
+            &cue_path,
+            "FILE \"game (Track 1).bin\" BINARY\nFILE \"game (Track 2).bin\" BINARY\n",
+        )?;
+        let cue_path = cue_path.to_str().unwrap();
+        assert!(matches!(
+            analyze_rom_data(cue_path),
+            Err(RomAnalyzerError::ArchiveError(_))
+        ));
+
+        let mut data = vec![0; 0x2000];
+        data[0x100..0x104].copy_from_slice(b"SLPS");
+        let track_path = dir.path().join("game (Track 1).bin");
+        fs::write(&track_path, &data)?;
+        fs::write(dir.path().join("game (Track 2).bin"), [0; 0x2000])?;
+
+        let analysis = analyze_rom_data(cue_path)?;
+        assert!(matches!(analysis, RomAnalysisResult::PSX(_)));
+        assert_eq!(analysis.source_name(), track_path.to_str().unwrap());
+        assert_eq!(analysis.archive_source(), Some(cue_path));
+        assert_eq!(analysis.region_bitmask(), Region::JAPAN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_lenient_valid_rom() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.sfc");
+        let mut data = vec![0; 0x8000];
+        data[0x7FC0 + 0x19] = 0x00; // Japan
+        fs::write(&rom_path, &data)?;
+        let rom_path = rom_path.to_str().unwrap();
+
+        let lenient = analyze_rom_data_lenient(rom_path)?;
+        assert_eq!(lenient.analysis, Some(analyze_rom_data(rom_path)?));
+        assert_eq!(lenient.region, Region::JAPAN);
+        assert!(lenient.warnings.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_lenient_keeps_other_errors() {
+        assert!(matches!(
+            analyze_rom_data_lenient("non_existent_file.sfc"),
+            Err(RomAnalyzerError::IoError(_))
+        ));
+    }
+
+    #[test]
+    fn test_analyze_rom_data_or_unknown_text_file() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let path = dir.path().join("Manual (Japan).txt");
+        fs::write(&path, b"plain text")?;
+        let path = path.to_str().unwrap();
+
+        assert!(matches!(
+            analyze_rom_data(path),
+            Err(RomAnalyzerError::UnsupportedFormat(_))
+        ));
+        let analysis = analyze_rom_data_or_unknown(path, None)?;
+        let RomAnalysisResult::Unknown(unknown) = &analysis else {
+            panic!("Expected an Unknown result, got {:?}", analysis);
+        };
+        assert_eq!(unknown.size, 10);
+        assert_eq!(analysis.region_bitmask(), Region::JAPAN);
+        assert_eq!(analysis.console_name(), "Unknown");
+
+        let json =
+            serde_json::to_value(&analysis).map_err(|e| RomAnalyzerError::new(&e.to_string()))?;
+        assert_eq!(json["console"], "Unknown");
+        assert_eq!(json["size"], 10);
+        assert_eq!(json["region_string"], "Japan");
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_rom_data_or_unknown_keeps_other_errors() -> Result<(), RomAnalyzerError> {
+        assert!(matches!(
+            analyze_rom_data_or_unknown("non_existent_file.txt", None),
+            Err(RomAnalyzerError::IoError(_))
+        ));
+
+        // Recognized files with a bad header still fail.
+        let dir = tempdir()?;
+        let path = dir.path().join("game.nes");
+        fs::write(&path, b"NES")?;
+        let result = analyze_rom_data_or_unknown(path.to_str().unwrap(), None);
+        assert!(matches!(result, Err(RomAnalyzerError::DataTooSmall { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_paths_order_and_errors() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let mut paths = Vec::new();
+        for name in ["a.nes", "b.nes", "c.nes"] {
+            let path = dir.path().join(name);
+            fs::write(
+                &path,
+                b"NES\x1a\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+            )?;
+            paths.push(path.to_str().unwrap().to_string());
+        }
+        let invalid_path = dir.path().join("invalid.nes");
+        fs::write(&invalid_path, b"not a valid NES file")?;
+        paths.insert(1, invalid_path.to_str().unwrap().to_string());
+        paths.push("non_existent_file.nes".to_string());
+
+        let results = analyze_paths(&paths);
+        assert_eq!(results.len(), 5);
+        for i in [0, 2, 3] {
+            assert_eq!(results[i].as_ref().unwrap().source_name(), paths[i]);
+        }
+        assert!(matches!(
+            &results[1],
+            Err(RomAnalyzerError::WithPath(path, _)) if *path == paths[1]
+        ));
+        assert!(matches!(
+            &results[4],
+            Err(RomAnalyzerError::FileNotFound(path)) if path == "non_existent_file.nes"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_paths_empty_input() {
+        assert!(analyze_paths(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_with_mismatch_mode() -> Result<(), RomAnalyzerError> {
+        // NES NTSC headers cover both USA and Japan.
+        let mut data = vec![0; 16];
+        data[0..4].copy_from_slice(b"NES\x1a");
+        let analysis = process_rom_data(&data, "game (U).nes")?;
+        assert!(!analysis.region_mismatch());
+
+        let exact = analysis.clone().with_mismatch_mode(MismatchMode::Exact);
+        assert!(exact.region_mismatch());
+        let off = exact.with_mismatch_mode(MismatchMode::Off);
+        assert!(!off.region_mismatch());
+        assert_eq!(off.with_mismatch_mode(MismatchMode::Strict), analysis);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mismatch_modes_unrestricted_segacd() -> Result<(), RomAnalyzerError> {
+        // Region byte 0x00 is unrestricted, which overlaps any single-region filename.
+        let mut data = vec![0; 0x200];
+        data[0x100..0x107].copy_from_slice(b"SEGA CD");
+        let analysis = process_rom_data(&data, "game (J).scd")?;
+        assert_eq!(analysis.region(), "Unrestricted/BIOS region");
+
+        let modes = [
+            (MismatchMode::Off, false),
+            (MismatchMode::Strict, false),
+            (MismatchMode::Exact, true),
+        ];
+        for (mode, expected) in modes {
+            assert_eq!(
+                analysis.clone().with_mismatch_mode(mode).region_mismatch(),
+                expected,
+                "Failed for {:?}",
+                mode
+            );
+        }
+
+        // Off also silences mismatches strict mode reports.
+        data[0x10B] = 0x80; // Europe
+        let analysis = process_rom_data(&data, "game (J).scd")?;
+        assert!(analysis.region_mismatch());
+        assert!(
+            !analysis
+                .with_mismatch_mode(MismatchMode::Off)
+                .region_mismatch()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_confidence() -> Result<(), RomAnalyzerError> {
+        let mut snes_data = vec![0; 0x8000];
+        snes_data[0x7FC0 + 0x19] = 0x01; // USA / Canada
+        let analysis = process_rom_data(&snes_data, "game.sfc")?;
+        assert_eq!(analysis.region_confidence(), Confidence::FromHeader);
+
+        // Without a 'TMR SEGA' header, the Game Gear region comes from the filename.
+        let analysis = process_rom_data(&vec![0; 0x8000], "game (Japan).gg")?;
+        assert_eq!(analysis.region_bitmask(), Region::JAPAN);
+        assert_eq!(analysis.region_confidence(), Confidence::FromFilename);
+
+        let analysis = process_rom_data(&vec![0; 0x8000], "game.gg")?;
+        assert_eq!(analysis.region_confidence(), Confidence::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_video_standard() -> Result<(), RomAnalyzerError> {
+        // NES 2.0 headers give the timing explicitly.
+        let mut nes_data = vec![0; 16];
+        nes_data[0..4].copy_from_slice(b"NES\x1a");
+        nes_data[7] = 0x08;
+        nes_data[12] = 0x01;
+        let nes = process_rom_data(&nes_data, "game.nes")?;
+        assert_eq!(nes.video_standard(), VideoSystem::Pal);
+
+        // Genesis timing follows the region code.
+        let mut genesis_data = vec![0; 0x200];
+        genesis_data[0x100..0x110].copy_from_slice(TEST_SEGA_GENESIS_HEADER);
+        genesis_data[0x1F0] = b'U';
+        let genesis = process_rom_data(&genesis_data, "game.md")?;
+        assert_eq!(genesis.video_standard(), VideoSystem::Ntsc);
+
+        // PSX timing follows the executable prefix.
+        let mut psx_data = vec![0; 0x2000];
+        psx_data[0x100..0x104].copy_from_slice(b"SLES");
+        let psx = process_rom_data(&psx_data, "game.iso")?;
+        assert_eq!(psx.video_standard(), VideoSystem::Pal);
+        let psx = process_rom_data(&[0; 0x2000], "game.iso")?;
+        assert_eq!(psx.video_standard(), VideoSystem::Unknown);
+
+        let n64 = process_rom_data(&[0; 0x40], "game.n64")?;
+        assert_eq!(n64.video_standard(), VideoSystem::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_bitmask_snes_usa() -> Result<(), RomAnalyzerError> {
+        let mut data = vec![0; 0x8000];
+        data[0x7FC0 + 0x19] = 0x01; // USA / Canada
+        let analysis = process_rom_data(&data, "game.sfc")?;
+
+        assert_eq!(analysis.region(), "USA / Canada (NTSC)");
+        assert_eq!(analysis.region_bitmask(), Region::USA);
+        assert!(analysis.region_bitmask().contains(Region::USA));
+        assert!(
+            !analysis
+                .region_bitmask()
+                .intersects(Region::JAPAN | Region::EUROPE)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_every_variant() -> Result<(), RomAnalyzerError> {
+        let mut nes_data = vec![0; 16];
+        nes_data[0..4].copy_from_slice(b"NES\x1a");
+        let mut genesis_data = vec![0; 0x200];
+        genesis_data[0x100..0x110].copy_from_slice(TEST_SEGA_GENESIS_HEADER);
+        let mut neo_data = vec![0; 0x1000];
+        neo_data[0..3].copy_from_slice(b"NEO");
+        let mut threedo_data = vec![0; 0x2000];
+        threedo_data[0..7].copy_from_slice(b"\x01ZZZZZ\x01");
+        let psp_data = psp::tests::generate_psp_image(&[], b"");
+        let cd_image_data = cdimage::tests::generate_cd_image(0x8000, "", "DISC", "");
+        let a78_data = atari7800::tests::generate_a78_rom("GAME", 0x00, 0);
+
+        let test_cases = vec![
+            (vec![0; 0x100], "game.gg", "GameGear"),
+            (vec![0; 0x150], "game.gb", "GB"),
+            (vec![0; 0xC0], "game.gba", "GBA"),
+            (genesis_data, "game.md", "Genesis"),
+            (vec![0; 0x28], "game.int", "Intellivision"),
+            (vec![0; 0x7FFD], "game.sms", "MasterSystem"),
+            (vec![0; 0x40], "game.n64", "N64"),
+            (neo_data, "game.neo", "NeoGeo"),
+            (nes_data, "game.nes", "NES"),
+            (psp_data, "game.iso", "PSP"),
+            (vec![0; 0x2000], "game.iso", "PSX"),
+            (vec![0; 0x200], "game.scd", "SegaCD"),
+            (vec![0; 0x7FE0], "game.sfc", "SNES"),
+            (threedo_data, "game.iso", "ThreeDO"),
+            (cd_image_data, "game.iso", "CdImage"),
+            (a78_data, "game.a78", "Atari7800"),
+        ];
+        for (data, name, expected_tag) in test_cases {
+            let analysis = process_rom_data(&data, name)?;
+            let json = serde_json::to_value(&analysis)
+                .map_err(|e| RomAnalyzerError::new(&e.to_string()))?;
+
+            assert_eq!(json["console"], expected_tag, "Failed for {}", name);
+            assert_eq!(json["source_name"], name, "Failed for {}", name);
+            assert_eq!(
+                json["region_string"],
+                analysis.region(),
+                "Failed for {}",
+                name
+            );
+            assert!(json["region"].is_string(), "Failed for {}", name);
+            assert!(json["region_mismatch"].is_boolean(), "Failed for {}", name);
+            assert!(json["archive_source"].is_null(), "Failed for {}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_range() -> Result<(), RomAnalyzerError> {
+        let analysis = process_rom_data(&vec![0; 0x8000], "game.sfc")?;
+        assert_eq!(analysis.header_range(), Some(0x7FC0..0x7FE0));
+
+        let mut genesis_data = vec![0; 0x200];
+        genesis_data[0x100..0x110].copy_from_slice(TEST_SEGA_GENESIS_HEADER);
+        let analysis = process_rom_data(&genesis_data, "game.md")?;
+        assert_eq!(analysis.header_range(), Some(0x100..0x200));
+
+        let analysis = process_rom_data(&vec![0; 0x2000], "game.iso")?;
+        assert_eq!(analysis.header_range(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_console_name() -> Result<(), RomAnalyzerError> {
+        let mut nes_data = vec![0; 16];
+        nes_data[0..4].copy_from_slice(b"NES\x1a");
+        let mut genesis_data = vec![0; 0x200];
+        genesis_data[0x100..0x110].copy_from_slice(TEST_SEGA_GENESIS_HEADER);
+        let mut neo_data = vec![0; 0x1000];
+        neo_data[0..3].copy_from_slice(b"NEO");
+        let mut threedo_data = vec![0; 0x2000];
+        threedo_data[0..7].copy_from_slice(b"\x01ZZZZZ\x01");
+        let psp_data = psp::tests::generate_psp_image(&[], b"");
+        let cd_image_data = cdimage::tests::generate_cd_image(0x8000, "", "DISC", "");
+        let a78_data = atari7800::tests::generate_a78_rom("GAME", 0x00, 0);
+
+        let test_cases = vec![
+            (vec![0; 0x100], "game.gg", "Sega Game Gear"),
+            (vec![0; 0x150], "game.gb", "Game Boy"),
+            (vec![0; 0xC0], "game.gba", "Game Boy Advance"),
+            (genesis_data, "game.md", "Sega Genesis"),
+            (vec![0; 0x28], "game.int", "Intellivision"),
+            (vec![0; 0x7FFD], "game.sms", "Sega Master System"),
+            (vec![0; 0x40], "game.n64", "N64"),
+            (neo_data, "game.neo", "Neo Geo"),
+            (nes_data, "game.nes", "NES"),
+            (psp_data, "game.iso", "PSP"),
+            (vec![0; 0x2000], "game.iso", "PlayStation"),
+            (vec![0; 0x200], "game.scd", "Sega CD"),
+            (vec![0; 0x7FE0], "game.sfc", "SNES"),
+            (threedo_data, "game.iso", "3DO"),
+            (cd_image_data, "game.iso", "CD Image"),
+            (a78_data, "game.a78", "Atari 7800"),
+        ];
+        for (data, name, expected_console) in test_cases {
+            let analysis = process_rom_data(&data, name)?;
+            assert_eq!(
+                analysis.console_name(),
+                expected_console,
+                "Failed for {}",
+                name
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_title() -> Result<(), RomAnalyzerError> {
+        let mut gb_data = vec![0; 0x150];
+        gb_data[0x134..0x13D].copy_from_slice(b"GAMETITLE");
+        let gb = process_rom_data(&gb_data, "game.gb")?;
+        assert_eq!(gb.game_title(), Some("GAMETITLE".to_string()));
+
+        let mut genesis_data = vec![0; 0x200];
+        genesis_data[0x100..0x110].copy_from_slice(TEST_SEGA_MEGA_DRIVE_HEADER);
+        genesis_data[0x120..0x128].copy_from_slice(b"DOMESTIC");
+        genesis_data[0x150..0x15D].copy_from_slice(b"INTERNATIONAL");
+        let genesis = process_rom_data(&genesis_data, "game.md")?;
+        assert_eq!(genesis.game_title(), Some("INTERNATIONAL".to_string()));
+
+        let mut nes_data = vec![0; 16];
+        nes_data[0..4].copy_from_slice(b"NES\x1a");
+        let nes = process_rom_data(&nes_data, "game.nes")?;
+        assert_eq!(nes.game_title(), None);
+
+        let psx = process_rom_data(&[0; 0x2000], "game.iso")?;
+        assert_eq!(psx.game_title(), None);
+        Ok(())
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_analyze_plain_file_mmap_matches_read() -> Result<(), RomAnalyzerError> {
+        use std::io::Write;
+
+        // A sparse 64MB N64 image, only the header is actually written to disk.
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("large (E).z64");
+        let mut file = File::create(&path)?;
+        let mut header = vec![0; 0x40];
+        header[0x3E] = b'P';
+        file.write_all(&header)?;
+        file.set_len(64 * 1024 * 1024)?;
+        let path = path.to_str().unwrap();
+
+        let mapped = map_file(&File::open(path)?).expect("Failed to memory-map the test ROM");
+        assert_eq!(mapped.len(), 64 * 1024 * 1024);
+
+        let analysis = analyze_plain_file(path, &AnalysisOptions::default())?;
+        assert_eq!(analysis, process_rom_data(&fs::read(path)?, path)?);
+        assert_eq!(analysis.region(), "Europe (PAL)");
+        Ok(())
+    }
+
     #[test]
     fn test_analyze_rom_data_chd() {
         let dir = tempdir().unwrap();
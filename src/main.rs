@@ -1,13 +1,30 @@
-use std::path::Path;
-
-use clap::{ArgAction, Parser};
-use log::{LevelFilter, error, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use clap::{ArgAction, Parser, ValueEnum};
+use log::{LevelFilter, debug, error, warn};
+use rayon::iter::Either;
 use rayon::prelude::*;
+use serde::Serialize;
 use walkdir::WalkDir;
 
+use rom_analyzer::archive::cue::cue_track_paths;
+use rom_analyzer::console::{genesis, nes};
 use rom_analyzer::error::RomAnalyzerError;
-use rom_analyzer::region::infer_region_from_filename;
-use rom_analyzer::{RomAnalysisResult, analyze_rom_data};
+use rom_analyzer::hash::{Hashes, hash_file};
+use rom_analyzer::multicart::scan_multicart;
+use rom_analyzer::region::{MismatchMode, infer_region_from_filename};
+use rom_analyzer::xml::to_xml_string;
+use rom_analyzer::{
+    AnalysisOptions, RomAnalysisResult, RomFileType, analyze_path,
+    analyze_rom_data_multi_with_options, is_rom_file,
+};
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -20,14 +37,23 @@ struct Cli {
     #[clap(short, action = ArgAction::Count)]
     verbose: u8,
 
-    /// Silence all output except errors
+    /// Silence all diagnostics except errors (results are still printed)
     #[clap(short, long, action = ArgAction::SetTrue)]
     quiet: bool,
 
-    /// Format output as JSON (suppresses everything except STDERR)
+    /// Format output as JSON, with errors in an "errors" array (suppresses everything except STDERR)
     #[clap(short, long, action = ArgAction::SetTrue)]
     json: bool,
 
+    /// Format output as XML (suppresses everything except STDERR)
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with = "json")]
+    xml: bool,
+
+    /// Print each result as a single-line JSON object as soon as it's analyzed (NDJSON), in no
+    /// particular order. Errors only go to STDERR
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with_all = ["json", "xml"])]
+    json_lines: bool,
+
     /// Number of threads to use for parallel processing (0 or omitted uses all available threads)
     #[clap(long, value_name = "N")]
     threads: Option<usize>,
@@ -35,6 +61,210 @@ struct Cli {
     /// Recursively process directories for ROM files
     #[clap(short, long, action = ArgAction::SetTrue)]
     recursive: bool,
+
+    /// Process every file found in directories, not only those with a ROM or archive extension
+    #[clap(long, action = ArgAction::SetTrue)]
+    all_files: bool,
+
+    /// Don't follow symlinks found while walking directories (paths given explicitly are still
+    /// followed)
+    #[clap(long, action = ArgAction::SetTrue)]
+    no_follow_symlinks: bool,
+
+    /// Skip files and directories whose name matches a glob pattern (`*` and `?` wildcards, e.g.
+    /// "BIOS" or "*.sav"). Can be repeated
+    #[clap(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+
+    /// Analyze every ROM inside ZIP archives instead of only the largest one
+    #[clap(long, action = ArgAction::SetTrue)]
+    all_in_archive: bool,
+
+    /// Report files no analyzer recognizes as "Unknown" results (with the region inferred from
+    /// their filename) instead of errors
+    #[clap(long, action = ArgAction::SetTrue)]
+    include_unknown: bool,
+
+    /// When to warn about a region mismatch: "off", "strict" (the filename and header regions
+    /// share nothing) or "exact" (they differ at all)
+    #[clap(long, value_name = "MODE", default_value = "strict", value_parser = parse_mismatch_mode)]
+    mismatch_mode: MismatchMode,
+
+    /// Warn about SNES ROMs with the "Common / International" region code whose filename names a
+    /// single region
+    #[clap(long, action = ArgAction::SetTrue)]
+    strict_world: bool,
+
+    /// Reject suspicious headers instead of only warning about them: NES ROMs without an iNES
+    /// header, SNES ROMs without a valid checksum and unexpected Genesis or Sega CD signatures
+    #[clap(long, action = ArgAction::SetTrue)]
+    strict: bool,
+
+    /// Read at most this many megabytes of each non-archive file for analysis
+    #[clap(long, value_name = "MB")]
+    max_file_size: Option<usize>,
+
+    /// Analyze every file as this console, bypassing detection by extension and content (e.g. for
+    /// misnamed files or ambiguous .bin dumps)
+    #[clap(
+        long,
+        value_name = "CONSOLE",
+        value_enum,
+        conflicts_with = "all_in_archive"
+    )]
+    force_type: Option<ForceType>,
+
+    /// Only print the CRC32 and SHA1 of each file, skipping header analysis
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with_all = ["json", "xml", "json_lines"])]
+    hash_only: bool,
+
+    /// Only list groups of files with identical contents (by SHA1), skipping header analysis
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with_all = ["json", "xml", "json_lines", "hash_only"])]
+    find_duplicates: bool,
+
+    /// Periodically print the number of processed files to STDERR (ignored with --quiet)
+    #[clap(long, action = ArgAction::SetTrue)]
+    progress: bool,
+
+    /// Write the results to this file instead of STDOUT (errors still go to STDERR)
+    #[clap(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Print a hexdump of the header bytes each analysis was read from
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with_all = ["json", "xml", "json_lines", "hash_only", "group_by"])]
+    dump_header: bool,
+
+    /// Scan NES and Genesis ROMs for concatenated games and list each one found
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with_all = ["json", "xml", "json_lines", "hash_only", "group_by"])]
+    multicart: bool,
+
+    /// List the files that would be analyzed without reading them
+    #[clap(long, action = ArgAction::SetTrue, conflicts_with_all = ["json", "xml", "hash_only"])]
+    dry_run: bool,
+
+    /// Print the number of results per console, region or continent instead of each result (each
+    /// result is still printed with -v)
+    #[clap(long, value_name = "KEY", value_enum, conflicts_with_all = ["json", "xml", "json_lines", "hash_only"])]
+    group_by: Option<GroupBy>,
+}
+
+/// What the results are counted by with --group-by.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum GroupBy {
+    /// The console name, see [`RomAnalysisResult::console_name`].
+    Console,
+    /// The header region, see [`RomAnalysisResult::region_bitmask`].
+    Region,
+    /// The continent of the header region, see [`rom_analyzer::region::Region::continent`].
+    Continent,
+}
+
+/// The consoles files can be analyzed as with --force-type.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum ForceType {
+    Nes,
+    Snes,
+    N64,
+    MasterSystem,
+    GameGear,
+    GameBoy,
+    GameBoyAdvance,
+    Genesis,
+    SegaCd,
+    NeoGeo,
+    Intellivision,
+    Atari7800,
+    /// The CD-based systems, told apart by their content.
+    Cd,
+    /// GameCube and Wii discs.
+    Gamecube,
+}
+
+impl From<ForceType> for RomFileType {
+    fn from(force_type: ForceType) -> Self {
+        match force_type {
+            ForceType::Nes => RomFileType::Nes,
+            ForceType::Snes => RomFileType::Snes,
+            ForceType::N64 => RomFileType::N64,
+            ForceType::MasterSystem => RomFileType::MasterSystem,
+            ForceType::GameGear => RomFileType::GameGear,
+            ForceType::GameBoy => RomFileType::GameBoy,
+            ForceType::GameBoyAdvance => RomFileType::GameBoyAdvance,
+            ForceType::Genesis => RomFileType::Genesis,
+            ForceType::SegaCd => RomFileType::SegaCD,
+            ForceType::NeoGeo => RomFileType::NeoGeo,
+            ForceType::Intellivision => RomFileType::Intellivision,
+            ForceType::Atari7800 => RomFileType::Atari7800,
+            ForceType::Cd => RomFileType::CDSystem,
+            ForceType::Gamecube => RomFileType::GameCube,
+        }
+    }
+}
+
+/// How directories given on the command line are walked for files to process.
+#[derive(Clone, Copy)]
+struct WalkOptions<'a> {
+    /// Walk directories, instead of skipping them with a warning.
+    recursive: bool,
+    /// Keep every file found, not only those with a supported extension.
+    all_files: bool,
+    /// Follow symlinks to files and directories found within walked directories.
+    follow_symlinks: bool,
+    /// Glob patterns for the names of files and directories to skip, see [`is_excluded`].
+    exclude: &'a [String],
+}
+
+impl<'a> WalkOptions<'a> {
+    fn from_cli(cli: &'a Cli) -> Self {
+        WalkOptions {
+            recursive: cli.recursive,
+            all_files: cli.all_files,
+            follow_symlinks: !cli.no_follow_symlinks,
+            exclude: &cli.exclude,
+        }
+    }
+}
+
+/// Matches a name against a glob pattern, where `*` matches any run of characters (including
+/// none) and `?` matches a single character. All other characters match themselves.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // The pattern position after the last `*` seen, and the name position it was tried at.
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character and retry from there.
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Checks whether a file or directory name matches any of the --exclude patterns.
+fn is_excluded(name: &OsStr, exclude: &[String]) -> bool {
+    let name = name.to_string_lossy();
+    exclude.iter().any(|pattern| glob_matches(pattern, &name))
+}
+
+fn parse_mismatch_mode(mode: &str) -> Result<MismatchMode, String> {
+    mode.parse().map_err(|e: RomAnalyzerError| e.to_string())
 }
 
 fn get_log_level(quiet: bool, verbose: u8) -> LevelFilter {
@@ -49,67 +279,571 @@ fn get_log_level(quiet: bool, verbose: u8) -> LevelFilter {
     }
 }
 
-/// Recursively expands directory paths into a list of file paths.
-/// If recursive is false, directories are skipped with a warning.
-/// Uses walkdir to handle edge cases like circular symbolic links gracefully.
-fn expand_paths(paths: &[String], recursive: bool) -> Vec<String> {
-    let mut found_files = std::collections::BTreeSet::new();
-    for path_str in paths {
-        let path = Path::new(path_str);
-        if path.is_dir() {
-            if recursive {
-                for node_result in WalkDir::new(path) {
-                    match node_result {
-                        Ok(entry) => {
-                            if entry.file_type().is_file()
-                                && let Some(entry_path_str) = entry.path().to_str()
-                            {
-                                found_files.insert(entry_path_str.to_string());
-                            }
-                        }
-                        Err(e) => warn!("Error walking directory: {}", e),
-                    }
-                }
-            } else {
+/// Recursively expands directory paths into a parallel stream of unique file paths.
+/// If walk.recursive is false, directories are skipped with a warning.
+/// Uses walkdir to handle edge cases like circular symbolic links gracefully: a followed symlink
+/// leading back to one of its own ancestors is reported as a warning and not descended into.
+/// Unless walk.follow_symlinks is set, symlinks found within directories are skipped, while
+/// symlinks given explicitly are always followed.
+/// Unless walk.all_files is set, files found in directories are only kept if they have a
+/// supported extension (see [`is_rom_file`]) or no extension at all, as those may be identified
+/// by content.
+/// Paths given explicitly are always kept.
+/// Files and directories whose name matches one of walk.exclude are skipped, directories along
+/// with everything below them. This applies to the file name of paths given explicitly too.
+/// With group_cue_tracks set, files in a directory that a CUE sheet in the same directory
+/// references are skipped, so a bin/cue image is analyzed once through its sheet.
+/// Directories are walked lazily and bridged into rayon, so analysis of the first files can
+/// start while the rest of the tree is still being walked. Paths are yielded in no particular
+/// order, duplicates are dropped through a shared set.
+fn walk_paths<'a>(
+    paths: &'a [String],
+    walk: WalkOptions<'a>,
+    group_cue_tracks: bool,
+) -> impl ParallelIterator<Item = String> + 'a {
+    let seen = Mutex::new(HashSet::new());
+    paths
+        .iter()
+        .flat_map(move |path_str| -> Box<dyn Iterator<Item = String> + Send> {
+            let path = Path::new(path_str);
+            if path
+                .file_name()
+                .is_some_and(|name| is_excluded(name, walk.exclude))
+            {
+                debug!("Skipping excluded path {}", path.display());
+                return Box::new(std::iter::empty());
+            }
+            if !path.is_dir() {
+                return Box::new(std::iter::once(path_str.clone()));
+            }
+            if !walk.recursive {
                 warn!(
                     "Skipping directory {} (use -r for recursion)",
                     path.display()
                 );
+                return Box::new(std::iter::empty());
             }
+            // The tracks referenced by the CUE sheets of each directory seen so far.
+            let mut cue_tracks: HashMap<PathBuf, HashSet<PathBuf>> = HashMap::new();
+            let walker = WalkDir::new(path).follow_links(walk.follow_symlinks);
+            Box::new(
+                walker
+                    .into_iter()
+                    // The root was checked above, pruning excluded directories skips their walk.
+                    .filter_entry(move |entry| {
+                        entry.depth() == 0 || !is_excluded(entry.file_name(), walk.exclude)
+                    })
+                    .filter_map(move |node_result| match node_result {
+                        Ok(entry) => {
+                            if entry.file_type().is_file() {
+                                if group_cue_tracks
+                                    && let Some(dir) = entry.path().parent()
+                                    && cue_tracks
+                                        .entry(dir.to_path_buf())
+                                        .or_insert_with(|| cue_tracks_in_dir(dir))
+                                        .contains(entry.path())
+                                {
+                                    return None;
+                                }
+                                entry
+                                    .path()
+                                    .to_str()
+                                    .filter(|file_path| {
+                                        walk.all_files || is_walked_rom_file(file_path)
+                                    })
+                                    .map(str::to_string)
+                            } else {
+                                None
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Error walking directory: {}", e);
+                            None
+                        }
+                    }),
+            )
+        })
+        .par_bridge()
+        .filter(move |file_path| {
+            seen.lock()
+                .map(|mut seen| seen.insert(file_path.clone()))
+                .unwrap_or(true)
+        })
+}
+
+/// Collects the track files referenced by the CUE sheets directly within a directory.
+/// Sheets that cannot be read are ignored here, the error surfaces when they are analyzed.
+fn cue_tracks_in_dir(dir: &Path) -> HashSet<PathBuf> {
+    let Ok(entries) = dir.read_dir() else {
+        return HashSet::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("cue"))
+        })
+        .filter_map(|cue_path| cue_track_paths(&cue_path).ok())
+        .flatten()
+        .collect()
+}
+
+/// Walks the given paths and collects the files that would be analyzed, sorted by path.
+/// Used by --dry-run, no file is opened.
+fn collect_paths(paths: &[String], walk: WalkOptions<'_>) -> Vec<String> {
+    let mut collected: Vec<String> = walk_paths(paths, walk, true).collect();
+    collected.par_sort();
+    collected
+}
+
+/// Checks whether a file found while walking a directory should be analyzed.
+fn is_walked_rom_file(file_path: &str) -> bool {
+    is_rom_file(file_path) || Path::new(file_path).extension().is_none()
+}
+
+/// Analyzes every ROM within a single file, attaching its path to any error for context.
+/// Files other than ZIP archives hold a single ROM, and are analyzed as by [`analyze_path`].
+fn analyze_file_multi(
+    file_path: &str,
+    options: AnalysisOptions,
+) -> Vec<Result<RomAnalysisResult, RomAnalyzerError>> {
+    let is_zip = Path::new(file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if !is_zip {
+        return vec![analyze_path(file_path, &options)];
+    }
+    match analyze_rom_data_multi_with_options(file_path, &options) {
+        Ok(analyses) => analyses.into_iter().map(Ok).collect(),
+        Err(e) => vec![Err(e.with_path(file_path))],
+    }
+}
+
+/// Returns the input path a result was produced for.
+/// Analyses of archives report the archive path, which is the path that was passed in.
+fn result_path(result: &Result<RomAnalysisResult, RomAnalyzerError>) -> &str {
+    match result {
+        Ok(analysis) => analysis
+            .archive_source()
+            .unwrap_or_else(|| analysis.source_name()),
+        Err(RomAnalyzerError::FileNotFound(path)) | Err(RomAnalyzerError::WithPath(path, _)) => {
+            path
+        }
+        Err(_) => "",
+    }
+}
+
+/// An analysis failure as reported in the JSON output.
+#[derive(Debug, PartialEq, Serialize)]
+struct ErrorEntry {
+    /// The input path the error was produced for.
+    path: String,
+    /// The error message, without the path.
+    message: String,
+    /// The stable error kind from [`RomAnalyzerError::kind_str`].
+    kind: &'static str,
+}
+
+impl ErrorEntry {
+    fn from_error(e: &RomAnalyzerError) -> ErrorEntry {
+        let (path, inner) = match e {
+            RomAnalyzerError::WithPath(path, inner) => (path.as_str(), inner.as_ref()),
+            RomAnalyzerError::FileNotFound(path) => (path.as_str(), e),
+            _ => ("", e),
+        };
+        ErrorEntry {
+            path: path.to_string(),
+            message: inner.to_string(),
+            kind: e.kind_str(),
+        }
+    }
+}
+
+/// The JSON output, holding both the successful analyses and the errors.
+#[derive(Debug, Serialize)]
+struct JsonReport<'a> {
+    results: &'a [RomAnalysisResult],
+    errors: Vec<ErrorEntry>,
+}
+
+/// The format the results are written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Xml,
+}
+
+impl OutputFormat {
+    fn from_cli(cli: &Cli) -> Self {
+        if cli.json {
+            OutputFormat::Json
+        } else if cli.xml {
+            OutputFormat::Xml
         } else {
-            found_files.insert(path_str.clone());
+            OutputFormat::Text
         }
     }
-    found_files.into_iter().collect()
 }
 
-/// Processes a list of file paths in parallel, returning a vector of results.
-/// Each result is an analysis on success, or a RomAnalyzerError on failure.
-/// Results are returned in the same order as the input file paths.
-fn process_files_parallel(
-    file_paths: &[String],
-) -> Vec<Result<RomAnalysisResult, RomAnalyzerError>> {
-    file_paths
-        .par_iter()
-        .map(|file_path| match analyze_rom_data(file_path) {
-            Ok(analysis) => Ok(analysis),
-            Err(e) => {
-                // Convert NotFound IO errors to FileNotFound (no wrapping needed, path is included,)
-                // Wrap other errors with WithPath for context.
-                let err = match e {
-                    RomAnalyzerError::IoError(io_err)
-                        if io_err.kind() == std::io::ErrorKind::NotFound =>
-                    {
-                        RomAnalyzerError::FileNotFound(file_path.clone())
+/// Renders a single analysis in the given format. Results are data rather than diagnostics, so
+/// this is independent of the log level and --quiet never hides them.
+fn render_result(analysis: &RomAnalysisResult, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => format!("{}\n", analysis.print()),
+        OutputFormat::Json => serde_json::to_string_pretty(analysis)
+            .map(|json| json + "\n")
+            .unwrap_or_else(|e| format!("Error serializing JSON output: {}\n", e)),
+        OutputFormat::Xml => to_xml_string(std::slice::from_ref(analysis)),
+    }
+}
+
+/// Renders the unrecognized region byte of an analysis, printed with -vv, e.g.
+/// "Raw Region:   0xFF at offset 0x7FD9". Returns an empty string if the region was recognized.
+fn render_raw_region_info(analysis: &RomAnalysisResult) -> String {
+    analysis
+        .raw_region_info()
+        .map(|(offset, byte)| {
+            format!(
+                "{:<13} 0x{:02X} at offset 0x{:X}\n",
+                "Raw Region:", byte, offset
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Counts the results per console, region or continent, ordered by descending count, then by name.
+fn group_counts(results: &[RomAnalysisResult], group_by: GroupBy) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for analysis in results {
+        let key = match group_by {
+            GroupBy::Console => analysis.console_name().to_string(),
+            GroupBy::Region => analysis.region_bitmask().to_string(),
+            GroupBy::Continent => analysis.region_bitmask().continent().to_string(),
+        };
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a_key, a_count), (b_key, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_key.cmp(b_key))
+    });
+    counts
+}
+
+/// Renders the counts from [`group_counts`] as a single line, e.g. "SNES: 412, Genesis: 203".
+fn render_group_counts(counts: &[(String, usize)]) -> String {
+    let groups: Vec<String> = counts
+        .iter()
+        .map(|(key, count)| format!("{}: {}", key, count))
+        .collect();
+    format!("{}\n", groups.join(", "))
+}
+
+/// Groups hashed files by their SHA1, keeping only the groups of more than one file.
+///
+/// The groups are ordered by their first path, and the paths within a group keep their input
+/// order.
+fn find_duplicates(hashed_files: &[(String, Hashes)]) -> Vec<(&Hashes, Vec<&str>)> {
+    let mut groups: Vec<(&Hashes, Vec<&str>)> = Vec::new();
+    let mut group_index: HashMap<&str, usize> = HashMap::new();
+    for (file_path, hashes) in hashed_files {
+        let index = *group_index.entry(&hashes.sha1).or_insert_with(|| {
+            groups.push((hashes, Vec::new()));
+            groups.len() - 1
+        });
+        groups[index].1.push(file_path);
+    }
+    groups.retain(|(_, paths)| paths.len() > 1);
+    groups.sort_by(|(_, a), (_, b)| a[0].cmp(b[0]));
+    groups
+}
+
+/// Renders the groups from [`find_duplicates`], listing the hashes of each group followed by its
+/// indented paths.
+fn render_duplicates(groups: &[(&Hashes, Vec<&str>)]) -> String {
+    groups
+        .iter()
+        .map(|(hashes, paths)| {
+            let listing: String = paths.iter().map(|path| format!("  {}\n", path)).collect();
+            format!(
+                "Duplicates ({} files): {}\n{}",
+                paths.len(),
+                hashes,
+                listing
+            )
+        })
+        .collect()
+}
+
+/// Formats bytes as a hexdump, with 16 bytes per line prefixed by their offset and followed by
+/// their printable ASCII characters.
+fn format_hexdump(data: &[u8], base_offset: usize) -> String {
+    data.chunks(16)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02X}", byte)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
                     }
-                    other => RomAnalyzerError::WithPath(file_path.clone(), Box::new(other)),
-                };
-                Err(err)
-            }
+                })
+                .collect();
+            format!(
+                "{:08X}  {:<47}  |{}|\n",
+                base_offset + i * 16,
+                hex.join(" "),
+                ascii
+            )
         })
         .collect()
 }
 
+/// Reads the header bytes of an analyzed ROM and renders them for --dump-header.
+///
+/// Only plain files can be dumped, as the header of a ROM read from an archive isn't at a known
+/// offset of a file on disk.
+fn render_header_dump(analysis: &RomAnalysisResult) -> Result<String, RomAnalyzerError> {
+    if let Some(archive) = analysis.archive_source() {
+        return Err(RomAnalyzerError::new(&format!(
+            "Can't dump the header of a ROM read from {}.",
+            archive
+        )));
+    }
+    let range = analysis.header_range().ok_or_else(|| {
+        RomAnalyzerError::new(&format!(
+            "No fixed header location is known for this {} ROM.",
+            analysis.console_name()
+        ))
+    })?;
+    let mut file = File::open(analysis.source_name())?;
+    file.seek(SeekFrom::Start(range.start as u64))?;
+    let mut header = Vec::new();
+    file.take(range.len() as u64).read_to_end(&mut header)?;
+    Ok(format!(
+        "Header (0x{:X}..0x{:X}):\n{}",
+        range.start,
+        range.end,
+        format_hexdump(&header, range.start)
+    ))
+}
+
+/// Reads an analyzed NES or Genesis ROM and renders the games it concatenates for --multicart.
+///
+/// Returns an empty String for ROMs holding a single game and for other consoles, which aren't
+/// scanned.
+fn render_multicart(analysis: &RomAnalysisResult) -> Result<String, RomAnalyzerError> {
+    if !matches!(
+        analysis,
+        RomAnalysisResult::NES(_) | RomAnalysisResult::Genesis(_)
+    ) {
+        return Ok(String::new());
+    }
+    if let Some(archive) = analysis.archive_source() {
+        return Err(RomAnalyzerError::new(&format!(
+            "Can't scan a ROM read from {} for multicart games.",
+            archive
+        )));
+    }
+    let data = std::fs::read(analysis.source_name())?;
+    let offsets = scan_multicart(&data);
+    if offsets.len() < 2 {
+        return Ok(String::new());
+    }
+    let mut output = format!("Multicart:    {} games\n", offsets.len());
+    for (i, &start) in offsets.iter().enumerate() {
+        let end = offsets.get(i + 1).copied().unwrap_or(data.len());
+        let source_name = format!("{} @ 0x{:X}", analysis.source_name(), start);
+        let game = match analysis {
+            RomAnalysisResult::Genesis(_) => RomAnalysisResult::Genesis(
+                genesis::analyze_genesis_data(&data[start..end], &source_name)?,
+            ),
+            _ => RomAnalysisResult::NES(nes::analyze_nes_data(&data[start..end], &source_name)?),
+        };
+        output.push_str(&render_result(&game, OutputFormat::Text));
+    }
+    Ok(output)
+}
+
+/// Renders the JSON output, holding both the successful analyses and the errors.
+fn render_json(
+    results: &[RomAnalysisResult],
+    errors: Vec<ErrorEntry>,
+) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&JsonReport { results, errors }).map(|json| json + "\n")
+}
+
+/// Renders a result as a single line of JSON for --json-lines.
+fn render_json_line(analysis: &RomAnalysisResult) -> serde_json::Result<String> {
+    serde_json::to_string(analysis).map(|json| json + "\n")
+}
+
+/// Writes each result to `writer` as a line of JSON as soon as it's produced, for --json-lines.
+/// Errors are logged instead. Returns whether any result failed or couldn't be written.
+fn stream_json_lines<W: Write + Send>(
+    results: impl ParallelIterator<Item = Result<RomAnalysisResult, RomAnalyzerError>>,
+    mismatch_mode: MismatchMode,
+    writer: &Mutex<W>,
+) -> bool {
+    let had_error = AtomicBool::new(false);
+    results.for_each(|result| {
+        let written = result.and_then(|analysis| {
+            let line = render_json_line(&analysis.with_mismatch_mode(mismatch_mode))
+                .map_err(|e| RomAnalyzerError::new(&format!("Error serializing JSON: {}", e)))?;
+            let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+            writer.write_all(line.as_bytes())?;
+            writer.flush()?;
+            Ok(())
+        });
+        if let Err(e) = written {
+            error!("{}", e);
+            had_error.store(true, Ordering::Relaxed);
+        }
+    });
+    had_error.into_inner()
+}
+
+/// Writes the rendered results to the --output file, or to STDOUT without one.
+fn write_output(output: Option<&Path>, contents: &str) -> std::io::Result<()> {
+    match output {
+        Some(path) => std::fs::write(path, contents),
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(contents.as_bytes())?;
+            stdout.flush()
+        }
+    }
+}
+
+/// The minimum time between two --progress lines.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Counts processed files for --progress and throttles the lines reporting them.
+/// The counter is atomic so that it can be shared by the rayon workers.
+struct Progress {
+    total: AtomicUsize,
+    processed: AtomicUsize,
+    last_report: Mutex<Instant>,
+    interval: Duration,
+}
+
+impl Progress {
+    fn new(interval: Duration) -> Self {
+        Progress {
+            total: AtomicUsize::new(0),
+            processed: AtomicUsize::new(0),
+            last_report: Mutex::new(Instant::now()),
+            interval,
+        }
+    }
+
+    /// Records a processed file, returning a progress line if the interval has passed since the
+    /// last one. The last file always produces a line, so the final count is never skipped.
+    fn file_processed(&self) -> Option<String> {
+        let processed = self.processed.fetch_add(1, Ordering::Relaxed) + 1;
+        let total = self.total.load(Ordering::Relaxed);
+        let mut last_report = self.last_report.lock().ok()?;
+        let now = Instant::now();
+        if processed < total && now.duration_since(*last_report) < self.interval {
+            return None;
+        }
+        *last_report = now;
+        Some(format!("{}/{} files processed", processed, total))
+    }
+
+    /// Records a processed file and prints the progress line to STDERR if one is due.
+    fn report(&self) {
+        if let Some(line) = self.file_processed() {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+/// Passes walked paths through as they are found, unless progress is reported. The walk then
+/// completes first, so the total number of files is known before the first one is processed.
+fn with_progress_total<'a>(
+    files: impl ParallelIterator<Item = String> + 'a,
+    progress: Option<&Progress>,
+) -> impl ParallelIterator<Item = String> + 'a {
+    match progress {
+        Some(progress) => {
+            let files: Vec<String> = files.collect();
+            progress.total.store(files.len(), Ordering::Relaxed);
+            Either::Left(files.into_par_iter())
+        }
+        None => Either::Right(files),
+    }
+}
+
+/// Walks and analyzes the given paths concurrently, yielding each result as soon as it's
+/// produced. With `all_in_archive` set, every ROM within a ZIP archive is analyzed and reported
+/// in archive order. Each file is analyzed with the given options.
+/// Each processed file is counted in progress, if given.
+fn analyze_paths_parallel<'a>(
+    paths: &'a [String],
+    walk: WalkOptions<'a>,
+    all_in_archive: bool,
+    options: AnalysisOptions,
+    progress: Option<&'a Progress>,
+) -> impl ParallelIterator<Item = Result<RomAnalysisResult, RomAnalyzerError>> + 'a {
+    let files = walk_paths(paths, walk, true);
+    with_progress_total(files, progress).flat_map_iter(move |file_path| {
+        let results = if all_in_archive {
+            analyze_file_multi(&file_path, options)
+        } else {
+            vec![analyze_path(&file_path, &options)]
+        };
+        if let Some(progress) = progress {
+            progress.report();
+        }
+        results
+    })
+}
+
+/// Walks and analyzes the given paths concurrently, returning a vector of results.
+/// Files are analyzed as soon as they are found, so results are sorted by path at the end to
+/// keep the output deterministic. See [`analyze_paths_parallel`] for the other arguments.
+fn process_paths_parallel(
+    paths: &[String],
+    walk: WalkOptions<'_>,
+    all_in_archive: bool,
+    options: AnalysisOptions,
+    progress: Option<&Progress>,
+) -> Vec<Result<RomAnalysisResult, RomAnalyzerError>> {
+    let mut results: Vec<_> =
+        analyze_paths_parallel(paths, walk, all_in_archive, options, progress).collect();
+    if results.len() > 1 {
+        results.par_sort_by(|a, b| result_path(a).cmp(result_path(b)));
+    }
+    results
+}
+
+/// Walks and hashes the given paths concurrently, without running any console analyzers.
+/// Results are paired with their path and sorted by it to keep the output deterministic.
+/// Each hashed file is counted in progress, if given.
+fn hash_paths_parallel(
+    paths: &[String],
+    walk: WalkOptions<'_>,
+    progress: Option<&Progress>,
+) -> Vec<(String, Result<Hashes, RomAnalyzerError>)> {
+    // Every track is hashed on its own, as DAT files list them individually.
+    let files = walk_paths(paths, walk, false);
+    let mut results: Vec<_> = with_progress_total(files, progress)
+        .map(|file_path| {
+            let hashes = hash_file(Path::new(&file_path));
+            if let Some(progress) = progress {
+                progress.report();
+            }
+            (file_path, hashes)
+        })
+        .collect();
+    results.par_sort_by(|a, b| a.0.cmp(&b.0));
+    results
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -135,20 +869,118 @@ fn main() {
         .format_target(false)
         .init();
 
+    if cli.dry_run {
+        for file_path in collect_paths(&cli.file_paths, WalkOptions::from_cli(&cli)) {
+            println!("{}", file_path);
+        }
+        return;
+    }
+
+    let progress = (cli.progress && !cli.quiet).then(|| Progress::new(PROGRESS_INTERVAL));
+    let format = OutputFormat::from_cli(&cli);
     let mut had_error = false;
+    // The results are rendered in full, then written to the --output file or STDOUT. They don't
+    // go through the logger, which is reserved for diagnostics.
+    let mut output = String::new();
+
+    if cli.hash_only || cli.find_duplicates {
+        let mut hashed_files = Vec::new();
+        for (file_path, result) in hash_paths_parallel(
+            &cli.file_paths,
+            WalkOptions::from_cli(&cli),
+            progress.as_ref(),
+        ) {
+            match result {
+                Ok(hashes) if cli.find_duplicates => hashed_files.push((file_path, hashes)),
+                Ok(hashes) => output.push_str(&format!("{} {}\n", file_path, hashes)),
+                Err(e) => {
+                    error!("{}", RomAnalyzerError::WithPath(file_path, Box::new(e)));
+                    had_error = true;
+                }
+            }
+        }
+        if cli.find_duplicates {
+            output = render_duplicates(&find_duplicates(&hashed_files));
+        }
+        if let Err(e) = write_output(cli.output.as_deref(), &output) {
+            eprintln!("Error writing output: {}", e);
+            had_error = true;
+        }
+        if had_error {
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    let mut json_results: Vec<RomAnalysisResult> = Vec::new();
+    let options = AnalysisOptions {
+        strict: cli.strict,
+        max_file_size: cli.max_file_size.map(|mb| mb.saturating_mul(1024 * 1024)),
+        force_type: cli.force_type.map(RomFileType::from),
+        include_unknown: cli.include_unknown,
+        strict_world: cli.strict_world,
+    };
+
+    if cli.json_lines {
+        let writer: Box<dyn Write + Send> = match cli.output.as_deref() {
+            Some(path) => match File::create(path) {
+                Ok(file) => Box::new(file),
+                Err(e) => {
+                    eprintln!("Error writing output: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            None => Box::new(std::io::stdout()),
+        };
+        let results = analyze_paths_parallel(
+            &cli.file_paths,
+            WalkOptions::from_cli(&cli),
+            cli.all_in_archive,
+            options,
+            progress.as_ref(),
+        );
+        if stream_json_lines(results, cli.mismatch_mode, &Mutex::new(writer)) {
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    let expanded_file_paths = expand_paths(&cli.file_paths, cli.recursive);
-    let results = process_files_parallel(&expanded_file_paths);
+    let mut structured_results: Vec<RomAnalysisResult> = Vec::new();
+    let mut structured_errors: Vec<ErrorEntry> = Vec::new();
+
+    let results = process_paths_parallel(
+        &cli.file_paths,
+        WalkOptions::from_cli(&cli),
+        cli.all_in_archive,
+        options,
+        progress.as_ref(),
+    );
 
     for result in results {
         match result {
             Ok(analysis) => {
-                if cli.json {
-                    json_results.push(analysis);
+                let analysis = analysis.with_mismatch_mode(cli.mismatch_mode);
+                if format != OutputFormat::Text {
+                    structured_results.push(analysis);
                 } else {
-                    info!("{}", analysis.print());
+                    // With --group-by, only the counts are printed unless -v is given.
+                    if cli.group_by.is_none() || cli.verbose > 0 {
+                        output.push_str(&render_result(&analysis, format));
+                        if cli.verbose >= 2 {
+                            output.push_str(&render_raw_region_info(&analysis));
+                        }
+                        if cli.dump_header {
+                            match render_header_dump(&analysis) {
+                                Ok(dump) => output.push_str(&dump),
+                                Err(e) => warn!("{}", e.with_path(analysis.source_name())),
+                            }
+                        }
+                        if cli.multicart {
+                            match render_multicart(&analysis) {
+                                Ok(games) => output.push_str(&games),
+                                Err(e) => warn!("{}", e.with_path(analysis.source_name())),
+                            }
+                        }
+                    }
                     if analysis.region_mismatch() {
                         let inferred_region = infer_region_from_filename(analysis.source_name());
                         warn!(
@@ -162,20 +994,35 @@ fn main() {
                             analysis.region(),
                         );
                     }
+                    if cli.group_by.is_some() {
+                        structured_results.push(analysis);
+                    }
                 }
             }
             Err(e) => {
                 error!("{}", e);
+                if format == OutputFormat::Json {
+                    structured_errors.push(ErrorEntry::from_error(&e));
+                }
                 had_error = true;
             }
         }
     }
 
-    if cli.json {
-        match serde_json::to_string_pretty(&json_results) {
-            Ok(json_output) => {
-                println!("{}", json_output);
-            }
+    if let Some(group_by) = cli.group_by {
+        output.push_str(&render_group_counts(&group_counts(
+            &structured_results,
+            group_by,
+        )));
+    }
+
+    if format == OutputFormat::Xml {
+        output = to_xml_string(&structured_results);
+    }
+
+    if format == OutputFormat::Json {
+        match render_json(&structured_results, structured_errors) {
+            Ok(json_output) => output = json_output,
             Err(e) => {
                 eprintln!("Error serializing combined JSON output: {}", e);
                 had_error = true;
@@ -183,6 +1030,11 @@ fn main() {
         }
     }
 
+    if let Err(e) = write_output(cli.output.as_deref(), &output) {
+        eprintln!("Error writing output: {}", e);
+        had_error = true;
+    }
+
     if had_error {
         std::process::exit(1);
     }
@@ -191,12 +1043,275 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rom_analyzer::analyze_paths;
     use std::fs;
     use tempfile::tempdir;
 
     const TEST_NES_HEADER: &[u8] =
         b"NES\x1a\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
 
+    /// Test helper for the default walk options, with or without recursion.
+    fn walk(recursive: bool) -> WalkOptions<'static> {
+        WalkOptions {
+            recursive,
+            all_files: false,
+            follow_symlinks: true,
+            exclude: &[],
+        }
+    }
+
+    /// Test helper to collect the streamed paths into a sorted list.
+    fn expand_paths(paths: &[String], recursive: bool) -> Vec<String> {
+        collect_paths(paths, walk(recursive))
+    }
+
+    #[test]
+    fn test_json_report_includes_errors() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.nes");
+        fs::write(&rom_path, TEST_NES_HEADER)?;
+        let rom_path = rom_path.to_str().unwrap().to_string();
+        let missing_path = dir.path().join("missing.nes").to_str().unwrap().to_string();
+
+        let results = analyze_paths(&[rom_path.clone(), missing_path.clone()]);
+        let (successes, failures): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
+        let report = JsonReport {
+            results: &successes
+                .into_iter()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+            errors: failures
+                .iter()
+                .map(|result| ErrorEntry::from_error(result.as_ref().unwrap_err()))
+                .collect(),
+        };
+
+        let json =
+            serde_json::to_value(&report).map_err(|e| RomAnalyzerError::new(&e.to_string()))?;
+        assert_eq!(json["results"].as_array().unwrap().len(), 1);
+        assert_eq!(json["results"][0]["console"], "NES");
+        assert_eq!(json["results"][0]["source_name"], rom_path.as_str());
+        assert_eq!(
+            json["errors"],
+            serde_json::json!([{
+                "path": missing_path,
+                "message": format!("File not found: {}", missing_path),
+                "kind": "file_not_found",
+            }])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_output_json_file() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.nes");
+        fs::write(&rom_path, TEST_NES_HEADER)?;
+        let rom_path = rom_path.to_str().unwrap().to_string();
+        let results: Vec<RomAnalysisResult> = analyze_paths(std::slice::from_ref(&rom_path))
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+
+        let json =
+            render_json(&results, Vec::new()).map_err(|e| RomAnalyzerError::new(&e.to_string()))?;
+        let output_path = dir.path().join("report.json");
+        write_output(Some(&output_path), &json)?;
+
+        let written = fs::read_to_string(&output_path)?;
+        assert_eq!(written, json);
+        let report: serde_json::Value =
+            serde_json::from_str(&written).map_err(|e| RomAnalyzerError::new(&e.to_string()))?;
+        assert_eq!(report["results"][0]["console"], "NES");
+        assert_eq!(report["results"][0]["source_name"], rom_path.as_str());
+        assert_eq!(report["errors"], serde_json::json!([]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_entry_unwraps_path_context() {
+        let e = RomAnalyzerError::WithPath(
+            "game.zip".to_string(),
+            Box::new(RomAnalyzerError::ArchiveError("empty".to_string())),
+        );
+        assert_eq!(
+            ErrorEntry::from_error(&e),
+            ErrorEntry {
+                path: "game.zip".to_string(),
+                message: "Archive error: empty".to_string(),
+                kind: "archive_error",
+            }
+        );
+    }
+
+    #[test]
+    fn test_render_result_ignores_log_level() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.nes");
+        fs::write(&rom_path, TEST_NES_HEADER)?;
+        let analysis = analyze_path(rom_path.to_str().unwrap(), &AnalysisOptions::default())?;
+
+        // Results are rendered in full even when only errors would be logged, as with --quiet.
+        log::set_max_level(get_log_level(true, 0));
+        assert_eq!(
+            render_result(&analysis, OutputFormat::Text),
+            format!("{}\n", analysis.print())
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&render_result(&analysis, OutputFormat::Json))
+                .map_err(|e| RomAnalyzerError::new(&e.to_string()))?;
+        assert_eq!(json["console"], "NES");
+        assert_eq!(
+            render_result(&analysis, OutputFormat::Xml),
+            to_xml_string(&[analysis])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_hexdump() {
+        let mut data = b"SUPER GAME\x00\x01".to_vec();
+        data.extend_from_slice(&[0xFF; 6]);
+        assert_eq!(
+            format_hexdump(&data, 0x7FC0),
+            "00007FC0  53 55 50 45 52 20 47 41 4D 45 00 01 FF FF FF FF  |SUPER GAME......|\n\
+             00007FD0  FF FF                                            |..|\n"
+        );
+        assert_eq!(format_hexdump(&[], 0), "");
+    }
+
+    #[test]
+    fn test_render_header_dump() -> Result<(), RomAnalyzerError> {
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("game.nes");
+        fs::write(&rom_path, TEST_NES_HEADER)?;
+        let analysis = analyze_path(rom_path.to_str().unwrap(), &AnalysisOptions::default())?;
+        assert_eq!(
+            render_header_dump(&analysis)?,
+            format!(
+                "Header (0x0..0x10):\n{}",
+                format_hexdump(&TEST_NES_HEADER[..16], 0)
+            )
+        );
+
+        // Headerless NES ROMs have nothing to dump.
+        let rom_path = dir.path().join("headerless.nes");
+        fs::write(&rom_path, [0; 0x4000])?;
+        let analysis = analyze_path(rom_path.to_str().unwrap(), &AnalysisOptions::default())?;
+        assert!(render_header_dump(&analysis).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_by_conflicts_with_per_result_output() {
+        // Tests that output only printed with each result can't be asked for with --group-by.
+        for flag in ["--dump-header", "--multicart"] {
+            let args = ["rom-analyzer", "--group-by", "console", flag, "game.nes"];
+            assert!(Cli::try_parse_from(args).is_err(), "{}", flag);
+        }
+        assert!(Cli::try_parse_from(["rom-analyzer", "--group-by", "console", "game.nes"]).is_ok());
+    }
+
+    #[test]
+    fn test_render_multicart() -> Result<(), RomAnalyzerError> {
+        let mut game = TEST_NES_HEADER[..16].to_vec();
+        game.resize(0x4010, 0);
+        let dir = tempdir()?;
+        let rom_path = dir.path().join("multicart.nes");
+        fs::write(&rom_path, [game.clone(), game.clone()].concat())?;
+        let analysis = analyze_path(rom_path.to_str().unwrap(), &AnalysisOptions::default())?;
+        let output = render_multicart(&analysis)?;
+        assert!(output.starts_with("Multicart:    2 games\n"));
+        assert!(output.contains("multicart.nes @ 0x0\n"));
+        assert!(output.contains("multicart.nes @ 0x4010\n"));
+
+        // Single-game ROMs list nothing.
+        let rom_path = dir.path().join("game.nes");
+        fs::write(&rom_path, &game)?;
+        let analysis = analyze_path(rom_path.to_str().unwrap(), &AnalysisOptions::default())?;
+        assert_eq!(render_multicart(&analysis)?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let hashes = |crc32: u32, sha1: &str| Hashes {
+            crc32,
+            sha1: sha1.to_string(),
+        };
+        let hashed_files = vec![
+            ("roms/a.nes".to_string(), hashes(0x1111_1111, "aa")),
+            ("roms/b.nes".to_string(), hashes(0x2222_2222, "bb")),
+            ("roms/copy of a.nes".to_string(), hashes(0x1111_1111, "aa")),
+        ];
+
+        let groups = find_duplicates(&hashed_files);
+        assert_eq!(
+            groups,
+            vec![(
+                &hashes(0x1111_1111, "aa"),
+                vec!["roms/a.nes", "roms/copy of a.nes"]
+            )]
+        );
+        assert_eq!(
+            render_duplicates(&groups),
+            "Duplicates (2 files): 11111111 aa\n  roms/a.nes\n  roms/copy of a.nes\n"
+        );
+        assert!(find_duplicates(&hashed_files[..2]).is_empty());
+    }
+
+    #[test]
+    fn test_render_raw_region_info() -> Result<(), RomAnalyzerError> {
+        let mut data = vec![0; 0x200];
+        data[0x100..0x10C].copy_from_slice(b"SEGA GENESIS");
+        data[0x1F0] = b'Z';
+        let analysis = RomAnalysisResult::Genesis(genesis::analyze_genesis_data(&data, "a.md")?);
+        assert_eq!(
+            render_raw_region_info(&analysis),
+            "Raw Region:   0x5A at offset 0x1F0\n"
+        );
+
+        data[0x1F0] = b'U';
+        let analysis = RomAnalysisResult::Genesis(genesis::analyze_genesis_data(&data, "a.md")?);
+        assert_eq!(render_raw_region_info(&analysis), "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_counts() -> Result<(), RomAnalyzerError> {
+        let genesis_rom = |region: u8| {
+            let mut data = vec![0; 0x200];
+            data[0x100..0x10C].copy_from_slice(b"SEGA GENESIS");
+            data[0x1F0] = region;
+            data
+        };
+        let mut nes_rom = TEST_NES_HEADER.to_vec();
+        nes_rom.resize(0x4010, 0);
+        let results = vec![
+            RomAnalysisResult::Genesis(genesis::analyze_genesis_data(&genesis_rom(b'U'), "a.md")?),
+            RomAnalysisResult::NES(nes::analyze_nes_data(&nes_rom, "b.nes")?),
+            RomAnalysisResult::Genesis(genesis::analyze_genesis_data(&genesis_rom(b'J'), "c.md")?),
+            RomAnalysisResult::Genesis(genesis::analyze_genesis_data(&genesis_rom(b'U'), "d.md")?),
+        ];
+
+        let by_console = group_counts(&results, GroupBy::Console);
+        assert_eq!(
+            render_group_counts(&by_console),
+            "Sega Genesis: 3, NES: 1\n"
+        );
+        let by_region = group_counts(&results, GroupBy::Region);
+        assert_eq!(
+            render_group_counts(&by_region),
+            "USA: 2, Japan: 1, Japan/USA: 1\n"
+        );
+        let by_continent = group_counts(&results, GroupBy::Continent);
+        assert_eq!(
+            render_group_counts(&by_continent),
+            "North America: 2, Asia: 1, Multiple: 1\n"
+        );
+        assert!(group_counts(&[], GroupBy::Region).is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_get_log_level_quiet() {
         // Tests that quiet mode sets log level to Error regardless of verbosity.
@@ -217,7 +1332,7 @@ mod tests {
     fn test_process_files_parallel_non_existent_file() {
         // Tests processing a non-existent file returns a FileNotFound error.
         let non_existent = ["non_existent_file.nes".to_string()];
-        let results = process_files_parallel(&non_existent);
+        let results = analyze_paths(&non_existent);
         assert_eq!(results.len(), 1);
         assert!(results[0].is_err());
         match &results[0] {
@@ -239,7 +1354,7 @@ mod tests {
         let file_path_str = file_path.to_str().unwrap().to_string();
         let file_paths = vec![file_path_str.clone()];
 
-        let results = process_files_parallel(&file_paths);
+        let results = analyze_paths(&file_paths);
         assert_eq!(results.len(), 1);
         match &results[0] {
             Ok(analysis) => assert_eq!(analysis.source_name(), &file_path_str),
@@ -260,7 +1375,7 @@ mod tests {
             "invalid.nes".to_string(),
         ];
 
-        let results = process_files_parallel(&file_paths);
+        let results = analyze_paths(&file_paths);
         let ok_count = results.iter().filter(|r| r.is_ok()).count();
         let err_count = results.iter().filter(|r| r.is_err()).count();
         assert_eq!(results.len(), 2);
@@ -271,7 +1386,7 @@ mod tests {
     #[test]
     fn test_process_files_parallel_empty_input() {
         // Tests processing an empty list of files returns an empty results list.
-        let results = process_files_parallel(&[]);
+        let results = analyze_paths(&[]);
         assert!(results.is_empty());
     }
 
@@ -297,7 +1412,7 @@ mod tests {
             file3.to_str().unwrap().to_string(),
         ];
         // Process the files in parallel.
-        let results = process_files_parallel(&file_paths);
+        let results = analyze_paths(&file_paths);
 
         // Assert the results are in the correct order.
         assert_eq!(results.len(), 3);
@@ -321,7 +1436,7 @@ mod tests {
         let file_paths = vec![invalid_file.to_str().unwrap().to_string()];
 
         // Process the file, expecting a RomAnalyzerError::WithPath.
-        let results = process_files_parallel(&file_paths);
+        let results = analyze_paths(&file_paths);
 
         assert_eq!(results.len(), 1);
         match &results[0] {
@@ -332,6 +1447,389 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_process_paths_parallel_large_tree() {
+        // Tests that a large tree is fully walked, deduplicated and returned in sorted order.
+
+        // Spread 5,000 files across 50 subdirectories.
+        let root = tempdir().unwrap();
+        for dir_index in 0..50 {
+            let sub_dir = root.path().join(format!("dir{:02}", dir_index));
+            fs::create_dir(&sub_dir).unwrap();
+            for file_index in 0..100 {
+                let file_path = sub_dir.join(format!("rom{:03}.nes", file_index));
+                fs::write(file_path, TEST_NES_HEADER).unwrap();
+            }
+        }
+
+        // Pass the tree twice and one of its files explicitly to exercise deduplication.
+        let root_str = root.path().to_str().unwrap().to_string();
+        let single_file = root.path().join("dir00").join("rom000.nes");
+        let paths = vec![
+            root_str.clone(),
+            root_str,
+            single_file.to_str().unwrap().to_string(),
+        ];
+
+        let expanded = expand_paths(&paths, true);
+        assert_eq!(expanded.len(), 5000);
+
+        let results =
+            process_paths_parallel(&paths, walk(true), false, AnalysisOptions::default(), None);
+        assert_eq!(results.len(), 5000);
+        let result_paths: Vec<&str> = results
+            .iter()
+            .map(|result| {
+                assert!(result.is_ok(), "Expected Ok, but got {:?}", result);
+                result_path(result)
+            })
+            .collect();
+        assert_eq!(result_paths, expanded);
+    }
+
+    #[test]
+    fn test_process_paths_parallel_sorts_errors_with_results() {
+        // Tests that failed files are ordered by their path alongside successful ones.
+        let dir = tempdir().unwrap();
+        let valid_file = dir.path().join("b.nes");
+        fs::write(&valid_file, TEST_NES_HEADER).unwrap();
+        let invalid_file = dir.path().join("a.nes");
+        fs::write(&invalid_file, b"not a valid NES file").unwrap();
+        let paths = vec![dir.path().to_str().unwrap().to_string()];
+
+        let results =
+            process_paths_parallel(&paths, walk(true), false, AnalysisOptions::default(), None);
+        assert_eq!(results.len(), 2);
+        assert_eq!(result_path(&results[0]), invalid_file.to_str().unwrap());
+        assert!(results[0].is_err());
+        assert_eq!(result_path(&results[1]), valid_file.to_str().unwrap());
+        assert!(results[1].is_ok());
+    }
+
+    #[test]
+    fn test_process_paths_parallel_all_in_archive() {
+        // Tests that every ROM in a zip is reported when all_in_archive is set.
+        use std::io::Write;
+        use zip::write::{FileOptions, ZipWriter};
+
+        let dir = tempdir().unwrap();
+        let zip_path = dir.path().join("romset.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        for name in ["a.nes", "b.nes"] {
+            zip.start_file(name, FileOptions::default()).unwrap();
+            zip.write_all(TEST_NES_HEADER).unwrap();
+        }
+        zip.finish().unwrap();
+        let paths = vec![zip_path.to_str().unwrap().to_string()];
+
+        assert_eq!(
+            process_paths_parallel(&paths, walk(false), false, AnalysisOptions::default(), None)
+                .len(),
+            1
+        );
+        let results =
+            process_paths_parallel(&paths, walk(false), true, AnalysisOptions::default(), None);
+        let source_names: Vec<&str> = results
+            .iter()
+            .map(|result| result.as_ref().unwrap().source_name())
+            .collect();
+        assert_eq!(source_names, vec!["a.nes", "b.nes"]);
+    }
+
+    #[test]
+    fn test_process_paths_parallel_include_unknown() {
+        // Tests that unrecognized files are only reported as Unknown results when requested.
+        let dir = tempdir().unwrap();
+        let text_file = dir.path().join("notes (USA).txt");
+        fs::write(&text_file, b"not a rom").unwrap();
+        let paths = vec![text_file.to_str().unwrap().to_string()];
+
+        let results =
+            process_paths_parallel(&paths, walk(false), false, AnalysisOptions::default(), None);
+        assert!(matches!(
+            &results[0],
+            Err(RomAnalyzerError::WithPath(_, e)) if matches!(**e, RomAnalyzerError::UnsupportedFormat(_))
+        ));
+
+        let options = AnalysisOptions {
+            include_unknown: true,
+            ..AnalysisOptions::default()
+        };
+        let results = process_paths_parallel(&paths, walk(false), false, options, None);
+        let analysis = results[0].as_ref().unwrap();
+        assert!(matches!(analysis, RomAnalysisResult::Unknown(_)));
+        assert_eq!(analysis.source_name(), paths[0]);
+        assert_eq!(analysis.region(), "USA");
+    }
+
+    #[test]
+    fn test_stream_json_lines() {
+        // Tests that every result is written as its own line of JSON, and errors are left out.
+        let dir = tempdir().unwrap();
+        let mut rom_data = TEST_NES_HEADER.to_vec();
+        rom_data.resize(0x4010, 0);
+        for name in ["a (USA).nes", "b (Japan).nes"] {
+            fs::write(dir.path().join(name), &rom_data).unwrap();
+        }
+        fs::write(dir.path().join("c.nes"), b"NES").unwrap();
+        let paths = vec![dir.path().to_str().unwrap().to_string()];
+
+        let writer = Mutex::new(Vec::new());
+        let results =
+            analyze_paths_parallel(&paths, walk(true), false, AnalysisOptions::default(), None);
+        assert!(stream_json_lines(results, MismatchMode::Strict, &writer));
+
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let mut source_names: Vec<String> = output
+            .lines()
+            .map(|line| {
+                let json: serde_json::Value = serde_json::from_str(line).unwrap();
+                assert_eq!(json["console"], "NES");
+                json["source_name"].as_str().unwrap().to_string()
+            })
+            .collect();
+        source_names.sort();
+        assert_eq!(source_names.len(), 2);
+        assert!(source_names[0].ends_with("a (USA).nes"));
+        assert!(source_names[1].ends_with("b (Japan).nes"));
+    }
+
+    #[test]
+    fn test_process_paths_parallel_strict() {
+        // Tests that headerless NES ROMs are only rejected in strict mode.
+        let dir = tempdir().unwrap();
+        let rom_path = dir.path().join("headerless.nes");
+        fs::write(&rom_path, vec![0; 0x8000]).unwrap();
+        let paths = vec![rom_path.to_str().unwrap().to_string()];
+
+        let results =
+            process_paths_parallel(&paths, walk(false), false, AnalysisOptions::default(), None);
+        assert!(results[0].is_ok());
+
+        let options = AnalysisOptions {
+            strict: true,
+            ..AnalysisOptions::default()
+        };
+        let results = process_paths_parallel(&paths, walk(false), false, options, None);
+        assert!(results[0].is_err());
+    }
+
+    #[test]
+    fn test_process_paths_parallel_strict_all_in_archive() {
+        // Tests that strict mode also rejects ROMs within a zip when every ROM is analyzed.
+        use std::io::Write;
+        use zip::write::{FileOptions, ZipWriter};
+
+        let dir = tempdir().unwrap();
+        let zip_path = dir.path().join("romset.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        for name in ["headerless.nes", "bad_checksum.sfc"] {
+            zip.start_file(name, FileOptions::default()).unwrap();
+            zip.write_all(&[0; 0x8000]).unwrap();
+        }
+        zip.finish().unwrap();
+        let paths = vec![zip_path.to_str().unwrap().to_string()];
+
+        let results =
+            process_paths_parallel(&paths, walk(false), true, AnalysisOptions::default(), None);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(Result::is_ok));
+
+        let options = AnalysisOptions {
+            strict: true,
+            ..AnalysisOptions::default()
+        };
+        let results = process_paths_parallel(&paths, walk(false), true, options, None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(result_path(&results[0]), paths[0]);
+        assert!(results[0].is_err());
+    }
+
+    #[test]
+    fn test_process_paths_parallel_strict_world() {
+        // Tests that an international SNES ROM named for one region is only flagged with
+        // strict world, and that the mismatch mode doesn't clear the flag.
+        let dir = tempdir().unwrap();
+        let rom_path = dir.path().join("game (J).sfc");
+        let mut data = vec![0; 0x8000];
+        data[0x7FD9] = 0x0E;
+        fs::write(&rom_path, data).unwrap();
+        let paths = vec![rom_path.to_str().unwrap().to_string()];
+
+        let results =
+            process_paths_parallel(&paths, walk(false), false, AnalysisOptions::default(), None);
+        assert!(!results[0].as_ref().unwrap().region_mismatch());
+
+        let options = AnalysisOptions {
+            strict_world: true,
+            ..AnalysisOptions::default()
+        };
+        let results = process_paths_parallel(&paths, walk(false), false, options, None);
+        let analysis = results[0].as_ref().unwrap().clone();
+        assert!(analysis.region_mismatch());
+        assert!(
+            analysis
+                .clone()
+                .with_mismatch_mode(MismatchMode::Strict)
+                .region_mismatch()
+        );
+        assert!(
+            !analysis
+                .with_mismatch_mode(MismatchMode::Off)
+                .region_mismatch()
+        );
+    }
+
+    #[test]
+    fn test_process_paths_parallel_strict_world_all_in_archive() {
+        // Tests that strict world also applies to ROMs within a zip when every ROM is analyzed.
+        use std::io::Write;
+        use zip::write::{FileOptions, ZipWriter};
+
+        let mut data = vec![0; 0x8000];
+        data[0x7FD9] = 0x0E;
+        let dir = tempdir().unwrap();
+        let zip_path = dir.path().join("romset.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        zip.start_file("game (J).sfc", FileOptions::default())
+            .unwrap();
+        zip.write_all(&data).unwrap();
+        zip.finish().unwrap();
+        let paths = vec![zip_path.to_str().unwrap().to_string()];
+
+        let results =
+            process_paths_parallel(&paths, walk(false), true, AnalysisOptions::default(), None);
+        assert!(!results[0].as_ref().unwrap().region_mismatch());
+
+        let options = AnalysisOptions {
+            strict_world: true,
+            ..AnalysisOptions::default()
+        };
+        let results = process_paths_parallel(&paths, walk(false), true, options, None);
+        assert!(results[0].as_ref().unwrap().region_mismatch());
+    }
+
+    #[test]
+    fn test_process_paths_parallel_force_type() {
+        // Tests that a forced console overrides the extension, even with include_unknown set.
+        let dir = tempdir().unwrap();
+        let rom_path = dir.path().join("game.txt");
+        fs::write(&rom_path, vec![0; 0x8000]).unwrap();
+        let paths = vec![rom_path.to_str().unwrap().to_string()];
+        let options = AnalysisOptions {
+            force_type: Some(ForceType::Snes.into()),
+            include_unknown: true,
+            ..AnalysisOptions::default()
+        };
+
+        let results = process_paths_parallel(&paths, walk(false), false, options, None);
+        assert_eq!(results[0].as_ref().unwrap().console_name(), "SNES");
+    }
+
+    #[test]
+    fn test_process_paths_parallel_groups_cue_tracks() {
+        // Tests that the tracks of a bin/cue image are analyzed once, through the CUE sheet.
+        let dir = tempdir().unwrap();
+        let cue_path = dir.path().join("game.cue");
+        fs::write(
+            &cue_path,
+            "FILE \"game (Track 1).bin\" BINARY\n  TRACK 01 MODE2/2352\n\
+             FILE \"game (Track 2).bin\" BINARY\n  TRACK 02 AUDIO\n",
+        )
+        .unwrap();
+        let mut data_track = vec![0; 0x2000];
+        data_track[0x100..0x104].copy_from_slice(b"SLUS");
+        fs::write(dir.path().join("game (Track 1).bin"), &data_track).unwrap();
+        fs::write(dir.path().join("game (Track 2).bin"), vec![0; 0x2000]).unwrap();
+        let paths = vec![dir.path().to_str().unwrap().to_string()];
+
+        let results =
+            process_paths_parallel(&paths, walk(true), false, AnalysisOptions::default(), None);
+        assert_eq!(results.len(), 1);
+        let analysis = results[0].as_ref().unwrap();
+        assert!(matches!(analysis, RomAnalysisResult::PSX(_)));
+        assert_eq!(result_path(&results[0]), cue_path.to_str().unwrap());
+        assert_eq!(
+            collect_paths(&paths, walk(true)),
+            vec![cue_path.to_str().unwrap().to_string()]
+        );
+
+        // Hash-only mode still hashes every track.
+        assert_eq!(hash_paths_parallel(&paths, walk(true), None).len(), 3);
+    }
+
+    #[test]
+    fn test_progress_throttles_lines() {
+        let progress = Progress::new(Duration::from_secs(3600));
+        progress.total.store(3, Ordering::Relaxed);
+        // Within the interval only the last file produces a line.
+        assert_eq!(progress.file_processed(), None);
+        assert_eq!(progress.file_processed(), None);
+        assert_eq!(
+            progress.file_processed().as_deref(),
+            Some("3/3 files processed")
+        );
+
+        let progress = Progress::new(Duration::ZERO);
+        progress.total.store(2, Ordering::Relaxed);
+        assert_eq!(
+            progress.file_processed().as_deref(),
+            Some("1/2 files processed")
+        );
+        assert_eq!(
+            progress.file_processed().as_deref(),
+            Some("2/2 files processed")
+        );
+    }
+
+    #[test]
+    fn test_process_paths_parallel_counts_progress() {
+        let dir = tempdir().unwrap();
+        for name in ["a.nes", "b.nes", "c.nes"] {
+            fs::write(dir.path().join(name), TEST_NES_HEADER).unwrap();
+        }
+        let paths = vec![dir.path().to_str().unwrap().to_string()];
+
+        let progress = Progress::new(PROGRESS_INTERVAL);
+        let results = process_paths_parallel(
+            &paths,
+            walk(true),
+            false,
+            AnalysisOptions::default(),
+            Some(&progress),
+        );
+        assert_eq!(results.len(), 3);
+        assert_eq!(progress.total.load(Ordering::Relaxed), 3);
+        assert_eq!(progress.processed.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_hash_paths_parallel() {
+        // Tests that hash-only mode hashes files without parsing their headers.
+        let dir = tempdir().unwrap();
+        let valid_file = dir.path().join("a.nes");
+        fs::write(&valid_file, TEST_NES_HEADER).unwrap();
+        let invalid_file = dir.path().join("b.nes");
+        fs::write(&invalid_file, b"not a valid NES file").unwrap();
+        let paths = vec![
+            dir.path().to_str().unwrap().to_string(),
+            "missing.nes".to_string(),
+        ];
+
+        let results = hash_paths_parallel(&paths, walk(true), None);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, valid_file.to_str().unwrap());
+        assert_eq!(
+            results[0].1.as_ref().unwrap().crc32,
+            crc32fast::hash(TEST_NES_HEADER)
+        );
+        // Files that would fail header analysis are still hashed.
+        assert_eq!(results[1].0, invalid_file.to_str().unwrap());
+        assert!(results[1].1.is_ok());
+        assert_eq!(results[2].0, "missing.nes");
+        assert!(results[2].1.is_err());
+    }
+
     #[test]
     fn test_expand_paths_non_recursive_skips_dirs() {
         // Tests that non-recursive mode skips directories without expanding them.
@@ -520,6 +2018,174 @@ mod tests {
         assert!(expanded[0].contains("link"));
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_expand_paths_no_follow_symlinks() {
+        // Tests that symlinks within a walked directory are skipped when following is disabled.
+        use std::os::unix::fs::symlink;
+
+        let target = tempdir().unwrap();
+        fs::write(target.path().join("linked.nes"), TEST_NES_HEADER).unwrap();
+        let root = tempdir().unwrap();
+        fs::write(root.path().join("file.nes"), TEST_NES_HEADER).unwrap();
+        symlink(target.path(), root.path().join("link")).unwrap();
+        let paths = vec![root.path().to_str().unwrap().to_string()];
+
+        let followed = expand_paths(&paths, true);
+        assert_eq!(followed.len(), 2);
+        assert!(followed.iter().any(|p| p.ends_with("link/linked.nes")));
+
+        let not_followed = collect_paths(
+            &paths,
+            WalkOptions {
+                follow_symlinks: false,
+                ..walk(true)
+            },
+        );
+        assert_eq!(
+            not_followed,
+            vec![root.path().join("file.nes").to_str().unwrap().to_string()]
+        );
+    }
+
+    #[test]
+    fn test_expand_paths_filters_non_rom_files() {
+        let dir = tempdir().unwrap();
+        for name in [
+            "game.nes",
+            "romset.zip",
+            "cover.png",
+            "notes.txt",
+            "game.sav",
+            "DUMP",
+        ] {
+            fs::write(dir.path().join(name), TEST_NES_HEADER).unwrap();
+        }
+        let paths = vec![dir.path().to_str().unwrap().to_string()];
+
+        let names = |expanded: Vec<String>| -> Vec<String> {
+            expanded
+                .iter()
+                .map(|p| {
+                    Path::new(p)
+                        .file_name()
+                        .unwrap()
+                        .to_str()
+                        .unwrap()
+                        .to_string()
+                })
+                .collect()
+        };
+        assert_eq!(
+            names(expand_paths(&paths, true)),
+            vec!["DUMP", "game.nes", "romset.zip"]
+        );
+        assert_eq!(
+            collect_paths(
+                &paths,
+                WalkOptions {
+                    all_files: true,
+                    ..walk(true)
+                }
+            )
+            .len(),
+            6
+        );
+
+        // Explicitly given files are kept regardless of their extension.
+        let notes = vec![dir.path().join("notes.txt").to_str().unwrap().to_string()];
+        assert_eq!(expand_paths(&notes, true), notes);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("BIOS", "BIOS"));
+        assert!(!glob_matches("BIOS", "bios"));
+        assert!(glob_matches("*.sav", "game.sav"));
+        assert!(glob_matches("*.sav", ".sav"));
+        assert!(!glob_matches("*.sav", "game.sav.nes"));
+        assert!(glob_matches("game?.nes", "game1.nes"));
+        assert!(!glob_matches("game?.nes", "game.nes"));
+        assert!(glob_matches("*a*b*", "xxaxxbxx"));
+        assert!(!glob_matches("*a*b", "xxbxxa"));
+        assert!(glob_matches("*", ""));
+    }
+
+    #[test]
+    fn test_collect_paths_exclude() {
+        let root = tempdir().unwrap();
+        let bios = root.path().join("BIOS");
+        fs::create_dir(&bios).unwrap();
+        fs::write(bios.join("bios.bin"), TEST_NES_HEADER).unwrap();
+        fs::write(root.path().join("game.nes"), TEST_NES_HEADER).unwrap();
+        fs::write(root.path().join("game (Beta).nes"), TEST_NES_HEADER).unwrap();
+        let paths = vec![root.path().to_str().unwrap().to_string()];
+        let collect_excluding = |exclude: &[String]| {
+            collect_paths(
+                &paths,
+                WalkOptions {
+                    exclude,
+                    ..walk(true)
+                },
+            )
+        };
+        let game = root.path().join("game.nes").to_str().unwrap().to_string();
+
+        // Excluded directories are skipped along with their contents.
+        assert_eq!(
+            collect_excluding(&["BIOS".to_string(), "*(Beta)*".to_string()]),
+            vec![game.clone()]
+        );
+        assert_eq!(collect_excluding(&["*.nes".to_string()]).len(), 1);
+
+        // The name of an explicitly given path is matched too.
+        assert!(
+            collect_excluding(&[root
+                .path()
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()])
+            .is_empty()
+        );
+        let explicit = vec![game.clone()];
+        let excluded = collect_paths(
+            &explicit,
+            WalkOptions {
+                exclude: &["game.*".to_string()],
+                ..walk(true)
+            },
+        );
+        assert!(excluded.is_empty());
+    }
+
+    #[test]
+    fn test_collect_paths_dry_run() {
+        let root = tempdir().unwrap();
+        let sub_dir = root.path().join("sub");
+        fs::create_dir(&sub_dir).unwrap();
+        let expected_files = [
+            root.path().join("b.sfc"),
+            root.path().join("a.nes"),
+            sub_dir.join("c.zip"),
+        ];
+        for file in &expected_files {
+            fs::write(file, b"").unwrap();
+        }
+        fs::write(sub_dir.join("readme.txt"), b"").unwrap();
+        let paths = vec![root.path().to_str().unwrap().to_string()];
+
+        let mut expected: Vec<String> = expected_files
+            .iter()
+            .map(|p| p.to_str().unwrap().to_string())
+            .collect();
+        expected.sort();
+        assert_eq!(collect_paths(&paths, walk(true)), expected);
+
+        // Without recursion the directory itself is skipped.
+        assert!(collect_paths(&paths, walk(false)).is_empty());
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_expand_paths_unreadable_dir() {
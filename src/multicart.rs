@@ -0,0 +1,88 @@
+//! Provides detection of multicart ROMs that concatenate several games.
+//!
+//! Pirate and reproduction multicarts are often dumped as the games' ROM files joined end to end.
+//! The analyzers only read the header of the first game, so [`scan_multicart`] looks for the
+//! headers of the following ones. Scanning reads the whole file, so it is only done on request.
+
+use crate::{RomFileType, detect_rom_file_type};
+
+/// iNES files are a 16-byte header followed by 512-byte trainers and 8KB banks, so each
+/// concatenated header starts at a multiple of 16 bytes.
+const NES_ALIGNMENT: usize = 0x10;
+/// Genesis ROMs are sized in multiples of 64KB, with the header 0x100 bytes into each game.
+const GENESIS_ALIGNMENT: usize = 0x10000;
+
+/// Scans NES and Genesis ROM data for the headers of concatenated games.
+///
+/// The console is identified by the header at the start of `data`, then every aligned offset is
+/// checked for another header of the same console.
+///
+/// # Arguments
+///
+/// * `data` - The raw ROM data.
+///
+/// # Returns
+///
+/// The offset of each game found, starting with 0. Single-game ROMs return `[0]`, while data that
+/// doesn't start with an iNES or Genesis header returns an empty `Vec`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::multicart::scan_multicart;
+///
+/// let mut data = b"NES\x1a\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00".to_vec();
+/// data.resize(0x4010, 0);
+/// assert_eq!(scan_multicart(&data), vec![0]);
+/// assert!(scan_multicart(b"plain text").is_empty());
+/// ```
+pub fn scan_multicart(data: &[u8]) -> Vec<usize> {
+    let file_type = detect_rom_file_type(data);
+    let alignment = match file_type {
+        RomFileType::Nes => NES_ALIGNMENT,
+        RomFileType::Genesis => GENESIS_ALIGNMENT,
+        _ => return Vec::new(),
+    };
+    (0..data.len())
+        .step_by(alignment)
+        .filter(|&offset| detect_rom_file_type(&data[offset..]) == file_type)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_nes_rom(prg_banks: u8) -> Vec<u8> {
+        let mut data = b"NES\x1a".to_vec();
+        data.push(prg_banks);
+        data.resize(0x10 + prg_banks as usize * 0x4000, 0);
+        data
+    }
+
+    #[test]
+    fn test_scan_multicart_nes() {
+        let mut data = generate_nes_rom(2);
+        data.extend(generate_nes_rom(1));
+        assert_eq!(scan_multicart(&data), vec![0, 0x8010]);
+    }
+
+    #[test]
+    fn test_scan_multicart_single_game() {
+        assert_eq!(scan_multicart(&generate_nes_rom(2)), vec![0]);
+    }
+
+    #[test]
+    fn test_scan_multicart_genesis() {
+        let mut data = vec![0; 3 * GENESIS_ALIGNMENT];
+        for offset in [0, 2 * GENESIS_ALIGNMENT] {
+            data[offset + 0x100..offset + 0x110].copy_from_slice(b"SEGA GENESIS    ");
+        }
+        assert_eq!(scan_multicart(&data), vec![0, 2 * GENESIS_ALIGNMENT]);
+    }
+
+    #[test]
+    fn test_scan_multicart_unsupported() {
+        assert!(scan_multicart(&[0; 0x1000]).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Provides the publisher names for the maker (licensee) codes stored in ROM headers.
+//!
+//! Nintendo assigned each licensed publisher a two-character maker code, which the Game Boy,
+//! Super Nintendo and Game Boy Advance headers all store. Older headers hold the code as a single
+//! byte, whose hexadecimal form matches the two-character code for the publishers listed here.
+//! Only common publishers are covered; unlisted codes are reported without a name.
+//!
+//! Licensee code documentation referenced here:
+//! <https://gbdev.io/pandocs/The_Cartridge_Header.html#01440145--new-licensee-code>
+
+/// Common maker codes and the names of their publishers.
+const PUBLISHERS: &[(&str, &str)] = &[
+    ("01", "Nintendo"),
+    ("08", "Capcom"),
+    ("13", "Electronic Arts"),
+    ("18", "Hudson Soft"),
+    ("20", "KSS"),
+    ("28", "Kemco"),
+    ("29", "SETA"),
+    ("30", "Viacom"),
+    ("31", "Nintendo"),
+    ("32", "Bandai"),
+    ("34", "Konami"),
+    ("37", "Taito"),
+    ("38", "Hudson Soft"),
+    ("39", "Banpresto"),
+    ("41", "Ubisoft"),
+    ("42", "Atlus"),
+    ("49", "Irem"),
+    ("50", "Absolute"),
+    ("51", "Acclaim"),
+    ("52", "Activision"),
+    ("53", "American Sammy"),
+    ("54", "Konami"),
+    ("56", "LJN"),
+    ("60", "Titus"),
+    ("61", "Virgin Interactive"),
+    ("64", "LucasArts"),
+    ("67", "Ocean"),
+    ("69", "Electronic Arts"),
+    ("70", "Infogrames"),
+    ("71", "Interplay"),
+    ("72", "Broderbund"),
+    ("78", "THQ"),
+    ("79", "Accolade"),
+    ("8P", "Sega"),
+    ("91", "Chunsoft"),
+    ("95", "Varie"),
+    ("A4", "Konami"),
+    ("AF", "Namco"),
+    ("B0", "Acclaim"),
+    ("B1", "ASCII"),
+    ("B4", "Enix"),
+    ("B6", "HAL Laboratory"),
+    ("BB", "Sunsoft"),
+    ("C0", "Taito"),
+    ("C2", "Kemco"),
+    ("C3", "Square"),
+    ("C8", "Koei"),
+    ("D9", "Banpresto"),
+    ("E9", "Natsume"),
+    ("EB", "Atlus"),
+];
+
+/// Looks up the publisher name for a two-character maker code.
+///
+/// # Arguments
+///
+/// * `maker_code` - The maker code from the ROM header (e.g. "01"), compared case-insensitively.
+///
+/// # Returns
+///
+/// An `Option` containing the publisher name, or `None` if the code is not a known publisher.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::publisher::publisher_name;
+///
+/// assert_eq!(publisher_name("01"), Some("Nintendo"));
+/// assert_eq!(publisher_name("af"), Some("Namco"));
+/// assert_eq!(publisher_name("ZZ"), None);
+/// ```
+pub fn publisher_name(maker_code: &str) -> Option<&'static str> {
+    PUBLISHERS
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(maker_code))
+        .map(|(_, name)| *name)
+}
+
+/// Formats a single-byte (old) licensee code as its two-character maker code.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::publisher::{maker_code_from_byte, publisher_name};
+///
+/// assert_eq!(maker_code_from_byte(0xC3), "C3");
+/// assert_eq!(publisher_name(&maker_code_from_byte(0xC3)), Some("Square"));
+/// ```
+pub fn maker_code_from_byte(code: u8) -> String {
+    format!("{:02X}", code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publisher_codes_are_unique() {
+        for (i, (code, _)) in PUBLISHERS.iter().enumerate() {
+            assert_eq!(code.len(), 2);
+            assert!(
+                PUBLISHERS[i + 1..].iter().all(|(other, _)| other != code),
+                "Duplicate maker code {}",
+                code
+            );
+        }
+    }
+}
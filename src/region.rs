@@ -11,10 +11,13 @@
 //! constant is a special case that represents ROMs compatible with multiple regions.
 
 use std::fmt;
+use std::str::FromStr;
 
 use bitflags::bitflags;
 use serde::Serialize;
 
+use crate::error::RomAnalyzerError;
+
 bitflags! {
     /// A bitflag struct representing geographical regions.
     /// Allows a ROM to belong to multiple regions (e.g., NES NTSC = USA + JAPAN).
@@ -38,6 +41,36 @@ bitflags! {
     }
 }
 
+impl Region {
+    /// Returns the continent of the region, for grouping regions coarsely.
+    ///
+    /// Composite masks whose regions all lie on the same continent (e.g. Japan/Korea) collapse
+    /// to that continent, while masks spanning several continents return "Multiple".
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rom_analyzer::region::Region;
+    ///
+    /// assert_eq!(Region::USA.continent(), "North America");
+    /// assert_eq!((Region::JAPAN | Region::KOREA).continent(), "Asia");
+    /// assert_eq!((Region::USA | Region::EUROPE).continent(), "Multiple");
+    /// ```
+    pub fn continent(&self) -> &'static str {
+        let mut continents = self.iter().map(|flag| match flag {
+            Region::USA => "North America",
+            Region::EUROPE => "Europe",
+            Region::RUSSIA => "Europe/Asia",
+            _ => "Asia",
+        });
+        match continents.next() {
+            None => "Unknown",
+            Some(first) if continents.all(|continent| continent == first) => first,
+            Some(_) => "Multiple",
+        }
+    }
+}
+
 impl fmt::Display for Region {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.is_empty() {
@@ -70,6 +103,113 @@ impl fmt::Display for Region {
     }
 }
 
+impl FromStr for Region {
+    type Err = RomAnalyzerError;
+
+    /// Parses a case-insensitive region name (e.g. "usa", "Japan", "world") into a [`Region`].
+    ///
+    /// Several names can be combined with `+` or `/` (e.g. "usa+japan"), in which case their
+    /// flags are OR-ed together. This also accepts the [`Display`](fmt::Display) output of a
+    /// [`Region`], such as "Japan/USA".
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rom_analyzer::region::Region;
+    ///
+    /// assert_eq!("USA".parse::<Region>().unwrap(), Region::USA);
+    /// assert_eq!(
+    ///     "usa+japan".parse::<Region>().unwrap(),
+    ///     Region::USA | Region::JAPAN
+    /// );
+    /// assert!("atlantis".parse::<Region>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(['+', '/']).try_fold(Region::UNKNOWN, |acc, name| {
+            let region = match name.trim().to_lowercase().as_str() {
+                "japan" | "jp" | "jpn" => Region::JAPAN,
+                "usa" | "us" => Region::USA,
+                "europe" | "eu" | "eur" => Region::EUROPE,
+                "russia" => Region::RUSSIA,
+                "asia" => Region::ASIA,
+                "china" => Region::CHINA,
+                "korea" => Region::KOREA,
+                "world" => Region::WORLD,
+                "unknown" => Region::UNKNOWN,
+                _ => {
+                    return Err(RomAnalyzerError::ParsingError(format!(
+                        "Unknown region name: '{}'",
+                        name.trim()
+                    )));
+                }
+            };
+            Ok(acc | region)
+        })
+    }
+}
+
+/// The video timing a ROM targets, which doesn't always follow from its region.
+///
+/// For example, Brazilian consoles use PAL-M, which has NTSC timing, even though Brazil is often
+/// grouped with PAL regions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum VideoSystem {
+    /// 60Hz NTSC timing (including PAL-M).
+    Ntsc,
+    /// 50Hz PAL timing.
+    Pal,
+    /// Runs with either timing.
+    Multi,
+    /// The header doesn't tell which timing is used.
+    Unknown,
+}
+
+impl fmt::Display for VideoSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VideoSystem::Ntsc => write!(f, "NTSC"),
+            VideoSystem::Pal => write!(f, "PAL"),
+            VideoSystem::Multi => write!(f, "Multi"),
+            VideoSystem::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// How the region of an analysis was determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Confidence {
+    /// The region was read from the ROM header.
+    FromHeader,
+    /// The header has no (known) region, so it was inferred from the filename.
+    FromFilename,
+    /// Neither the header nor the filename identified a region.
+    Unknown,
+}
+
+impl Confidence {
+    /// Returns the confidence for a region read from a ROM header.
+    ///
+    /// A header region of [`Region::UNKNOWN`] gives [`Confidence::Unknown`].
+    pub fn from_header(region: Region) -> Self {
+        if region.is_empty() {
+            Confidence::Unknown
+        } else {
+            Confidence::FromHeader
+        }
+    }
+
+    /// Returns the confidence for a region inferred with [`infer_region_from_filename`].
+    ///
+    /// An inferred region of [`Region::UNKNOWN`] gives [`Confidence::Unknown`].
+    pub fn from_filename(region: Region) -> Self {
+        if region.is_empty() {
+            Confidence::Unknown
+        } else {
+            Confidence::FromFilename
+        }
+    }
+}
+
 const REGION_PATTERNS: &[(&[&str], Region)] = &[
     (&["JAP", "JP", "(J)", "[J]", "NTSC-J"], Region::JAPAN),
     (&["USA", "(U)", "[U]", "NTSC-U", "NTSC-US"], Region::USA),
@@ -78,12 +218,42 @@ const REGION_PATTERNS: &[(&[&str], Region)] = &[
     (&["(WORLD)", "[WORLD]", "(W)", "[W]"], Region::WORLD),
 ];
 
+// Country and continent names used by No-Intro in parenthesized groups, e.g. "(USA, Europe)".
+// Countries are grouped the way the SNES header region codes group them.
+const COUNTRY_NAMES: &[(&str, Region)] = &[
+    ("JAPAN", Region::JAPAN),
+    ("USA", Region::USA),
+    ("CANADA", Region::USA),
+    ("BRAZIL", Region::USA),
+    ("EUROPE", Region::EUROPE),
+    ("AUSTRALIA", Region::EUROPE),
+    ("UK", Region::EUROPE),
+    ("FRANCE", Region::EUROPE),
+    ("GERMANY", Region::EUROPE),
+    ("ITALY", Region::EUROPE),
+    ("SPAIN", Region::EUROPE),
+    ("NETHERLANDS", Region::EUROPE),
+    ("SWEDEN", Region::EUROPE),
+    ("SCANDINAVIA", Region::EUROPE),
+    ("RUSSIA", Region::RUSSIA),
+    ("ASIA", Region::ASIA),
+    ("HONG KONG", Region::ASIA),
+    ("TAIWAN", Region::ASIA),
+    ("CHINA", Region::CHINA),
+    ("KOREA", Region::KOREA),
+    ("WORLD", Region::WORLD),
+];
+
 /// Infers the geographical region of a ROM from its filename.
 ///
 /// This function examines the provided filename for common region indicators (e.g., "JP", "USA",
 /// "EUR", "PAL", NTSC-J, NTSC-U, NTSC-E, (J), (U), (E), \[J\], \[U\], \[E\]) and returns a
 /// standardized region string if a match is found. The search is case-insensitive.
 ///
+/// Parenthesized No-Intro country groups such as "(USA, Europe)" or "(Japan, Korea)" are also
+/// split on commas, and every recognized country or continent is added to the region. Other
+/// tokens in a group, such as languages or revisions, are ignored.
+///
 /// # Arguments
 ///
 /// * `name` - The filename of the ROM as a string slice.
@@ -100,13 +270,17 @@ const REGION_PATTERNS: &[(&[&str], Region)] = &[
 /// assert_eq!(infer_region_from_filename("MyGame (J).zip"), Region::JAPAN);
 /// assert_eq!(infer_region_from_filename("AnotherGame (USA).nes"), Region::USA);
 /// assert_eq!(infer_region_from_filename("PAL_Game.sfc"), Region::EUROPE);
+/// assert_eq!(
+///     infer_region_from_filename("Game (Japan, Korea).sms"),
+///     Region::JAPAN | Region::KOREA
+/// );
 /// assert_eq!(infer_region_from_filename("UnknownGame.bin"), Region::UNKNOWN);
 /// ```
 pub fn infer_region_from_filename(name: &str) -> Region {
     // Case-insensitively scan the filename for known region tokens and OR together
     // any matching region flags to produce a combined Region bitmask.
     let upper_name = name.to_uppercase();
-    REGION_PATTERNS
+    let pattern_region = REGION_PATTERNS
         .iter()
         .fold(Region::UNKNOWN, |acc, (patterns, flag)| {
             if patterns.iter().any(|pattern| upper_name.contains(*pattern)) {
@@ -114,11 +288,74 @@ pub fn infer_region_from_filename(name: &str) -> Region {
             } else {
                 acc
             }
+        });
+    pattern_region | country_group_region(&upper_name)
+}
+
+/// Helper function to OR together the countries listed in the parenthesized groups of an
+/// uppercase filename.
+fn country_group_region(upper_name: &str) -> Region {
+    upper_name
+        .split('(')
+        .skip(1)
+        .filter_map(|group| group.split_once(')').map(|(group, _)| group))
+        .flat_map(|group| group.split(','))
+        .filter_map(|token| {
+            COUNTRY_NAMES
+                .iter()
+                .find(|(country, _)| *country == token.trim())
+                .map(|(_, region)| *region)
         })
+        .fold(Region::UNKNOWN, |acc, region| acc | region)
+}
+
+/// How strictly [`check_region_mismatch_with_mode`] compares the filename region to the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MismatchMode {
+    /// Never report a mismatch.
+    Off,
+    /// Report a mismatch when the regions share nothing, e.g. a "(E)" file with an NTSC header.
+    #[default]
+    Strict,
+    /// Report a mismatch unless the regions are identical, e.g. a "(U)" file with a World header.
+    ///
+    /// Broad header regions, such as SNES "Common / International" (0x0E) or Sega CD
+    /// "Unrestricted" (0x00), overlap every single-region filename and so never mismatch in
+    /// strict mode. In exact mode a single-region filename on such a ROM is reported, which
+    /// surfaces files that may be mislabeled.
+    Exact,
+}
+
+impl FromStr for MismatchMode {
+    type Err = RomAnalyzerError;
+
+    /// Parses a case-insensitive mode name ("off", "strict" or "exact") into a [`MismatchMode`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rom_analyzer::region::MismatchMode;
+    ///
+    /// assert_eq!("Exact".parse::<MismatchMode>().unwrap(), MismatchMode::Exact);
+    /// assert!("loose".parse::<MismatchMode>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "off" => Ok(MismatchMode::Off),
+            "strict" => Ok(MismatchMode::Strict),
+            "exact" => Ok(MismatchMode::Exact),
+            _ => Err(RomAnalyzerError::ParsingError(format!(
+                "Unknown mismatch mode: '{}'",
+                s.trim()
+            ))),
+        }
+    }
 }
 
 /// Compare the inferred region (via filename) to the region reported by the ROM's header.
 ///
+/// This is [`check_region_mismatch_with_mode`] in [`MismatchMode::Strict`] mode.
+///
 /// # Arguments
 ///
 /// * `name` - The filename of the ROM as a string slice.
@@ -148,6 +385,41 @@ pub fn infer_region_from_filename(name: &str) -> Region {
 /// assert!(check_region_mismatch("PAL_Game.sfc", Region::JAPAN));
 /// ```
 pub fn check_region_mismatch(source_name: &str, header_region: Region) -> bool {
+    check_region_mismatch_with_mode(source_name, header_region, MismatchMode::Strict)
+}
+
+/// Compare the inferred region (via filename) to the region reported by the ROM's header, using
+/// the given [`MismatchMode`].
+///
+/// # Arguments
+///
+/// * `name` - The filename of the ROM as a string slice.
+/// * `header_region` - The region reported by the ROM's header.
+/// * `mode` - How strictly the regions are compared.
+///
+/// # Returns
+///
+/// Returns `true` if there is a mismatch, otherwise returns `false`. If either region is
+/// unknown there is never a mismatch. Otherwise, a mismatch occurs if:
+/// - With [`MismatchMode::Off`], never.
+/// - With [`MismatchMode::Strict`], if the regions share NO common regions.
+/// - With [`MismatchMode::Exact`], if the regions are not identical.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::region::{check_region_mismatch_with_mode, MismatchMode, Region};
+///
+/// // A World ROM labeled as USA only overlaps, so it is only a mismatch in exact mode.
+/// assert!(!check_region_mismatch_with_mode("Game (U).md", Region::WORLD, MismatchMode::Strict));
+/// assert!(check_region_mismatch_with_mode("Game (U).md", Region::WORLD, MismatchMode::Exact));
+/// assert!(!check_region_mismatch_with_mode("Game (E).md", Region::USA, MismatchMode::Off));
+/// ```
+pub fn check_region_mismatch_with_mode(
+    source_name: &str,
+    header_region: Region,
+    mode: MismatchMode,
+) -> bool {
     let inferred_region = infer_region_from_filename(source_name);
 
     // If either region is unknown, do not return a mismatch.
@@ -155,7 +427,11 @@ pub fn check_region_mismatch(source_name: &str, header_region: Region) -> bool {
         return false;
     }
 
-    !inferred_region.intersects(header_region)
+    match mode {
+        MismatchMode::Off => false,
+        MismatchMode::Strict => !inferred_region.intersects(header_region),
+        MismatchMode::Exact => inferred_region != header_region,
+    }
 }
 
 #[cfg(test)]
@@ -221,6 +497,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_infer_region_from_filename_country_groups() {
+        assert_eq!(
+            infer_region_from_filename("game (USA, Europe).md"),
+            Region::USA | Region::EUROPE
+        );
+        assert_eq!(
+            infer_region_from_filename("game (Japan, Korea).sms"),
+            Region::JAPAN | Region::KOREA
+        );
+        assert_eq!(
+            infer_region_from_filename("game (World) (Rev 1).gb"),
+            Region::WORLD
+        );
+        assert_eq!(infer_region_from_filename("game (Asia).md"), Region::ASIA);
+        // Whitespace is trimmed, and unrecognized tokens and groups are ignored.
+        assert_eq!(
+            infer_region_from_filename("game ( brazil ,Atlantis) (En,Pt).md"),
+            Region::USA
+        );
+        // Unclosed groups are ignored.
+        assert_eq!(
+            infer_region_from_filename("game (Korea.sms"),
+            Region::UNKNOWN
+        );
+    }
+
     #[test]
     fn test_infer_region_from_filename_none() {
         assert_eq!(
@@ -336,6 +639,51 @@ mod tests {
         assert!(!check_region_mismatch("Game (W).bin", Region::USA));
     }
 
+    #[test]
+    fn test_mismatch_mode_world_rom_named_usa() {
+        // A World ROM named "(U)" shares the USA region, which is only enough when not exact.
+        assert!(!check_region_mismatch_with_mode(
+            "Game (U).md",
+            Region::WORLD,
+            MismatchMode::Strict
+        ));
+        assert!(check_region_mismatch_with_mode(
+            "Game (U).md",
+            Region::WORLD,
+            MismatchMode::Exact
+        ));
+        assert!(!check_region_mismatch_with_mode(
+            "Game (U).md",
+            Region::WORLD,
+            MismatchMode::Off
+        ));
+
+        // Identical regions never mismatch, and neither do unknown ones.
+        assert!(!check_region_mismatch_with_mode(
+            "Game (U).md",
+            Region::USA,
+            MismatchMode::Exact
+        ));
+        assert!(!check_region_mismatch_with_mode(
+            "Game.md",
+            Region::WORLD,
+            MismatchMode::Exact
+        ));
+    }
+
+    #[test]
+    fn test_mismatch_mode_from_str() -> Result<(), RomAnalyzerError> {
+        assert_eq!("off".parse::<MismatchMode>()?, MismatchMode::Off);
+        assert_eq!("STRICT".parse::<MismatchMode>()?, MismatchMode::Strict);
+        assert_eq!(" exact ".parse::<MismatchMode>()?, MismatchMode::Exact);
+        assert_eq!(MismatchMode::default(), MismatchMode::Strict);
+        assert!(matches!(
+            "loose".parse::<MismatchMode>(),
+            Err(RomAnalyzerError::ParsingError(_))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_multiple_region_filename_display() {
         let filename = "Super Game (U) (J).nes";
@@ -356,4 +704,80 @@ mod tests {
         assert_eq!(Region::WORLD.to_string(), "World");
         assert_eq!((Region::JAPAN | Region::USA).to_string(), "Japan/USA");
     }
+
+    #[test]
+    fn test_region_continent_single() {
+        assert_eq!(Region::USA.continent(), "North America");
+        assert_eq!(Region::EUROPE.continent(), "Europe");
+        assert_eq!(Region::RUSSIA.continent(), "Europe/Asia");
+        for region in [Region::JAPAN, Region::ASIA, Region::CHINA, Region::KOREA] {
+            assert_eq!(region.continent(), "Asia");
+        }
+        assert_eq!(Region::UNKNOWN.continent(), "Unknown");
+    }
+
+    #[test]
+    fn test_region_continent_composite() {
+        assert_eq!((Region::JAPAN | Region::CHINA).continent(), "Asia");
+        assert_eq!((Region::USA | Region::JAPAN).continent(), "Multiple");
+        assert_eq!((Region::EUROPE | Region::RUSSIA).continent(), "Multiple");
+        assert_eq!(Region::WORLD.continent(), "Multiple");
+    }
+
+    #[test]
+    fn test_video_system_display() {
+        assert_eq!(VideoSystem::Ntsc.to_string(), "NTSC");
+        assert_eq!(VideoSystem::Pal.to_string(), "PAL");
+        assert_eq!(VideoSystem::Multi.to_string(), "Multi");
+        assert_eq!(VideoSystem::Unknown.to_string(), "Unknown");
+    }
+
+    #[test]
+    fn test_region_from_str_single_names() -> Result<(), RomAnalyzerError> {
+        assert_eq!("usa".parse::<Region>()?, Region::USA);
+        assert_eq!("Japan".parse::<Region>()?, Region::JAPAN);
+        assert_eq!("EUROPE".parse::<Region>()?, Region::EUROPE);
+        assert_eq!("korea".parse::<Region>()?, Region::KOREA);
+        assert_eq!("world".parse::<Region>()?, Region::WORLD);
+        assert_eq!("unknown".parse::<Region>()?, Region::UNKNOWN);
+        assert_eq!(" jp ".parse::<Region>()?, Region::JAPAN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_from_str_composites() -> Result<(), RomAnalyzerError> {
+        assert_eq!("usa+japan".parse::<Region>()?, Region::USA | Region::JAPAN);
+        assert_eq!(
+            "Europe + Asia + China".parse::<Region>()?,
+            Region::EUROPE | Region::ASIA | Region::CHINA
+        );
+        assert_eq!("usa+world".parse::<Region>()?, Region::WORLD);
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_from_str_round_trips_display() -> Result<(), RomAnalyzerError> {
+        for region in [
+            Region::JAPAN | Region::USA,
+            Region::EUROPE | Region::ASIA,
+            Region::RUSSIA,
+            Region::WORLD,
+            Region::UNKNOWN,
+        ] {
+            assert_eq!(region.to_string().parse::<Region>()?, region);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_from_str_invalid() {
+        for input in ["atlantis", "usa+atlantis", "", "usa+"] {
+            match input.parse::<Region>() {
+                Err(RomAnalyzerError::ParsingError(message)) => {
+                    assert!(message.starts_with("Unknown region name"))
+                }
+                other => panic!("Expected ParsingError for {:?}, got {:?}", input, other),
+            }
+        }
+    }
 }
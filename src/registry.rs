@@ -0,0 +1,300 @@
+//! Provides a registry of console analyzers keyed by [`RomFileType`].
+//!
+//! [`crate::analyze_rom_data`] looks the detected [`RomFileType`] up in a table of built-in
+//! analyzers. An [`AnalyzerRegistry`] lets downstream crates replace a built-in analyzer, or handle
+//! files no console is detected for, without editing that table. Every built-in console is
+//! registered by default.
+
+use std::collections::HashMap;
+
+use crate::console::RomMetadata;
+use crate::console::{
+    atari7800, cdimage, gamecube, gamegear, gb, gba, genesis, intellivision, mastersystem, n64,
+    neogeo, nes, psp, psx, segacd, snes, threedo,
+};
+use crate::error::RomAnalyzerError;
+use crate::{
+    AnalysisOptions, RomAnalysisResult, RomFileType, dispatch_file_type, process_rom_data,
+};
+
+/// A console analyzer, taking the raw ROM data and the name of the ROM file.
+pub type AnalyzerFn = fn(&[u8], &str) -> Result<Box<dyn RomMetadata>, RomAnalyzerError>;
+
+/// A built-in console analyzer, which also takes the [`AnalysisOptions`] of the analysis.
+type BuiltinAnalyzerFn =
+    fn(&[u8], &str, &AnalysisOptions) -> Result<RomAnalysisResult, RomAnalyzerError>;
+
+/// The analyzers of the built-in consoles. [`RomFileType::Unknown`] has none.
+const BUILTIN_ANALYZERS: &[(RomFileType, BuiltinAnalyzerFn)] = &[
+    (RomFileType::Nes, |data, rom_path, options| {
+        nes::analyze_nes_data_with_options(data, rom_path, options.strict)
+            .map(RomAnalysisResult::NES)
+    }),
+    (RomFileType::Snes, analyze_snes),
+    (RomFileType::N64, |data, rom_path, _| {
+        n64::analyze_n64_data(data, rom_path).map(RomAnalysisResult::N64)
+    }),
+    (RomFileType::MasterSystem, |data, rom_path, _| {
+        mastersystem::analyze_mastersystem_data(data, rom_path).map(RomAnalysisResult::MasterSystem)
+    }),
+    (RomFileType::GameGear, |data, rom_path, _| {
+        gamegear::analyze_gamegear_data(data, rom_path).map(RomAnalysisResult::GameGear)
+    }),
+    (RomFileType::GameBoy, |data, rom_path, _| {
+        gb::analyze_gb_data(data, rom_path).map(RomAnalysisResult::GB)
+    }),
+    (RomFileType::GameBoyAdvance, |data, rom_path, _| {
+        gba::analyze_gba_data(data, rom_path).map(RomAnalysisResult::GBA)
+    }),
+    (RomFileType::Genesis, |data, rom_path, options| {
+        genesis::analyze_genesis_data_with_options(data, rom_path, options.strict)
+            .map(RomAnalysisResult::Genesis)
+    }),
+    (RomFileType::SegaCD, |data, rom_path, options| {
+        segacd::analyze_segacd_data_with_options(data, rom_path, options.strict)
+            .map(RomAnalysisResult::SegaCD)
+    }),
+    (RomFileType::NeoGeo, |data, rom_path, _| {
+        neogeo::analyze_neogeo_data(data, rom_path).map(RomAnalysisResult::NeoGeo)
+    }),
+    (RomFileType::Intellivision, |data, rom_path, _| {
+        intellivision::analyze_intellivision_data(data, rom_path)
+            .map(RomAnalysisResult::Intellivision)
+    }),
+    (RomFileType::Atari7800, |data, rom_path, _| {
+        atari7800::analyze_atari7800_data(data, rom_path).map(RomAnalysisResult::Atari7800)
+    }),
+    (RomFileType::CDSystem, analyze_cd_system),
+    (RomFileType::GameCube, |data, rom_path, _| {
+        gamecube::analyze_gamecube_data(data, rom_path).map(RomAnalysisResult::GameCube)
+    }),
+];
+
+/// Returns the built-in analyzer for `file_type`, or `None` for [`RomFileType::Unknown`].
+pub(crate) fn builtin_analyzer(file_type: RomFileType) -> Option<BuiltinAnalyzerFn> {
+    BUILTIN_ANALYZERS
+        .iter()
+        .find(|(builtin_type, _)| *builtin_type == file_type)
+        .map(|(_, analyzer)| *analyzer)
+}
+
+/// Analyzes SNES data, failing in strict mode when no header has a valid checksum.
+fn analyze_snes(
+    data: &[u8],
+    rom_path: &str,
+    options: &AnalysisOptions,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    let analysis = snes::analyze_snes_data_with_options(data, rom_path, options.strict_world)?;
+    if options.strict && !analysis.checksum_valid {
+        return Err(RomAnalyzerError::ChecksumMismatch(
+            "No SNES header with a valid checksum found.".to_string(),
+        ));
+    }
+    Ok(RomAnalysisResult::SNES(analysis))
+}
+
+/// Analyzes data with a CD image extension, which may also hold a cartridge or disc header.
+fn analyze_cd_system(
+    data: &[u8],
+    rom_path: &str,
+    options: &AnalysisOptions,
+) -> Result<RomAnalysisResult, RomAnalyzerError> {
+    // Some cartridge formats (like Sega Genesis) use the .bin extension, which
+    // conflicts with CD image formats. This checks for cartridge headers inside
+    // files that might otherwise be treated as CD images.
+    // Genesis ROMs may also sit behind a 512-byte copier header.
+    const SEGA_HEADER_START: usize = 0x100;
+    const SEGA_CD_SIGNATURE_END: usize = 0x107;
+    const SEGA_CD_MIN_LEN: usize = 0x10C; // To read region code at 0x10B
+
+    if genesis::has_genesis_signature(data) {
+        genesis::analyze_genesis_data_with_options(data, rom_path, options.strict)
+            .map(RomAnalysisResult::Genesis)
+    } else if data.len() >= SEGA_CD_MIN_LEN
+        && data[SEGA_HEADER_START..SEGA_CD_SIGNATURE_END].eq_ignore_ascii_case(b"SEGA CD")
+    {
+        segacd::analyze_segacd_data_with_options(data, rom_path, options.strict)
+            .map(RomAnalysisResult::SegaCD)
+    } else if gamecube::is_gamecube_disc(data) {
+        gamecube::analyze_gamecube_data(data, rom_path).map(RomAnalysisResult::GameCube)
+    } else if threedo::find_volume_header(data).is_some() {
+        threedo::analyze_threedo_data(data, rom_path).map(RomAnalysisResult::ThreeDO)
+    } else if psp::is_psp_image(data) {
+        psp::analyze_psp_data(data, rom_path).map(RomAnalysisResult::PSP)
+    } else if cdimage::is_generic_cd_image(
+        data,
+        psx::find_executable_prefix(data, psx::DEFAULT_SCAN_WINDOW).is_some(),
+    ) {
+        cdimage::analyze_cd_image_data(data, rom_path).map(RomAnalysisResult::CdImage)
+    } else {
+        psx::analyze_psx_data(data, rom_path).map(RomAnalysisResult::PSX)
+    }
+}
+
+/// Maps each [`RomFileType`] to the analyzer used for it.
+#[derive(Debug, Clone)]
+pub struct AnalyzerRegistry {
+    /// The analyzers, keyed by the file type they are dispatched for.
+    analyzers: HashMap<RomFileType, AnalyzerFn>,
+}
+
+impl Default for AnalyzerRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerRegistry {
+    /// Creates a registry with the built-in analyzer registered for every supported console.
+    pub fn new() -> Self {
+        let analyzers = BUILTIN_ANALYZERS
+            .iter()
+            .map(|(file_type, _)| (*file_type, analyze_builtin as AnalyzerFn))
+            .collect();
+        AnalyzerRegistry { analyzers }
+    }
+
+    /// Registers `analyzer` for a file type, replacing any analyzer already registered for it.
+    ///
+    /// An analyzer registered for [`RomFileType::Unknown`] receives the files no console is
+    /// detected for, such as files with an extension the crate doesn't know.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_type` - The file type to dispatch to `analyzer`.
+    /// * `analyzer` - The analyzer to dispatch files of this type to.
+    pub fn register(&mut self, file_type: RomFileType, analyzer: AnalyzerFn) {
+        self.analyzers.insert(file_type, analyzer);
+    }
+
+    /// Returns the analyzer registered for `file_type`, if any.
+    pub fn analyzer(&self, file_type: RomFileType) -> Option<AnalyzerFn> {
+        self.analyzers.get(&file_type).copied()
+    }
+
+    /// Analyzes raw ROM data with the analyzer registered for its [`RomFileType`].
+    ///
+    /// The file type is detected like [`crate::analyze_rom_data`] does: from the extension of
+    /// `rom_path`, or from the content of files without one. Unlike [`crate::analyze_rom_data`],
+    /// this works on data that was already read, so archives have to be extracted by the caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A byte slice (`&[u8]`) containing the raw ROM data.
+    /// * `rom_path` - The name of the ROM file, used for dispatch and region mismatch checks.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is:
+    /// - `Ok`(`Box<dyn RomMetadata>`) containing the analysis results.
+    /// - `Err`([`RomAnalyzerError::UnsupportedFormat`]) if no analyzer is registered for the
+    ///   file type, or the error returned by the analyzer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rom_analyzer::RomFileType;
+    /// use rom_analyzer::console::RomMetadata;
+    /// use rom_analyzer::console::unknown::analyze_unknown_file;
+    /// use rom_analyzer::error::RomAnalyzerError;
+    /// use rom_analyzer::registry::AnalyzerRegistry;
+    ///
+    /// fn analyze_foo(data: &[u8], name: &str) -> Result<Box<dyn RomMetadata>, RomAnalyzerError> {
+    ///     Ok(Box::new(analyze_unknown_file(name, data.len() as u64)))
+    /// }
+    ///
+    /// let mut registry = AnalyzerRegistry::new();
+    /// assert!(registry.analyze(&[0; 16], "game.foo").is_err());
+    /// registry.register(RomFileType::Unknown, analyze_foo);
+    /// let analysis = registry.analyze(&[0; 16], "game.foo").unwrap();
+    /// assert_eq!(analysis.source_name(), "game.foo");
+    /// ```
+    pub fn analyze(
+        &self,
+        data: &[u8],
+        rom_path: &str,
+    ) -> Result<Box<dyn RomMetadata>, RomAnalyzerError> {
+        let file_type = dispatch_file_type(data, rom_path);
+        let analyzer = self.analyzer(file_type).ok_or_else(|| {
+            RomAnalyzerError::UnsupportedFormat(format!(
+                "No analyzer registered for {:?} file: {}",
+                file_type, rom_path
+            ))
+        })?;
+        analyzer(data, rom_path)
+    }
+}
+
+/// The analyzer registered for the built-in consoles, dispatching like [`crate::analyze_rom_data`].
+fn analyze_builtin(data: &[u8], rom_path: &str) -> Result<Box<dyn RomMetadata>, RomAnalyzerError> {
+    process_rom_data(data, rom_path).map(|analysis| Box::new(analysis) as Box<dyn RomMetadata>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::unknown::{UnknownAnalysis, analyze_unknown_file};
+
+    fn analyze_dummy(data: &[u8], name: &str) -> Result<Box<dyn RomMetadata>, RomAnalyzerError> {
+        Ok(Box::new(UnknownAnalysis {
+            region_string: "Dummy".to_string(),
+            ..analyze_unknown_file(name, data.len() as u64)
+        }))
+    }
+
+    #[test]
+    fn test_registry_dispatches_custom_extension() -> Result<(), RomAnalyzerError> {
+        let mut registry = AnalyzerRegistry::new();
+        assert!(registry.analyze(&[0; 16], "game.dmy").is_err());
+
+        registry.register(RomFileType::Unknown, analyze_dummy);
+        let analysis = registry.analyze(&[0; 16], "game.dmy")?;
+        assert_eq!(analysis.region_string(), "Dummy");
+        assert_eq!(analysis.source_name(), "game.dmy");
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_dispatches_builtin() -> Result<(), RomAnalyzerError> {
+        let mut data = b"NES\x1a\x01\x01\x00\x00".to_vec();
+        data.resize(0x6010, 0);
+        let analysis = AnalyzerRegistry::default().analyze(&data, "game (USA).nes")?;
+        assert!(analysis.to_fields().contains(&(
+            "System".to_string(),
+            "Nintendo Entertainment System (NES)".to_string()
+        )));
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_replaces_builtin() -> Result<(), RomAnalyzerError> {
+        let mut registry = AnalyzerRegistry::new();
+        registry.register(RomFileType::Nes, analyze_dummy);
+        let analysis = registry.analyze(&[0; 16], "game.NES")?;
+        assert_eq!(analysis.region_string(), "Dummy");
+        Ok(())
+    }
+
+    #[test]
+    fn test_builtin_analyzers_cover_every_console() {
+        for console in [
+            RomFileType::Nes,
+            RomFileType::Snes,
+            RomFileType::N64,
+            RomFileType::MasterSystem,
+            RomFileType::GameGear,
+            RomFileType::GameBoy,
+            RomFileType::GameBoyAdvance,
+            RomFileType::Genesis,
+            RomFileType::SegaCD,
+            RomFileType::NeoGeo,
+            RomFileType::Intellivision,
+            RomFileType::Atari7800,
+            RomFileType::CDSystem,
+            RomFileType::GameCube,
+        ] {
+            assert!(builtin_analyzer(console).is_some(), "{:?}", console);
+        }
+        assert!(builtin_analyzer(RomFileType::Unknown).is_none());
+    }
+}
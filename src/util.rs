@@ -0,0 +1,123 @@
+//! Provides small helpers shared by the console analyzers.
+//!
+//! [`footer_slice`] is public, for callers parsing footer-based headers of their own.
+
+/// Converts a raw header title field into a clean, printable string.
+///
+/// Title fields are fixed-size and padded with nulls, spaces or 0xFF fill depending on the
+/// mastering tools used. This function trims that padding from both ends, decodes the rest
+/// as UTF-8 (replacing invalid sequences with U+FFFD) and drops any control characters left
+/// embedded in the title.
+///
+/// # Arguments
+///
+/// * `raw` - The raw bytes of the title field.
+///
+/// # Returns
+///
+/// A `String` containing the sanitized title, which may be empty.
+pub(crate) fn sanitize_title(raw: &[u8]) -> String {
+    let is_padding = |b: &u8| matches!(b, 0x00 | b' ' | 0xFF);
+    let start = raw.iter().position(|b| !is_padding(b)).unwrap_or(raw.len());
+    let end = raw
+        .iter()
+        .rposition(|b| !is_padding(b))
+        .map_or(start, |i| i + 1);
+
+    String::from_utf8_lossy(&raw[start..end])
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Returns the `len` bytes starting `from_end` bytes before the end of `data`.
+///
+/// Some handhelds (Virtual Boy, WonderSwan, Neo Geo Pocket) store their header at a fixed
+/// offset from the end of the file rather than from its start. For example, a footer whose last
+/// byte is the last byte of the file is read with `from_end == len`.
+///
+/// # Arguments
+///
+/// * `data` - The raw ROM data.
+/// * `from_end` - How many bytes before the end of `data` the slice starts.
+/// * `len` - The length of the slice, which must not extend past the end of `data`.
+///
+/// # Returns
+///
+/// The footer bytes, or `None` if `data` is shorter than `from_end` or the slice would run past
+/// the end of `data`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::util::footer_slice;
+///
+/// // WonderSwan ROMs end with a 10-byte footer.
+/// let rom = [0u8, 0, 0, 0, 0xEA, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+/// assert_eq!(footer_slice(&rom, 10, 10).map(|footer| footer[0]), Some(0xEA));
+/// assert_eq!(footer_slice(&rom[..8], 10, 10), None);
+/// ```
+pub fn footer_slice(data: &[u8], from_end: usize, len: usize) -> Option<&[u8]> {
+    let start = data.len().checked_sub(from_end)?;
+    data.get(start..start.checked_add(len)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_title_null_and_space_padding() {
+        assert_eq!(sanitize_title(b"GAME TITLE\0\0\0"), "GAME TITLE");
+        assert_eq!(sanitize_title(b"  GAME TITLE    "), "GAME TITLE");
+    }
+
+    #[test]
+    fn test_sanitize_title_ff_padding() {
+        assert_eq!(sanitize_title(b"GAME TITLE\xFF\xFF\xFF\xFF"), "GAME TITLE");
+        assert_eq!(sanitize_title(b"\xFF\xFFGAME\xFF \0"), "GAME");
+    }
+
+    #[test]
+    fn test_sanitize_title_embedded_control_bytes() {
+        assert_eq!(sanitize_title(b"GAME\0TI\x01TLE"), "GAMETITLE");
+        assert_eq!(sanitize_title(b"GAME\x01 \0"), "GAME");
+    }
+
+    #[test]
+    fn test_sanitize_title_invalid_utf8() {
+        assert_eq!(sanitize_title(b"GAME \x80TITLE"), "GAME \u{FFFD}TITLE");
+    }
+
+    #[test]
+    fn test_sanitize_title_all_padding() {
+        assert_eq!(sanitize_title(b""), "");
+        assert_eq!(sanitize_title(b"\0\0\0"), "");
+        assert_eq!(sanitize_title(b"\xFF\xFF  "), "");
+    }
+
+    #[test]
+    fn test_footer_slice() {
+        let data = [0, 1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(footer_slice(&data, 4, 2), Some(&[4, 5][..]));
+        assert_eq!(footer_slice(&data, 3, 3), Some(&[5, 6, 7][..]));
+        // The slice may not extend past the end of the file.
+        assert_eq!(footer_slice(&data, 2, 3), None);
+    }
+
+    #[test]
+    fn test_footer_slice_exact_fit() {
+        let data = [0, 1, 2, 3];
+        assert_eq!(footer_slice(&data, 4, 4), Some(&data[..]));
+        assert_eq!(footer_slice(&data, 0, 0), Some(&[][..]));
+    }
+
+    #[test]
+    fn test_footer_slice_too_small() {
+        assert_eq!(footer_slice(&[0, 1, 2], 4, 4), None);
+        assert_eq!(footer_slice(&[], 1, 1), None);
+        assert_eq!(footer_slice(&[0; 4], 4, usize::MAX), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Provides an XML rendering of analysis results.
+//!
+//! The document layout follows the flat, attribute-based style used by clrmamepro-compatible
+//! ROM managers, with one `<rom>` element per analyzed ROM. The writer is hand-rolled as only
+//! attribute escaping is needed.
+
+use crate::RomAnalysisResult;
+
+/// Escapes a string for use inside a double-quoted XML attribute value.
+///
+/// Whitespace characters other than a plain space are written as character references so they
+/// survive attribute value normalization.
+fn escape_attribute(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push_str(&format!("&#{};", c as u32)),
+            // Other control characters are not allowed in XML 1.0 documents at all.
+            c if c.is_control() => escaped.push('\u{FFFD}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders analysis results as an XML document.
+///
+/// Each result becomes a `<rom>` element carrying `source`, `console` and `region` attributes.
+/// The `title` attribute is added for consoles that store a game title, and `archive` is added
+/// when the ROM was read from an archive.
+///
+/// # Arguments
+///
+/// * `results` - The analysis results to render.
+///
+/// # Returns
+///
+/// A `String` containing the complete XML document.
+///
+/// # Examples
+///
+/// ```rust
+/// use rom_analyzer::xml::to_xml_string;
+///
+/// let xml = to_xml_string(&[]);
+/// assert!(xml.contains("<roms>"));
+/// ```
+pub fn to_xml_string(results: &[RomAnalysisResult]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<roms>\n");
+    for result in results {
+        xml.push_str(&format!(
+            "  <rom source=\"{}\" console=\"{}\" region=\"{}\"",
+            escape_attribute(result.source_name()),
+            escape_attribute(result.console_name()),
+            escape_attribute(result.region())
+        ));
+        if let Some(title) = result.game_title() {
+            xml.push_str(&format!(" title=\"{}\"", escape_attribute(&title)));
+        }
+        if let Some(archive_source) = result.archive_source() {
+            xml.push_str(&format!(
+                " archive=\"{}\"",
+                escape_attribute(archive_source)
+            ));
+        }
+        xml.push_str("/>\n");
+    }
+    xml.push_str("</roms>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::gb::analyze_gb_data;
+    use crate::console::nes::analyze_nes_data;
+    use crate::error::RomAnalyzerError;
+    use quick_xml::Reader;
+    use quick_xml::events::Event;
+    use std::collections::HashMap;
+
+    /// Test helper to parse every `<rom>` element's attributes out of an XML document.
+    fn parse_roms(xml: &str) -> Vec<HashMap<String, String>> {
+        let mut reader = Reader::from_str(xml);
+        let mut roms = Vec::new();
+        loop {
+            match reader.read_event().expect("Produced XML failed to parse") {
+                Event::Empty(element) if element.name().as_ref() == b"rom" => {
+                    let attributes = element
+                        .attributes()
+                        .map(|attribute| {
+                            let attribute = attribute.expect("Invalid attribute");
+                            (
+                                String::from_utf8(attribute.key.as_ref().to_vec()).unwrap(),
+                                attribute.unescape_value().unwrap().into_owned(),
+                            )
+                        })
+                        .collect();
+                    roms.push(attributes);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        roms
+    }
+
+    #[test]
+    fn test_to_xml_string_attributes() -> Result<(), RomAnalyzerError> {
+        let mut nes_data = vec![0; 16];
+        nes_data[0..4].copy_from_slice(b"NES\x1a");
+        let nes = RomAnalysisResult::NES(analyze_nes_data(&nes_data, "Tom & Jerry \"<U>\".nes")?);
+
+        let mut gb_data = vec![0; 0x150];
+        gb_data[0x134..0x13B].copy_from_slice(b"TETRIS'");
+        let mut gb_analysis = analyze_gb_data(&gb_data, "tetris.gb")?;
+        gb_analysis.archive_source = Some("roms.zip".to_string());
+        let gb = RomAnalysisResult::GB(gb_analysis);
+
+        let roms = parse_roms(&to_xml_string(&[nes, gb]));
+        assert_eq!(roms.len(), 2);
+
+        assert_eq!(roms[0]["source"], "Tom & Jerry \"<U>\".nes");
+        assert_eq!(roms[0]["console"], "NES");
+        assert_eq!(roms[0]["region"], "NTSC (USA/Japan)");
+        assert!(!roms[0].contains_key("title"));
+        assert!(!roms[0].contains_key("archive"));
+
+        assert_eq!(roms[1]["source"], "tetris.gb");
+        assert_eq!(roms[1]["console"], "Game Boy");
+        assert_eq!(roms[1]["title"], "TETRIS'");
+        assert_eq!(roms[1]["archive"], "roms.zip");
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_xml_string_empty() {
+        assert_eq!(
+            to_xml_string(&[]),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<roms>\n</roms>\n"
+        );
+    }
+
+    #[test]
+    fn test_escape_attribute() {
+        assert_eq!(
+            escape_attribute("a&b<c>d\"e'f\ng\u{1}"),
+            "a&amp;b&lt;c&gt;d&quot;e&apos;f&#10;g\u{FFFD}"
+        );
+    }
+}